@@ -0,0 +1,98 @@
+//! generate the builtins of the core library from its `#[no_mangle]`
+//! functions, one `<module>_builtins` function per module of `library/core`
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+/// the modules of the core library which export builtins
+const MODULES: [&str; 4] = ["fmt", "io", "string", "sys"];
+
+fn main() {
+  let path_manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+  let path_source = path_manifest.join("../../library/core/src");
+  let path_output = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+  let mut builtins = String::new();
+
+  for module in MODULES {
+    let path_module = path_source.join(format!("{module}.rs"));
+    let source = fs::read_to_string(&path_module).unwrap();
+
+    writeln!(builtins, "pub fn {module}_builtins() -> Vec<Builtin> {{")
+      .unwrap();
+    writeln!(builtins, "  vec![").unwrap();
+
+    for (name, inputs, output) in exported_functions(&source) {
+      let inputs = inputs
+        .iter()
+        .map(|input| format!("{}.into()", zhoo_ty(&name, input)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+      let output = match output {
+        Some(output) => zhoo_ty(&name, &output),
+        None => "Ty::VOID",
+      };
+
+      writeln!(
+        builtins,
+        "    Builtin::new(String::from({name:?}), Proto(vec![{inputs}], {output}.into())),"
+      )
+      .unwrap();
+    }
+
+    writeln!(builtins, "  ]\n}}\n").unwrap();
+
+    println!("cargo:rerun-if-changed={}", path_module.display());
+  }
+
+  fs::write(path_output.join("builtins.rs"), builtins).unwrap();
+
+  println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// the name, the input types and the output type of the functions which
+/// follow a `#[no_mangle]` attribute
+fn exported_functions(
+  source: &str,
+) -> Vec<(String, Vec<String>, Option<String>)> {
+  source
+    .split("#[no_mangle]")
+    .skip(1)
+    .map(|item| {
+      let signature = &item[..item.find('{').expect("a function body")];
+      let signature = &signature[signature.find("fn ").expect("a function")..];
+      let (name, rest) = signature[3..].split_once('(').unwrap();
+      let (inputs, output) = rest.rsplit_once(')').unwrap();
+
+      let inputs = inputs
+        .split(',')
+        .filter_map(|input| input.split_once(':'))
+        .map(|(_, ty)| ty.trim().to_string())
+        .collect();
+
+      let output = output
+        .trim()
+        .strip_prefix("->")
+        .map(|output| output.trim().to_string());
+
+      (name.trim().to_string(), inputs, output)
+    })
+    .collect()
+}
+
+/// the zhoo type of a type of the c abi
+fn zhoo_ty(name: &str, ty: &str) -> &'static str {
+  match ty {
+    "bool" => "Ty::BOOL",
+    "isize" | "i64" => "Ty::INT",
+    "u64" => "Ty::U64",
+    "f64" => "Ty::REAL",
+    "f32" => "Ty::F32",
+    "Char" => "Ty::CHAR",
+    "*const Str" => "Ty::STR",
+    _ => panic!("the type `{ty}` of the builtin `{name}` has no zhoo type"),
+  }
+}
@@ -1,14 +1,28 @@
 use super::checker::mainchecker;
 use super::checker::namechecker;
 use super::checker::typechecker;
+use super::checker::valchecker;
+
+use super::resolver::Resolutions;
 
 use zhoo_ast::ast::Program;
 use zhoo_errors::Result;
 
-pub fn analyze(program: &Program) -> Result<()> {
+/// checks a program, the resolutions it gives are the ones of
+/// [`resolve`]
+pub fn analyze(program: &Program) -> Result<Resolutions> {
   mainchecker::check(program)?;
   namechecker::check(program)?;
-  typechecker::check(program)?;
 
-  Ok(())
+  let resolutions = typechecker::check(program)?;
+
+  valchecker::check(program, &resolutions)?;
+
+  Ok(resolutions)
+}
+
+/// the resolutions of a checked program, the type checker selects the
+/// overload of every call of an overloaded function
+pub fn resolve(program: &Program) -> Result<Resolutions> {
+  typechecker::check(program)
 }
@@ -1,14 +1,29 @@
 mod c;
-mod io;
-mod sys;
 
 pub use c::c_builtins;
-pub use io::io_builtins;
-pub use sys::sys_builtins;
 
-use zhoo_ast::ast::Ty;
+use zhoo_ast::ast::{Ty, TyKind};
 use zhoo_ast::ptr::Fsp;
 
+// `fmt_builtins`, `io_builtins`, `string_builtins` and `sys_builtins` are
+// generated by the build script from the `#[no_mangle]` functions of
+// `library/core`
+include!(concat!(env!("OUT_DIR"), "/builtins.rs"));
+
+/// every builtin known by the analyzer and the codegen
+pub fn builtins() -> Vec<Builtin> {
+  vec![
+    c_builtins(),
+    fmt_builtins(),
+    io_builtins(),
+    string_builtins(),
+    sys_builtins(),
+  ]
+  .into_iter()
+  .flatten()
+  .collect()
+}
+
 pub struct Builtin {
   pub name: String,
   pub proto: Proto,
@@ -21,3 +36,62 @@ impl Builtin {
 }
 
 pub struct Proto(pub Vec<Fsp<Ty>>, pub Fsp<Ty>);
+
+/// a builtin written from any printable value, the type of its input selects
+/// its entry point of the runtime: `print(1)` calls `printi`. a value without
+/// entry point is formatted into a `str` first
+pub struct Overload {
+  pub name: &'static str,
+  pub entries: Vec<(Ty, &'static str)>,
+}
+
+impl Overload {
+  pub const fn new(
+    name: &'static str,
+    entries: Vec<(Ty, &'static str)>,
+  ) -> Self {
+    Self { name, entries }
+  }
+
+  /// the entry point which takes a value of type `ty`
+  pub fn entry(&self, ty: &TyKind) -> Option<&'static str> {
+    self
+      .entries
+      .iter()
+      .find(|(entry_ty, _)| entry_ty.kind == *ty)
+      .map(|(_, entry)| *entry)
+  }
+}
+
+/// the overloaded builtins, their entry points are in `io_builtins`
+pub fn overloads() -> Vec<Overload> {
+  vec![
+    Overload::new(
+      "print",
+      vec![
+        (Ty::STR, "print"),
+        (Ty::INT, "printi"),
+        (Ty::REAL, "printr"),
+        (Ty::BOOL, "printb"),
+        (Ty::CHAR, "printc"),
+      ],
+    ),
+    Overload::new(
+      "println",
+      vec![
+        (Ty::STR, "println"),
+        (Ty::INT, "printiln"),
+        (Ty::REAL, "printrln"),
+        (Ty::BOOL, "printbln"),
+        (Ty::CHAR, "printcln"),
+      ],
+    ),
+  ]
+}
+
+/// the overloaded builtin named `name`
+pub fn overload(name: &str) -> Option<Overload> {
+  overloads()
+    .into_iter()
+    .find(|overload| overload.name == name)
+}
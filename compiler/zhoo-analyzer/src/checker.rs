@@ -1,3 +1,4 @@
 pub(crate) mod mainchecker;
 pub(crate) mod namechecker;
 pub(crate) mod typechecker;
+pub(crate) mod valchecker;
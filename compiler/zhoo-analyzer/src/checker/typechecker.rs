@@ -1,19 +1,59 @@
+use crate::builtins::{builtins, overload, Proto};
 use crate::context::Context;
+use crate::eval;
+use crate::resolver::{self, DefId, DefKind, Resolutions};
 
 use zhoo_ast::ast::{
-  AsTy, BinOp, BinOpKind, Block, Decl, Expr, ExprKind, Fun, Lit, LitKind,
-  PatternKind, Program, Prototype, Stmt, StmtKind, Ty, TyKind, UnOp, UnOpKind,
+  BinOp, BinOpKind, LitKind, Program, Size, Ty, TyKind, UnOp, UnOpKind,
 };
 
 use zhoo_ast::ptr::Fsp;
 use zhoo_errors::{Report, Result, SemanticKind};
+use zhoo_hir::hir::{self, Block, Expr, ExprKind, Ident, Item, ItemKind};
+use zhoo_hir::lower;
 use zhoo_span::span::Span;
 
-pub(crate) fn check(program: &Program) -> Result<()> {
+use fxhash::FxHashMap;
+
+/// checks the types of a program, the resolutions it gives have the
+/// overloads selected by the calls
+pub(crate) fn check(program: &Program) -> Result<Resolutions> {
+  let mut hir = lower::lower(program);
+
+  eval::fold_sizes(program, &mut hir);
+  program.reporter.abort_if_has_error();
+
   let mut context = Context::new(program);
 
-  for stmt in &context.program.stmts {
-    match check_stmt(&mut context, stmt) {
+  context.resolutions = resolver::resolve(&hir, &program.reporter);
+  context.program.reporter.abort_if_has_error();
+
+  let mut builtins = builtins()
+    .into_iter()
+    .map(|builtin| (builtin.name, builtin.proto))
+    .collect::<FxHashMap<_, _>>();
+
+  for (def, decl) in context.resolutions.defs() {
+    if decl.kind == DefKind::Builtin {
+      if let Some(Proto(inputs, output)) = builtins.remove(&decl.name) {
+        context.funs.insert(def, (inputs, output));
+      }
+    }
+  }
+
+  for (x, local) in hir.locals.iter().enumerate() {
+    if let Some(ty) = &local.ty {
+      let def = context.resolutions.local(hir::LocalId(x));
+
+      context.tys.insert(def, ty.to_owned());
+    }
+  }
+
+  // the top-level items are visible from every function
+  declare_items(&mut context, hir.items.iter());
+
+  for item in &hir.items {
+    match check_item(&mut context, item) {
       Ok(_ty) => {}
       Err(report) => context.program.reporter.add_report(report),
     };
@@ -21,134 +61,182 @@ pub(crate) fn check(program: &Program) -> Result<()> {
 
   context.program.reporter.abort_if_has_error();
 
-  Ok(())
+  Ok(context.resolutions)
 }
 
-fn check_stmt(context: &mut Context, stmt: &Stmt) -> Result<Fsp<Ty>> {
-  match &stmt.kind {
-    StmtKind::Val(decl) => check_stmt_decl(context, decl),
-    StmtKind::Fun(fun) => check_stmt_fun(context, fun),
-    _ => unimplemented!(),
+/// gives their type to the items of a scope before checking it
+fn declare_items<'a>(
+  context: &mut Context,
+  items: impl Iterator<Item = &'a Item>,
+) {
+  for item in items {
+    match &item.kind {
+      ItemKind::Ext(ext) => {
+        let def = item_def(context, &ext.name);
+
+        context
+          .funs
+          .insert(def, (ext.inputs.to_owned(), ext.output.to_owned()));
+      }
+      ItemKind::Fun(fun) => {
+        let def = item_def(context, &fun.name);
+
+        let inputs_tys = fun
+          .inputs
+          .iter()
+          .map(|input| local_ty(context, context.resolutions.local(*input)))
+          .collect();
+
+        context
+          .funs
+          .insert(def, (inputs_tys, fun.output.to_owned()));
+      }
+      ItemKind::Val(val) => {
+        let def = item_def(context, &val.name);
+        let ty = val.ty.clone().unwrap_or_else(|| Ty::INFER.into());
+
+        context.tys.insert(def, ty);
+      }
+    }
   }
 }
 
-fn check_stmt_decl(context: &mut Context, decl: &Decl) -> Result<Fsp<Ty>> {
-  let ty = if let Some(ty) = &decl.ty {
-    ty.clone()
-  } else {
-    Ty::INFER.into()
-  };
+fn item_def(context: &Context, name: &Ident) -> DefId {
+  context
+    .resolutions
+    .resolution(name.span)
+    .expect("a resolved item")
+}
 
-  let name = match &decl.pattern.kind {
-    PatternKind::Identifier(identifier) => identifier,
-    _ => panic!("should be an identifier"),
-  };
+fn check_item(context: &mut Context, item: &Item) -> Result<Fsp<Ty>> {
+  match &item.kind {
+    ItemKind::Ext(_) => Ok(make_ty_void(item.span).into()),
+    ItemKind::Fun(fun) => check_item_fun(context, fun, item.span),
+    ItemKind::Val(val) => check_val(context, val, item.span),
+  }
+}
 
-  let Ok(_ty) = context.scope_map.set_decl(name.to_string(), ty) else {
-    return Err(Report::Semantic(SemanticKind::NameClash(name.span, name.to_string())));
-  };
+fn check_val(
+  context: &mut Context,
+  val: &hir::Val,
+  span: Span,
+) -> Result<Fsp<Ty>> {
+  let t1 = check_expr_global(context, &val.name)?;
+  let t2 = check_expr(context, &val.value)?;
 
-  let t1 = check_expr(context, name)?;
-  let t2 = check_expr(context, &decl.value)?;
+  if !coerce(&val.value, &t2, &t1)? {
+    unify_tys(context, &t1, &t2)?;
+  }
 
-  unify_tys(context, &t1, &t2)?;
-  Ok(make_ty_void(decl.span).into())
+  Ok(make_ty_void(span).into())
 }
 
-fn check_stmt_fun(context: &mut Context, fun: &Fun) -> Result<Fsp<Ty>> {
-  match context.scope_map.set_fun(
-    fun.prototype.name.to_string(),
-    (fun.prototype.as_inputs_tys(), fun.prototype.as_ty()),
-  ) {
-    Ok(_fun) => {
-      context.scope_map.enter_scope();
-      check_prototype(context, &fun.prototype)?;
-      check_block(context, &fun.body)?;
-      context.scope_map.exit_scope();
-      Ok(make_ty_void(fun.span).into())
-    }
-    Err(_error) => Err(Report::Semantic(SemanticKind::NameClash(
-      fun.prototype.name.span,
-      fun.prototype.name.to_string(),
-    ))),
-  }
-}
+fn check_item_fun(
+  context: &mut Context,
+  fun: &hir::Fun,
+  span: Span,
+) -> Result<Fsp<Ty>> {
+  // a nested function is not in the loops of the function which contains it
+  let return_ty =
+    std::mem::replace(&mut context.return_ty, fun.output.to_owned());
 
-fn check_prototype(context: &mut Context, prototype: &Prototype) -> Result<()> {
-  for input in &prototype.inputs {
-    if context
-      .scope_map
-      .set_decl(input.pattern.to_string(), input.ty.to_owned())
-      .is_err()
-    {
-      return Err(Report::Semantic(SemanticKind::NameClash(
-        input.span,
-        input.to_string(),
-      )));
-    }
-  }
+  let loop_depth = std::mem::replace(&mut context.loop_depth, 0);
+  let checked = check_block(context, &fun.body);
 
-  context.return_ty = prototype.as_ty();
+  context.return_ty = return_ty;
+  context.loop_depth = loop_depth;
+  checked?;
 
-  Ok(())
+  Ok(make_ty_void(span).into())
 }
 
-fn check_block(context: &mut Context, block: &Block) -> Result<()> {
+/// the type of a block is the type of its last expression
+fn check_block(context: &mut Context, block: &Block) -> Result<Fsp<Ty>> {
+  let mut t1 = make_ty_void(block.span).into();
+
+  declare_items(
+    context,
+    block.exprs.iter().filter_map(|expr| match &expr.kind {
+      ExprKind::Item(item) => Some(item.as_ref()),
+      _ => None,
+    }),
+  );
+
   for expr in &block.exprs {
-    check_expr(context, expr)?;
+    t1 = check_expr(context, expr)?;
   }
 
-  Ok(())
+  Ok(t1)
 }
 
 fn check_expr(context: &mut Context, expr: &Expr) -> Result<Fsp<Ty>> {
   match &expr.kind {
-    ExprKind::Lit(lit) => check_expr_lit(lit),
-    ExprKind::Identifier(identifier) => {
-      check_expr_identifier(context, expr.span, identifier)
+    ExprKind::Lit(lit) => check_expr_lit(lit, expr.span),
+    ExprKind::Interpolation(segments) => {
+      check_expr_interpolation(context, segments, expr.span)
+    }
+    ExprKind::Local(local) => {
+      Ok(local_ty(context, context.resolutions.local(*local)))
     }
+    ExprKind::Global(ident) => check_expr_global(context, ident),
     ExprKind::Call(callee, args) => check_expr_call(context, callee, args),
     ExprKind::UnOp(op, rhs) => check_expr_un_op(context, op, rhs),
     ExprKind::BinOp(lhs, op, rhs) => check_expr_bin_op(context, lhs, op, rhs),
-    ExprKind::Decl(decl) => check_expr_decl(context, decl),
-    ExprKind::Assign(lhs, op, rhs) => check_expr_assign(context, lhs, op, rhs),
-    ExprKind::AssignOp(lhs, op, rhs) => {
-      check_expr_assign_op(context, lhs, op, rhs)
-    }
-    ExprKind::Block(body) => check_expr_block(context, body),
-    ExprKind::Loop(body) => check_expr_loop(context, body),
-    ExprKind::While(condition, body) => {
-      check_expr_while(context, condition, body)
-    }
-    ExprKind::Until(condition, body) => {
-      check_expr_until(context, condition, body)
+    ExprKind::Cast(value, ty) => check_expr_cast(context, value, ty, expr.span),
+    ExprKind::Let(local, value) => {
+      check_expr_let(context, *local, value, expr.span)
     }
+    ExprKind::Assign(lhs, rhs) => check_expr_assign(context, lhs, rhs),
+    ExprKind::Block(body) => check_block(context, body),
+    ExprKind::Loop(body, _) => check_expr_loop(context, body),
     ExprKind::Return(maybe_expr) => {
       check_expr_return(context, maybe_expr, expr.span)
     }
-    ExprKind::Break(maybe_expr) => check_expr_break(context, maybe_expr, expr),
-    ExprKind::Continue => check_expr_continue(context, expr),
-    ExprKind::When(condition, consequence, alternative) => {
-      check_expr_when(context, condition, consequence, alternative)
+    ExprKind::Break(maybe_expr) => {
+      check_expr_break(context, maybe_expr, expr.span)
     }
-    ExprKind::IfElse(condition, consequence, maybe_alternative) => {
-      check_expr_if_else(context, condition, consequence, maybe_alternative)
+    ExprKind::Continue => check_expr_continue(context, expr.span),
+    ExprKind::If(condition, consequence, maybe_alternative) => {
+      check_expr_if(context, condition, consequence, maybe_alternative)
     }
     ExprKind::Array(elements) => check_expr_array(context, expr.span, elements),
-    ExprKind::ArrayAccess(indexed, index) => {
-      check_expr_array_access(context, expr.span, indexed, index)
+    ExprKind::Tuple(elements) => check_expr_tuple(context, expr.span, elements),
+    ExprKind::Index(indexed, index) => {
+      check_expr_index(context, expr.span, indexed, index)
+    }
+    ExprKind::Field(tuple, field) => {
+      check_expr_field(context, expr.span, tuple, field)
     }
-    ExprKind::Stmt(stmt) => check_expr_stmt(context, stmt),
-    _ => unimplemented!(),
+    ExprKind::Lambda(inputs, body) => {
+      check_expr_lambda(context, inputs, body, expr.span, None)
+    }
+    ExprKind::Item(item) => check_expr_item(context, item),
+  }
+}
+
+/// checks a value given to a type, a lambda takes the types of its inputs
+/// from it
+fn check_expr_as(
+  context: &mut Context,
+  expr: &Expr,
+  t1: &Ty,
+) -> Result<Fsp<Ty>> {
+  match &expr.kind {
+    ExprKind::Lambda(inputs, body) => {
+      check_expr_lambda(context, inputs, body, expr.span, Some(t1))
+    }
+    _ => check_expr(context, expr),
   }
 }
 
-fn check_expr_lit(lit: &Lit) -> Result<Fsp<Ty>> {
-  match &lit.kind {
-    LitKind::Bool(_boolean) => check_expr_lit_bool(lit.span),
-    LitKind::Int(_int) => check_expr_lit_int(lit.span),
-    LitKind::Real(_real) => check_expr_lit_real(lit.span),
-    LitKind::Str(_string) => check_expr_lit_str(lit.span),
+fn check_expr_lit(lit: &LitKind, span: Span) -> Result<Fsp<Ty>> {
+  match lit {
+    LitKind::Bool(_boolean) => check_expr_lit_bool(span),
+    LitKind::Int(int, suffix) => check_expr_lit_int(lit, *int, suffix, span),
+    LitKind::Real(_real, None) => check_expr_lit_real(span),
+    LitKind::Real(_, Some(ty)) => Ok(Ty::new(ty.to_owned(), span).into()),
+    LitKind::Char(_char) => check_expr_lit_char(span),
+    LitKind::Str(_string) => check_expr_lit_str(span),
   }
 }
 
@@ -156,31 +244,73 @@ fn check_expr_lit_bool(span: Span) -> Result<Fsp<Ty>> {
   Ok(make_ty_bool(span).into())
 }
 
-fn check_expr_lit_int(span: Span) -> Result<Fsp<Ty>> {
-  Ok(make_ty_int(span).into())
+/// a suffixed integer must fit in its suffix, an unsuffixed integer beyond
+/// `int` is a `u64`
+fn check_expr_lit_int(
+  lit: &LitKind,
+  int: i128,
+  suffix: &Option<TyKind>,
+  span: Span,
+) -> Result<Fsp<Ty>> {
+  fit_lit(lit, span, &Ty::new(LitKind::int_ty(int, suffix), span))
 }
 
 fn check_expr_lit_real(span: Span) -> Result<Fsp<Ty>> {
   Ok(make_ty_real(span).into())
 }
 
+fn check_expr_lit_char(span: Span) -> Result<Fsp<Ty>> {
+  Ok(make_ty_char(span).into())
+}
+
 fn check_expr_lit_str(span: Span) -> Result<Fsp<Ty>> {
   Ok(make_ty_str(span).into())
 }
 
-fn check_expr_identifier(
+/// every segment of an interpolated `str` is written by the formatting of its
+/// type, so its type must be printable
+fn check_expr_interpolation(
   context: &mut Context,
+  segments: &[Expr],
   span: Span,
-  identifier: &str,
 ) -> Result<Fsp<Ty>> {
-  if let Some(ty) = context.scope_map.decl(identifier) {
+  for segment in segments {
+    let ty = check_expr(context, segment)?;
+
+    if !ty.kind.is_printable() {
+      return Err(Report::Semantic(SemanticKind::InvalidInterpolation(
+        segment.span,
+        ty.to_string(),
+      )));
+    }
+  }
+
+  Ok(make_ty_str(span).into())
+}
+
+/// a global is a top-level `val` or a function, a function is a value of
+/// type `Fn`. an overloaded function can only be called
+fn check_expr_global(context: &mut Context, ident: &Ident) -> Result<Fsp<Ty>> {
+  if let Some(overloads) = context.resolutions.overloads(ident.span) {
+    return Err(Report::Semantic(SemanticKind::AmbiguousOverload(
+      ident.span,
+      ident.name.to_string(),
+      candidates(context, overloads),
+    )));
+  }
+
+  let def = item_def(context, ident);
+
+  if let Some(ty) = context.tys.get(&def) {
     Ok(ty.clone())
-  } else if let Some(ty) = context.scope_map.fun(identifier) {
-    Ok(ty.1.clone())
+  } else if let Some((inputs, output)) = context.funs.get(&def) {
+    let kind = TyKind::Fn(inputs.to_owned(), output.to_owned());
+
+    Ok(Ty::new(kind, ident.span).into())
   } else {
     Err(Report::Semantic(SemanticKind::IdentifierNotFound(
-      span,
-      identifier.to_string(),
+      ident.span,
+      ident.name.to_string(),
     )))
   }
 }
@@ -188,19 +318,90 @@ fn check_expr_identifier(
 fn check_expr_call(
   context: &mut Context,
   callee: &Expr,
-  inputs: &[Fsp<Expr>],
+  inputs: &[Expr],
 ) -> Result<Fsp<Ty>> {
-  let (fun_inputs_tys, fun_return_ty) =
-    match context.scope_map.fun(&callee.to_string()) {
-      Some(fun_ty) => fun_ty,
-      None => {
-        return Err(Report::Semantic(SemanticKind::FunctionNotFound(
-          callee.span,
-          callee.to_string(),
-        )));
+  let def = match &callee.kind {
+    ExprKind::Global(ident) => {
+      match context.resolutions.overloads(ident.span) {
+        // the selected overload takes the arguments
+        Some(overloads) => {
+          let def =
+            select_overload(context, ident, overloads.to_vec(), inputs)?;
+
+          return Ok(context.funs[&def].1.clone());
+        }
+        None => item_def(context, ident),
       }
-    };
+    }
+    ExprKind::Local(local) => context.resolutions.local(*local),
+    // a lambda or a call which gives a function
+    _ => {
+      return check_expr_call_fn(context, callee, String::from("fn"), inputs)
+    }
+  };
+
+  let name = context.resolutions.def(def).name.to_string();
+
+  let Some((fun_inputs_tys, fun_return_ty)) = context.funs.get(&def).cloned()
+  else {
+    // a binding which holds a lambda
+    if matches!(local_ty(context, def).kind, TyKind::Fn(..)) {
+      return check_expr_call_fn(context, callee, name, inputs);
+    }
+
+    return Err(Report::Semantic(SemanticKind::FunctionNotFound(
+      callee.span,
+      name,
+    )));
+  };
+
+  check_arity(callee, &name, &fun_inputs_tys, inputs)?;
+
+  if context.resolutions.def(def).kind == DefKind::Builtin
+    && overload(&name).is_some()
+  {
+    return check_expr_call_overload(context, name, &inputs[0], fun_return_ty);
+  }
+
+  for (input, input_ty) in inputs.iter().zip(&fun_inputs_tys) {
+    ensure_expr_ty(context, input, input_ty)?;
+  }
+
+  Ok(fun_return_ty)
+}
+
+/// a call of a value of type `Fn`: a binding which holds a lambda or a call
+/// which gives a function
+fn check_expr_call_fn(
+  context: &mut Context,
+  callee: &Expr,
+  name: String,
+  inputs: &[Expr],
+) -> Result<Fsp<Ty>> {
+  let t1 = check_expr(context, callee)?;
+
+  let TyKind::Fn(fun_inputs_tys, fun_return_ty) = &t1.kind else {
+    return Err(Report::Semantic(SemanticKind::FunctionNotFound(
+      callee.span,
+      name,
+    )));
+  };
+
+  check_arity(callee, &name, fun_inputs_tys, inputs)?;
 
+  for (input, input_ty) in inputs.iter().zip(fun_inputs_tys) {
+    ensure_expr_ty(context, input, input_ty)?;
+  }
+
+  Ok(Ty::new(fun_return_ty.kind.to_owned(), callee.span).into())
+}
+
+fn check_arity(
+  callee: &Expr,
+  name: &str,
+  fun_inputs_tys: &[Fsp<Ty>],
+  inputs: &[Expr],
+) -> Result<()> {
   if inputs.len() != fun_inputs_tys.len() {
     let expected_inputs = fun_inputs_tys
       .iter()
@@ -208,7 +409,7 @@ fn check_expr_call(
       .collect::<Vec<_>>()
       .join(", ");
 
-    let should_be = format!("{}({})", callee, expected_inputs);
+    let should_be = format!("{}({})", name, expected_inputs);
 
     return Err(Report::Semantic(SemanticKind::ArgumentsMismatch(
       callee.span,
@@ -219,15 +420,116 @@ fn check_expr_call(
     )));
   }
 
-  for (x, input) in inputs.iter().enumerate() {
-    if x < fun_inputs_tys.len() {
-      ensure_expr_ty(&mut context.clone(), input, &fun_inputs_tys[x])?;
-    }
+  Ok(())
+}
+
+/// an overloaded builtin takes any printable value, the lowering calls the
+/// entry point of its type or formats it into a `str`
+fn check_expr_call_overload(
+  context: &mut Context,
+  name: String,
+  input: &Expr,
+  return_ty: Fsp<Ty>,
+) -> Result<Fsp<Ty>> {
+  let ty = check_expr(context, input)?;
+
+  if !ty.kind.is_printable() {
+    return Err(Report::Semantic(SemanticKind::NotPrintable(
+      input.span,
+      name,
+      ty.to_string(),
+    )));
+  }
+
+  Ok(return_ty)
+}
+
+/// selects the overload whose inputs take the arguments of a call, an
+/// overload which takes their exact types is preferred to the ones which
+/// convert them
+fn select_overload(
+  context: &mut Context,
+  ident: &Ident,
+  overloads: Vec<DefId>,
+  inputs: &[Expr],
+) -> Result<DefId> {
+  let inputs_tys = inputs
+    .iter()
+    .map(|input| check_expr(context, input))
+    .collect::<Result<Vec<_>>>()?;
+
+  let takes = |def: &DefId, exact: bool| {
+    let (fun_inputs_tys, _) = &context.funs[def];
+
+    fun_inputs_tys.len() == inputs.len()
+      && inputs.iter().zip(&inputs_tys).zip(fun_inputs_tys).all(
+        |((input, t2), t1)| match exact {
+          true => t1.kind == t2.kind,
+          false => matches!(coerce(input, t2, t1), Ok(true)),
+        },
+      )
+  };
+
+  let mut matches = overloads
+    .iter()
+    .filter(|def| takes(def, true))
+    .copied()
+    .collect::<Vec<_>>();
+
+  if matches.is_empty() {
+    matches = overloads
+      .iter()
+      .filter(|def| takes(def, false))
+      .copied()
+      .collect();
   }
 
-  ensure_expr_ty(&mut context.clone(), callee, fun_return_ty)?;
+  match matches.as_slice() {
+    [def] => {
+      context.resolutions.select(ident.span, *def);
 
-  Ok(fun_return_ty.clone())
+      Ok(*def)
+    }
+    [] => Err(Report::Semantic(SemanticKind::NoMatchingOverload(
+      ident.span,
+      ident.name.to_string(),
+      inputs_tys
+        .iter()
+        .map(|ty| format!("`{ty}`"))
+        .collect::<Vec<_>>()
+        .join(", "),
+      candidates(context, &overloads),
+    ))),
+    _ => Err(Report::Semantic(SemanticKind::AmbiguousOverload(
+      ident.span,
+      ident.name.to_string(),
+      candidates(context, &matches),
+    ))),
+  }
+}
+
+/// the spans and the signatures of overloads: `fun add(int, int): int`
+fn candidates(context: &Context, overloads: &[DefId]) -> Vec<(Span, String)> {
+  overloads
+    .iter()
+    .map(|def| {
+      let decl = context.resolutions.def(*def);
+      let (inputs, output) = &context.funs[def];
+
+      let inputs = inputs
+        .iter()
+        .map(|input| input.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+      let signature = match output.kind {
+        TyKind::Void => format!("fun {}({inputs})", decl.name),
+        _ => format!("fun {}({inputs}): {output}", decl.name),
+      };
+
+      (decl.span, signature)
+    })
+    .collect()
 }
 
 fn check_expr_un_op(
@@ -235,6 +537,15 @@ fn check_expr_un_op(
   op: &UnOp,
   rhs: &Expr,
 ) -> Result<Fsp<Ty>> {
+  // a negated suffixed integer fits in its suffix as a negative: `-128s8`
+  if let (UnOpKind::Neg, ExprKind::Lit(LitKind::Int(int, Some(ty)))) =
+    (&op.node, &rhs.kind)
+  {
+    let lit = LitKind::Int(-int, Some(ty.to_owned()));
+
+    return check_expr_lit(&lit, Span::merge(&op.span, &rhs.span));
+  }
+
   let t1 = check_expr(context, rhs)?;
 
   match &op.node {
@@ -247,7 +558,7 @@ fn check_expr_un_op(
         )));
       }
 
-      Ok(make_ty_int(Span::merge(&op.span, &rhs.span)).into())
+      Ok(Ty::new(t1.kind.to_owned(), Span::merge(&op.span, &rhs.span)).into())
     }
     UnOpKind::Not => {
       if !t1.is_boolean() {
@@ -275,11 +586,15 @@ fn check_expr_bin_op(
 
   match &op.node {
     BinOpKind::Lt | BinOpKind::Le | BinOpKind::Gt | BinOpKind::Ge => {
-      if !t1.is_int() || !t2.is_int() {
+      let ty = operands_ty(lhs, &t1, op, rhs, &t2)?;
+
+      // the `str`s are compared by their bytes and the `char`s by their
+      // scalar values
+      if !ty.is_numeric() && ty.kind != TyKind::Str && !ty.kind.is_char() {
         return Err(Report::Semantic(SemanticKind::TypeMismatch(
           op.span,
-          t1.to_string(),
-          t2.to_string(),
+          Ty::INT.to_string(),
+          ty.to_string(),
         )));
       }
 
@@ -296,136 +611,228 @@ fn check_expr_bin_op(
 
       Ok(make_ty_bool(Span::merge(&lhs.span, &rhs.span)).into())
     }
-    BinOpKind::Eq | BinOpKind::Ne => {
-      if t1.kind != t2.kind {
+    BinOpKind::Concat => {
+      if let Some(ty) = [&t1, &t2].into_iter().find(|ty| ty.kind != TyKind::Str)
+      {
         return Err(Report::Semantic(SemanticKind::TypeMismatch(
           op.span,
-          t1.to_string(),
-          t2.to_string(),
+          Ty::STR.to_string(),
+          ty.to_string(),
         )));
       }
 
+      Ok(make_ty_str(Span::merge(&lhs.span, &rhs.span)).into())
+    }
+    BinOpKind::Eq | BinOpKind::Ne => {
+      operands_ty(lhs, &t1, op, rhs, &t2)?;
+
       Ok(make_ty_bool(Span::merge(&lhs.span, &rhs.span)).into())
     }
     _ => {
-      if t1.kind != t2.kind {
+      let ty = operands_ty(lhs, &t1, op, rhs, &t2)?;
+
+      let is_valid = match &op.node {
+        BinOpKind::Add
+        | BinOpKind::Sub
+        | BinOpKind::Mul
+        | BinOpKind::Div
+        | BinOpKind::Rem => ty.is_numeric(),
+        BinOpKind::Shl | BinOpKind::Shr => ty.is_integer(),
+        BinOpKind::BitAnd | BinOpKind::BitOr | BinOpKind::BitXor => {
+          ty.is_integer() || ty.is_boolean()
+        }
+        _ => true,
+      };
+
+      if !is_valid {
         return Err(Report::Semantic(SemanticKind::TypeMismatch(
           op.span,
-          t1.to_string(),
-          t2.to_string(),
+          Ty::INT.to_string(),
+          ty.to_string(),
         )));
       }
 
-      Ok(make_ty_int(Span::merge(&lhs.span, &rhs.span)).into())
+      // an operation gives a value of the type of its operands
+      Ok(Ty::new(ty.kind.to_owned(), Span::merge(&lhs.span, &rhs.span)).into())
     }
   }
 }
 
-fn check_expr_assign(
+fn check_expr_cast(
   context: &mut Context,
-  lhs: &Expr,
-  _op: &BinOp,
-  rhs: &Expr,
+  value: &Expr,
+  ty: &Ty,
+  span: Span,
 ) -> Result<Fsp<Ty>> {
-  let t1 = check_expr(context, lhs)?;
+  let t1 = check_expr(context, value)?;
 
-  ensure_expr_ty(context, rhs, &t1)?;
-  Ok(make_ty_void(Span::merge(&lhs.span, &rhs.span)).into())
+  if !t1.kind.casts_to(&ty.kind) {
+    return Err(Report::Semantic(SemanticKind::InvalidCast(
+      span,
+      t1.to_string(),
+      ty.to_string(),
+    )));
+  }
+
+  Ok(Ty::new(ty.kind.to_owned(), span).into())
 }
 
-fn check_expr_assign_op(
-  context: &mut Context,
+/// the type of the operands of a binary operation, an unsuffixed number takes
+/// the type of the other operand, an integer mixed with a real is converted to
+/// the real and the smaller operand is widened
+fn operands_ty(
   lhs: &Expr,
+  t1: &Ty,
   op: &BinOp,
   rhs: &Expr,
+  t2: &Ty,
 ) -> Result<Fsp<Ty>> {
-  let t1 = check_expr(context, lhs)?;
-  let t2 = check_expr(context, rhs)?;
+  if t1.kind == t2.kind {
+    return Ok(t1.into());
+  }
 
-  if !op.node.is_assign_op() {
-    return Err(Report::Semantic(SemanticKind::TypeMismatch(
+  if let Some(lit) = rhs.number_lit().filter(|lit| takes_ty(lit, t1)) {
+    return fit_lit(&lit, rhs.span, t1);
+  }
+
+  if let Some(lit) = lhs.number_lit().filter(|lit| takes_ty(lit, t2)) {
+    return fit_lit(&lit, lhs.span, t2);
+  }
+
+  if t1.is_numeric() && t2.is_numeric() {
+    match (t1.kind.is_float(), t2.kind.is_float()) {
+      (true, false) => return Ok(t1.into()),
+      (false, true) => return Ok(t2.into()),
+      _ => {}
+    }
+  }
+
+  if t2.kind.widens_to(&t1.kind) {
+    Ok(t1.into())
+  } else if t1.kind.widens_to(&t2.kind) {
+    Ok(t2.into())
+  } else {
+    Err(Report::Semantic(SemanticKind::TypeMismatch(
       op.span,
       t1.to_string(),
       t2.to_string(),
-    )));
+    )))
   }
-
-  expect_equality(context, &t1, &t2);
-  Ok(make_ty_void(Span::merge(&lhs.span, &rhs.span)).into())
 }
 
-fn check_expr_return(
-  context: &mut Context,
-  maybe_expr: &Option<Fsp<Expr>>,
-  return_span: Span,
-) -> Result<Fsp<Ty>> {
-  if let Some(expr) = maybe_expr {
-    let t1 = check_expr(context, expr)?;
-
-    expect_equality(context, &t1, &context.return_ty.clone());
+/// checks a value given to a type: an unsuffixed number takes the type when
+/// it fits in it and a smaller number is widened
+fn coerce(expr: &Expr, t2: &Ty, t1: &Ty) -> Result<bool> {
+  if t1.kind == t2.kind {
+    return Ok(true);
+  }
 
-    return Ok(t1);
-  };
+  if let Some(lit) = expr.number_lit().filter(|lit| takes_ty(lit, t1)) {
+    return fit_lit(&lit, expr.span, t1).map(|_| true);
+  }
 
-  Ok(make_ty_void(return_span).into())
+  Ok(t2.kind.widens_to(&t1.kind))
 }
 
-fn check_expr_block(context: &mut Context, body: &Block) -> Result<Fsp<Ty>> {
-  let mut t1 = make_ty_void(body.span).into();
-
-  for expr in &body.exprs {
-    t1 = check_expr(context, expr)?;
+/// an integer literal takes an integer type and a real literal a real type
+fn takes_ty(lit: &LitKind, ty: &Ty) -> bool {
+  match lit {
+    LitKind::Int(..) => ty.is_integer(),
+    LitKind::Real(..) => ty.kind.is_float(),
+    _ => false,
   }
-
-  Ok(t1)
 }
 
-fn check_expr_loop(context: &mut Context, body: &Block) -> Result<Fsp<Ty>> {
-  context.loop_depth += 1;
-  check_block(context, body)?;
-  context.loop_depth -= 1;
+fn fit_lit(lit: &LitKind, span: Span, ty: &Ty) -> Result<Fsp<Ty>> {
+  if !lit.fits(&ty.kind) {
+    return Err(Report::Semantic(SemanticKind::LiteralOutOfRange(
+      span,
+      lit.to_string(),
+      ty.to_string(),
+    )));
+  }
 
-  Ok(make_ty_void(body.span).into())
+  Ok(Ty::new(ty.kind.to_owned(), span).into())
 }
 
-fn check_expr_while(
+/// the type of a binding without type is the type of its value
+fn check_expr_let(
   context: &mut Context,
-  condition: &Expr,
-  body: &Block,
+  local: hir::LocalId,
+  value: &Expr,
+  span: Span,
 ) -> Result<Fsp<Ty>> {
-  check_expr_while_or_until(context, condition, body)
+  let def = context.resolutions.local(local);
+
+  match context.tys.get(&def).cloned() {
+    Some(t1) => {
+      let t2 = check_expr_as(context, value, &t1)?;
+
+      // the mismatch is reported on the value, not on its declaration
+      if !coerce(value, &t2, &t1)? {
+        unify_tys(context, &t1, &Ty::new(t2.kind.to_owned(), value.span))?;
+      }
+    }
+    None => {
+      let t2 = check_expr(context, value)?;
+
+      context.tys.insert(def, t2);
+    }
+  }
+
+  Ok(make_ty_void(span).into())
 }
 
-fn check_expr_until(
+fn check_expr_assign(
   context: &mut Context,
-  condition: &Expr,
-  body: &Block,
+  lhs: &Expr,
+  rhs: &Expr,
 ) -> Result<Fsp<Ty>> {
-  check_expr_while_or_until(context, condition, body)
+  let t1 = check_expr(context, lhs)?;
+
+  ensure_expr_ty(context, rhs, &t1)?;
+  Ok(make_ty_void(Span::merge(&lhs.span, &rhs.span)).into())
 }
 
-fn check_expr_while_or_until(
+fn check_expr_return(
   context: &mut Context,
-  condition: &Expr,
-  body: &Block,
+  maybe_expr: &Option<Box<Expr>>,
+  return_span: Span,
 ) -> Result<Fsp<Ty>> {
-  ensure_expr_ty(context, condition, &make_ty_bool(condition.span))?;
+  if let Some(expr) = maybe_expr {
+    let t1 = check_expr(context, expr)?;
+    let return_ty = context.return_ty.clone();
+
+    if !coerce(expr, &t1, &return_ty)? {
+      expect_equality(context, &return_ty, &t1);
+    }
+
+    return Ok(t1);
+  };
+
+  Ok(make_ty_void(return_span).into())
+}
+
+fn check_expr_loop(context: &mut Context, body: &Block) -> Result<Fsp<Ty>> {
   context.loop_depth += 1;
-  check_block(context, body)?;
+
+  let checked = check_block(context, body);
+
   context.loop_depth -= 1;
+  checked?;
 
   Ok(make_ty_void(body.span).into())
 }
 
 fn check_expr_break(
   context: &mut Context,
-  maybe_expr: &Option<Fsp<Expr>>,
-  origin: &Expr,
+  maybe_expr: &Option<Box<Expr>>,
+  span: Span,
 ) -> Result<Fsp<Ty>> {
   if context.loop_depth == 0 {
     return Err(Report::Semantic(SemanticKind::OutOfLoop(
-      origin.span,
-      origin.to_string(),
+      span,
+      String::from("break"),
     )));
   }
 
@@ -437,48 +844,28 @@ fn check_expr_break(
     return Ok(t1);
   }
 
-  Ok(make_ty_void(origin.span).into())
+  Ok(make_ty_void(span).into())
 }
 
-fn check_expr_continue(
-  context: &mut Context,
-  origin: &Expr,
-) -> Result<Fsp<Ty>> {
+fn check_expr_continue(context: &mut Context, span: Span) -> Result<Fsp<Ty>> {
   if context.loop_depth == 0 {
     return Err(Report::Semantic(SemanticKind::OutOfLoop(
-      origin.span,
-      origin.to_string(),
+      span,
+      String::from("continue"),
     )));
   }
 
-  Ok(make_ty_void(origin.span).into())
+  Ok(make_ty_void(span).into())
 }
 
-fn check_expr_when(
+/// an `if` has a value when it has an alternative
+fn check_expr_if(
   context: &mut Context,
   condition: &Expr,
   consequence: &Expr,
-  alternative: &Expr,
+  maybe_alternative: &Option<Box<Expr>>,
 ) -> Result<Fsp<Ty>> {
   let t1 = check_expr(context, condition)?;
-  let t2 = check_expr(context, consequence)?;
-  let t3 = check_expr(context, alternative)?;
-  let boolean = make_ty_bool(condition.span);
-
-  expect_equality(context, &t1, &boolean);
-  unify_tys(context, &t2, &t3)
-}
-
-fn check_expr_if_else(
-  context: &mut Context,
-  condition: &Expr,
-  consequence: &Expr,
-  maybe_alternative: &Option<Fsp<Expr>>,
-) -> Result<Fsp<Ty>> {
-  let t1 = check_expr(context, condition)?;
-  let t2 = check_expr(context, consequence)?;
-  let Some(alternative) = maybe_alternative else { return Ok(t2); };
-  let t3 = check_expr(context, alternative)?;
 
   if !t1.is_boolean() {
     let boolean = make_ty_bool(condition.span);
@@ -490,6 +877,14 @@ fn check_expr_if_else(
     )));
   }
 
+  let t2 = check_expr(context, consequence)?;
+
+  let Some(alternative) = maybe_alternative else {
+    return Ok(make_ty_void(consequence.span).into());
+  };
+
+  let t3 = check_expr(context, alternative)?;
+
   expect_equality(context, &t2, &t3);
   Ok(t2)
 }
@@ -497,12 +892,12 @@ fn check_expr_if_else(
 fn check_expr_array(
   context: &mut Context,
   span: Span,
-  elements: &[Fsp<Expr>],
+  elements: &[Expr],
 ) -> Result<Fsp<Ty>> {
   let mut element_tys = elements
     .iter()
-    .map(|element| check_expr(context, element).unwrap())
-    .collect::<Vec<Fsp<Ty>>>();
+    .map(|element| check_expr(context, element))
+    .collect::<Result<Vec<Fsp<Ty>>>>()?;
 
   let first_ty = if let Some(last_ty) = element_tys.pop() {
     last_ty
@@ -514,16 +909,116 @@ fn check_expr_array(
     expect_equality(context, &first_ty, &ty);
   }
 
-  Ok(make_ty_array(first_ty, Some(elements.len() as i64), span).into())
+  let size = Size::Int(elements.len() as i64);
+
+  Ok(make_ty_array(first_ty, Some(size), span).into())
+}
+
+fn check_expr_tuple(
+  context: &mut Context,
+  span: Span,
+  elements: &[Expr],
+) -> Result<Fsp<Ty>> {
+  let element_tys = elements
+    .iter()
+    .map(|element| check_expr(context, element))
+    .collect::<Result<Vec<_>>>()?;
+
+  Ok(make_ty_tuple(element_tys, span).into())
+}
+
+/// the field of a tuple is an integer literal: `pair.0`
+fn check_expr_field(
+  context: &mut Context,
+  span: Span,
+  tuple: &Expr,
+  field: &Expr,
+) -> Result<Fsp<Ty>> {
+  let t1 = check_expr(context, tuple)?;
+
+  let index = match &field.kind {
+    ExprKind::Lit(LitKind::Int(int, None)) => usize::try_from(*int).ok(),
+    _ => None,
+  };
+
+  match (&t1.kind, index) {
+    (TyKind::Tuple(elements), Some(index)) if index < elements.len() => {
+      Ok(Ty::new(elements[index].kind.to_owned(), span).into())
+    }
+    _ => Err(Report::Semantic(SemanticKind::FieldNotFound(
+      field.span,
+      field_name(field),
+      t1.to_string(),
+    ))),
+  }
+}
+
+fn field_name(field: &Expr) -> String {
+  match &field.kind {
+    ExprKind::Lit(lit) => lit.to_string(),
+    _ => String::from("_"),
+  }
+}
+
+/// a lambda takes the types of its inputs from the `Fn` it's given to, a
+/// lambda without inputs gives the type of its body
+fn check_expr_lambda(
+  context: &mut Context,
+  inputs: &[hir::LocalId],
+  body: &Expr,
+  span: Span,
+  expected: Option<&Ty>,
+) -> Result<Fsp<Ty>> {
+  let (inputs_tys, output_ty) = match expected.map(|ty| &ty.kind) {
+    Some(TyKind::Fn(inputs_tys, output_ty))
+      if inputs_tys.len() == inputs.len() =>
+    {
+      (inputs_tys.to_owned(), Some(output_ty.to_owned()))
+    }
+    _ if inputs.is_empty() => (vec![], None),
+    // the mismatch is reported by the binding or by the call
+    Some(_) => {
+      let inputs_tys = vec![Ty::INFER.into(); inputs.len()];
+
+      return Ok(make_ty_fn(inputs_tys, Ty::INFER.into(), span).into());
+    }
+    None => return Err(Report::Semantic(SemanticKind::UntypedLambda(span))),
+  };
+
+  for (input, input_ty) in inputs.iter().zip(&inputs_tys) {
+    let def = context.resolutions.local(*input);
+
+    context.tys.insert(def, input_ty.to_owned());
+  }
+
+  // a lambda is not in the loops of the function which contains it
+  let return_ty = std::mem::replace(
+    &mut context.return_ty,
+    output_ty.to_owned().unwrap_or_else(|| Ty::INFER.into()),
+  );
+
+  let loop_depth = std::mem::replace(&mut context.loop_depth, 0);
+
+  let checked = match &output_ty {
+    Some(output_ty) => {
+      ensure_expr_ty(context, body, output_ty).map(|_| output_ty.to_owned())
+    }
+    None => check_expr(context, body),
+  };
+
+  context.return_ty = return_ty;
+  context.loop_depth = loop_depth;
+
+  Ok(make_ty_fn(inputs_tys, checked?, span).into())
 }
 
-fn check_expr_array_access(
+fn check_expr_index(
   context: &mut Context,
-  _span: Span,
+  span: Span,
   indexed: &Expr,
   index: &Expr,
 ) -> Result<Fsp<Ty>> {
-  let _indexed = check_expr(context, indexed)?;
+  let indexed = check_expr(context, indexed)?;
   let index = check_expr(context, index)?;
 
   if index.kind != Ty::INT.kind {
@@ -533,42 +1028,42 @@ fn check_expr_array_access(
     )));
   }
 
-  Ok(index)
+  match &indexed.kind {
+    // a `str` is indexed by a char or sliced by a range of chars
+    TyKind::Str => Ok(make_ty_str(span).into()),
+    TyKind::Array(element, _) => {
+      Ok(Ty::new(element.kind.to_owned(), span).into())
+    }
+    _ => Err(Report::Semantic(SemanticKind::TypeMismatch(
+      indexed.span,
+      String::from("array"),
+      indexed.to_string(),
+    ))),
+  }
 }
 
-fn check_expr_stmt(context: &mut Context, stmt: &Stmt) -> Result<Fsp<Ty>> {
-  check_stmt(context, stmt)?;
-  Ok(make_ty_void(stmt.span).into())
+/// a `val` inside of a block is a binding, so a nested item is a function or
+/// an extern
+fn check_expr_item(context: &mut Context, item: &Item) -> Result<Fsp<Ty>> {
+  check_item(context, item)?;
+  Ok(make_ty_void(item.span).into())
 }
 
-fn check_expr_decl(context: &mut Context, decl: &Decl) -> Result<Fsp<Ty>> {
-  let ty = if let Some(ty) = &decl.ty {
-    ty.clone()
-  } else {
-    Ty::INFER.into()
-  };
-
-  let name = match &decl.pattern.kind {
-    PatternKind::Identifier(identifier) => identifier,
-    _ => panic!("should be an identifier"),
-  };
-
-  let variable_shadowed = context.scope_map.remove_decl(&name.to_string());
-  let _ = context.scope_map.set_decl(name.to_string(), ty);
-
-  let t1 = check_expr(context, name)?;
-  let t2 = check_expr(context, &decl.value)?;
-
-  if let Some(variable) = variable_shadowed {
-    let _ = context.scope_map.set_decl(name.to_string(), variable);
-  }
-
-  unify_tys(context, &t1, &t2)?;
-  Ok(make_ty_void(decl.span).into())
+/// the type of a binding, an inferred binding is checked before its uses
+fn local_ty(context: &Context, def: DefId) -> Fsp<Ty> {
+  context
+    .tys
+    .get(&def)
+    .cloned()
+    .unwrap_or_else(|| Ty::INFER.into())
 }
 
 fn ensure_expr_ty(context: &mut Context, expr: &Expr, t1: &Ty) -> Result<bool> {
-  let t2 = check_expr(context, expr)?;
+  let t2 = check_expr_as(context, expr, t1)?;
+
+  if coerce(expr, &t2, t1)? {
+    return Ok(true);
+  }
 
   Ok(expect_equality(context, t1, &t2))
 }
@@ -611,11 +1106,6 @@ const fn make_ty_bool(span: Span) -> Ty {
   Ty::new(TyKind::Bool, span)
 }
 
-#[inline]
-const fn make_ty_int(span: Span) -> Ty {
-  Ty::new(TyKind::Int, span)
-}
-
 #[inline]
 const fn make_ty_real(span: Span) -> Ty {
   Ty::new(TyKind::Real, span)
@@ -627,6 +1117,20 @@ const fn make_ty_str(span: Span) -> Ty {
 }
 
 #[inline]
-const fn make_ty_array(ty: Fsp<Ty>, size: Option<i64>, span: Span) -> Ty {
+const fn make_ty_char(span: Span) -> Ty {
+  Ty::new(TyKind::Char, span)
+}
+
+#[inline]
+const fn make_ty_fn(inputs: Vec<Fsp<Ty>>, output: Fsp<Ty>, span: Span) -> Ty {
+  Ty::new(TyKind::Fn(inputs, output), span)
+}
+
+#[inline]
+const fn make_ty_array(ty: Fsp<Ty>, size: Option<Size>, span: Span) -> Ty {
   Ty::new(TyKind::Array(ty, size), span)
 }
+
+const fn make_ty_tuple(tys: Vec<Fsp<Ty>>, span: Span) -> Ty {
+  Ty::new(TyKind::Tuple(tys), span)
+}
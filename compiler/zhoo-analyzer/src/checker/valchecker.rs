@@ -0,0 +1,18 @@
+use crate::constant;
+use crate::resolver::Resolutions;
+
+use zhoo_ast::ast::Program;
+use zhoo_errors::Result;
+
+pub(crate) fn check(
+  program: &Program,
+  resolutions: &Resolutions,
+) -> Result<()> {
+  if let Err(report) = constant::eval_vals(program, resolutions) {
+    program.reporter.add_report(report);
+  }
+
+  program.reporter.abort_if_has_error();
+
+  Ok(())
+}
@@ -0,0 +1,112 @@
+//! the top-level `val`s are evaluated at compile time, a `val` can use the
+//! other `val`s of the program in any order as long as they do not form a cycle
+
+use super::eval::Evaluator;
+use super::num::Num;
+use super::resolver::Resolutions;
+
+use zhoo_ast::ast::{LitKind, Program, StmtKind, TyKind};
+use zhoo_errors::Result;
+
+use std::fmt;
+
+/// the value of a `val` known at compile time, a number has its type
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constant {
+  Bool(bool),
+  /// an integer is wrapped to the bits of its type
+  Int(i64, Num),
+  /// an `f32` is rounded to its precision
+  Real(f64, Num),
+  Char(char),
+  Str(String),
+}
+
+impl Constant {
+  /// the constant of a literal, an unsuffixed number is an `int` or a `real`
+  pub fn lit(lit: &LitKind) -> Self {
+    match lit {
+      LitKind::Bool(boolean) => Self::Bool(*boolean),
+      LitKind::Int(int, suffix) => {
+        let num = Num::from_ty(&LitKind::int_ty(*int, suffix)).unwrap();
+
+        Self::Int(num.wrap(*int as i64), num)
+      }
+      LitKind::Real(real, suffix) => {
+        let num = suffix.as_ref().and_then(Num::from_ty).unwrap_or(Num::Real);
+
+        Self::Real(num.round(*real), num)
+      }
+      LitKind::Char(char) => Self::Char(*char),
+      LitKind::Str(string) => Self::Str(string.to_string()),
+    }
+  }
+
+  /// the type of a number
+  pub fn num(&self) -> Option<Num> {
+    match self {
+      Self::Int(_, num) | Self::Real(_, num) => Some(*num),
+      _ => None,
+    }
+  }
+
+  /// converts a number to a type it's given to, like `as` does: an integer
+  /// is wrapped to its new type and a real is truncated toward zero then
+  /// wrapped. the other constants are left as they are
+  pub fn convert(self, num: Num) -> Self {
+    match self {
+      Self::Int(int, from) if num.is_float() => {
+        Self::Real(num.round(from.to_real(int)), num)
+      }
+      Self::Int(int, _) => Self::Int(num.wrap(int), num),
+      Self::Real(real, _) if num.is_float() => Self::Real(num.round(real), num),
+      Self::Real(real, _) if num.is_unsigned() => {
+        Self::Int(num.wrap(real as u64 as i64), num)
+      }
+      Self::Real(real, _) => Self::Int(num.wrap(real as i64), num),
+      constant => constant,
+    }
+  }
+
+  /// converts a number to the type of the binding it's given to, the type
+  /// checker only lets a literal which fits in the type and a widening through
+  pub fn coerce(self, ty: &TyKind) -> Self {
+    match Num::from_ty(ty) {
+      Some(num) => self.convert(num),
+      None => self,
+    }
+  }
+}
+
+/// a constant is written like the core library writes its value
+impl fmt::Display for Constant {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Bool(boolean) => write!(f, "{boolean}"),
+      Self::Int(int, Num::U64) => write!(f, "{}", *int as u64),
+      Self::Int(int, _) => write!(f, "{int}"),
+      // an `f32` is written with its own precision, like `fmt_f32`
+      Self::Real(real, Num::F32) => write!(f, "{}", *real as f32),
+      Self::Real(real, _) => write!(f, "{real}"),
+      Self::Char(char) => write!(f, "{char}"),
+      Self::Str(string) => write!(f, "{string}"),
+    }
+  }
+}
+
+/// evaluates the top-level `val`s, a `val` comes after the `val`s it uses. the
+/// resolutions give the overloads called by the `const fun`s
+pub fn eval_vals(
+  program: &Program,
+  resolutions: &Resolutions,
+) -> Result<Vec<(String, Constant)>> {
+  let mut evaluator = Evaluator::new(program, resolutions);
+
+  for stmt in &program.stmts {
+    if let StmtKind::Val(decl) = &stmt.kind {
+      evaluator.eval_val(&decl.pattern.to_string(), decl.pattern.span)?;
+    }
+  }
+
+  Ok(evaluator.vals)
+}
@@ -1,31 +1,30 @@
-use super::builtins::{c_builtins, io_builtins, sys_builtins};
-use super::scope::ScopeMap;
+use super::resolver::{DefId, Resolutions};
 
 use zhoo_ast::ast::{Program, Ty};
 use zhoo_ast::ptr::Fsp;
 
+use fxhash::FxHashMap;
+
 #[derive(Clone, Debug)]
 pub(crate) struct Context<'a> {
   pub program: &'a Program,
-  pub scope_map: ScopeMap,
+  pub resolutions: Resolutions,
+  /// the types of the `val`s and of the bindings, an inferred binding gets
+  /// its type when its declaration is checked
+  pub tys: FxHashMap<DefId, Fsp<Ty>>,
+  /// the types of the inputs and of the output of the functions
+  pub funs: FxHashMap<DefId, (Vec<Fsp<Ty>>, Fsp<Ty>)>,
   pub return_ty: Fsp<Ty>,
   pub loop_depth: i32,
 }
 
 impl<'a> Context<'a> {
   pub fn new(program: &'a Program) -> Self {
-    let builtins = vec![c_builtins(), io_builtins(), sys_builtins()];
-    let mut scope_map = ScopeMap::default();
-
-    for builtin in builtins.into_iter().flatten() {
-      scope_map
-        .set_fun(builtin.name, (builtin.proto.0, builtin.proto.1))
-        .expect("declare builtin");
-    }
-
     Self {
       program,
-      scope_map,
+      resolutions: Resolutions::default(),
+      tys: FxHashMap::default(),
+      funs: FxHashMap::default(),
       return_ty: Ty::VOID.into(),
       loop_depth: 0,
     }
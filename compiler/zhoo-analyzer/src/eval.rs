@@ -0,0 +1,508 @@
+//! the compile-time evaluation engine, an interpreter of the pure expressions
+//! and of the calls to the `const fun`s used by the top-level `val`s and by
+//! the sizes of the array types
+
+use super::constant::Constant;
+use super::flow::{self, Evaluate, Flow, Interrupt};
+use super::ops::{self, OpError};
+use super::resolver::Resolutions;
+
+use zhoo_ast::ast::{
+  AsTy, BinOp, Block, Decl, Expr, ExprKind, Fun, Program, Size, StmtKind, Ty,
+  TyKind,
+};
+
+use zhoo_ast::ptr::Fsp;
+use zhoo_errors::{Report, SemanticKind};
+use zhoo_hir::hir;
+use zhoo_span::span::Span;
+
+use fxhash::FxHashMap;
+
+/// the maximum number of expressions evaluated for a `val`
+const STEPS_LIMIT: usize = 1_000_000;
+
+/// the maximum depth of the calls of the `const fun`s
+const CALLS_LIMIT: usize = 256;
+
+/// the value of an expression, `None` for the expressions without value
+type Value = Option<Constant>;
+
+type Eval = Flow<Value>;
+
+enum State {
+  Evaluating,
+  Evaluated(Constant),
+}
+
+/// the local variables of a `const fun` call, one map per block
+type Frame = Vec<FxHashMap<String, Constant>>;
+
+pub(crate) struct Evaluator<'a> {
+  decls: FxHashMap<String, &'a Decl>,
+  /// the top-level functions by the span of their name
+  funs: FxHashMap<Span, &'a Fun>,
+  resolutions: &'a Resolutions,
+  states: FxHashMap<String, State>,
+  frames: Vec<Frame>,
+  /// the names of the constants being evaluated, the last one is the current
+  names: Vec<String>,
+  steps: usize,
+  /// the `val`s in the order of their evaluation
+  pub vals: Vec<(String, Constant)>,
+}
+
+impl<'a> Evaluator<'a> {
+  pub fn new(program: &'a Program, resolutions: &'a Resolutions) -> Self {
+    let mut decls = FxHashMap::default();
+    let mut funs = FxHashMap::default();
+
+    for stmt in &program.stmts {
+      match &stmt.kind {
+        StmtKind::Val(decl) => {
+          decls.insert(decl.pattern.to_string(), &**decl);
+        }
+        StmtKind::Fun(fun) => {
+          funs.insert(fun.prototype.name.span, &**fun);
+        }
+        _ => {}
+      }
+    }
+
+    Self {
+      decls,
+      funs,
+      resolutions,
+      states: FxHashMap::default(),
+      frames: vec![],
+      names: vec![],
+      steps: 0,
+      vals: vec![],
+    }
+  }
+
+  /// evaluates a top-level `val` and the `val`s it uses, a number takes the
+  /// type the `val` is declared with
+  pub fn eval_val(
+    &mut self,
+    name: &str,
+    span: Span,
+  ) -> Result<Constant, Report> {
+    match self.states.get(name) {
+      Some(State::Evaluated(constant)) => return Ok(constant.to_owned()),
+      Some(State::Evaluating) => {
+        return Err(Report::Semantic(SemanticKind::ValCycle(
+          span,
+          name.to_string(),
+        )))
+      }
+      None => {}
+    }
+
+    let decl = self.decls[name];
+
+    self.states.insert(name.to_string(), State::Evaluating);
+
+    let constant = match &decl.ty {
+      Some(ty) => self.eval_constant_of(name, &decl.value)?.coerce(&ty.kind),
+      None => self.eval_constant_of(name, &decl.value)?,
+    };
+
+    self
+      .states
+      .insert(name.to_string(), State::Evaluated(constant.to_owned()));
+
+    self.vals.push((name.to_string(), constant.to_owned()));
+
+    Ok(constant)
+  }
+
+  /// evaluates the size of an array type, an integer from zero
+  pub fn eval_size(&mut self, expr: &'a Expr) -> Result<i64, Report> {
+    match self.eval_constant_of(&expr.to_string(), expr)? {
+      Constant::Int(int, _) if int >= 0 => Ok(int),
+      constant => Err(Report::Semantic(SemanticKind::InvalidSize(
+        expr.span,
+        constant.to_string(),
+      ))),
+    }
+  }
+
+  /// evaluates the constant named `name` in its own frame
+  fn eval_constant_of(
+    &mut self,
+    name: &str,
+    expr: &'a Expr,
+  ) -> Result<Constant, Report> {
+    self.names.push(name.to_string());
+    self.frames.push(vec![FxHashMap::default()]);
+
+    let steps = std::mem::replace(&mut self.steps, 0);
+    let value = self.eval_expr(expr);
+    let constant = match value {
+      Ok(Some(constant)) => Ok(constant),
+      Err(Interrupt::Report(report)) => Err(report),
+      _ => Err(self.not_constant(expr.span)),
+    };
+
+    self.steps = steps;
+    self.frames.pop();
+    self.names.pop();
+
+    constant
+  }
+
+  fn eval_expr_identifier(&mut self, span: Span, identifier: &str) -> Eval {
+    let frame = self.frames.last().expect("a frame");
+
+    for scope in frame.iter().rev() {
+      if let Some(constant) = scope.get(identifier) {
+        return Ok(Some(constant.to_owned()));
+      }
+    }
+
+    if self.decls.contains_key(identifier) {
+      return Ok(Some(self.eval_val(identifier, span)?));
+    }
+
+    Err(self.not_constant(span).into())
+  }
+
+  fn eval_expr_interpolation(&mut self, segments: &'a [Fsp<Expr>]) -> Eval {
+    let mut string = String::new();
+
+    for segment in segments {
+      string += &self.eval_constant(segment)?.to_string();
+    }
+
+    Ok(Some(Constant::Str(string)))
+  }
+
+  fn eval_expr_cast(&mut self, span: Span, value: &'a Expr, ty: &Ty) -> Eval {
+    let value = self.eval_constant(value)?;
+
+    match ops::eval_cast(value, &ty.kind) {
+      Some(constant) => Ok(Some(constant)),
+      None => Err(self.not_constant(span).into()),
+    }
+  }
+
+  fn eval_expr_bin_op(
+    &mut self,
+    span: Span,
+    lhs: &'a Expr,
+    op: &BinOp,
+    rhs: &'a Expr,
+  ) -> Eval {
+    let lhs_value = self.eval_constant(lhs)?;
+
+    if ops::short_circuits(&op.node, &lhs_value) {
+      return Ok(Some(lhs_value));
+    }
+
+    let rhs_value = self.eval_constant(rhs)?;
+    let (lhs_value, rhs_value) = ops::unify(lhs, lhs_value, rhs, rhs_value);
+
+    match ops::eval_bin_op(&op.node, lhs_value, rhs_value) {
+      Ok(Some(constant)) => Ok(Some(constant)),
+      Ok(None) => Err(self.not_constant(span).into()),
+      Err(OpError::DivisionByZero) => {
+        Err(Report::Semantic(SemanticKind::DivisionByZero(span)).into())
+      }
+      Err(OpError::Overflow(expr)) => {
+        Err(Report::Semantic(SemanticKind::Overflow(span, expr)).into())
+      }
+    }
+  }
+
+  /// the inputs and the output of the function called take the types of its
+  /// prototype
+  fn eval_expr_call(
+    &mut self,
+    callee: &'a Expr,
+    inputs: &'a [Fsp<Expr>],
+  ) -> Eval {
+    let fun = match self.fun(callee) {
+      Some(fun) if fun.is_const() => fun,
+      _ => {
+        return Err(
+          Report::Semantic(SemanticKind::NotConstFun(
+            callee.span,
+            callee.to_string(),
+          ))
+          .into(),
+        )
+      }
+    };
+
+    if self.frames.len() > CALLS_LIMIT {
+      return Err(self.limit(callee.span, "calls", CALLS_LIMIT));
+    }
+
+    let mut scope = FxHashMap::default();
+
+    for (input, arg) in inputs.iter().zip(&fun.prototype.inputs) {
+      let constant = self.eval_constant(input)?.coerce(&arg.ty.kind);
+
+      scope.insert(arg.pattern.to_string(), constant);
+    }
+
+    self.frames.push(vec![scope]);
+
+    let value = flow::returned(self.eval_block(&fun.body));
+
+    self.frames.pop();
+
+    // a function without output returns nothing even if its last expression
+    // has a value
+    match fun.prototype.as_ty().kind {
+      TyKind::Void => value.map(|_| None),
+      ref kind => value.map(|value| value.map(|value| value.coerce(kind))),
+    }
+  }
+
+  /// the function resolved at the name of the callee, an overloaded function
+  /// is resolved by the type checker. the sizes are folded before the names
+  /// are resolved, so a callee without resolution is the only top-level
+  /// function of its name
+  fn fun(&self, callee: &Expr) -> Option<&'a Fun> {
+    if let Some(def) = self.resolutions.resolution(callee.span) {
+      return self.funs.get(&self.resolutions.def(def).span).copied();
+    }
+
+    let name = callee.to_string();
+    let mut funs = self
+      .funs
+      .values()
+      .filter(|fun| fun.prototype.name.to_string() == name);
+
+    match (funs.next(), funs.next()) {
+      (Some(fun), None) => Some(*fun),
+      _ => None,
+    }
+  }
+
+  fn eval_decl(&mut self, decl: &'a Decl) -> Eval {
+    let constant = match &decl.ty {
+      Some(ty) => self.eval_constant(&decl.value)?.coerce(&ty.kind),
+      None => self.eval_constant(&decl.value)?,
+    };
+
+    self.scope().insert(decl.pattern.to_string(), constant);
+
+    Ok(None)
+  }
+
+  /// a number is converted to the type of the variable
+  fn assign(&mut self, lhs: &Expr, constant: Constant) -> Eval {
+    let name = lhs.to_string();
+    let frame = self.frames.last_mut().expect("a frame");
+
+    let place = frame
+      .iter_mut()
+      .rev()
+      .find_map(|scope| scope.get_mut(&name));
+
+    match place {
+      Some(place) => {
+        *place = match place.num() {
+          Some(num) => constant.convert(num),
+          None => constant,
+        };
+
+        Ok(None)
+      }
+      None => Err(self.not_constant(lhs.span).into()),
+    }
+  }
+
+  fn eval_constant(&mut self, expr: &'a Expr) -> Flow<Value, Constant> {
+    let value = self.eval_expr(expr)?;
+
+    self.expect_constant(expr.span, value)
+  }
+
+  fn expect_constant(&self, span: Span, value: Value) -> Flow<Value, Constant> {
+    value.ok_or_else(|| self.not_constant(span).into())
+  }
+
+  fn scope(&mut self) -> &mut FxHashMap<String, Constant> {
+    self
+      .frames
+      .last_mut()
+      .and_then(|frame| frame.last_mut())
+      .expect("a scope")
+  }
+
+  fn not_constant(&self, span: Span) -> Report {
+    Report::Semantic(SemanticKind::ValNotConstant(span, self.name()))
+  }
+
+  fn limit(&self, span: Span, what: &str, limit: usize) -> Interrupt<Value> {
+    Report::Semantic(SemanticKind::EvaluationLimit(
+      span,
+      self.name(),
+      format!("{limit} {what}"),
+    ))
+    .into()
+  }
+
+  fn name(&self) -> String {
+    self.names.last().cloned().unwrap_or_default()
+  }
+}
+
+impl<'a> Evaluate<'a> for Evaluator<'a> {
+  type Value = Value;
+
+  fn void() -> Value {
+    None
+  }
+
+  fn eval_expr(&mut self, expr: &'a Expr) -> Eval {
+    self.step(expr.span)?;
+
+    match &expr.kind {
+      ExprKind::Lit(lit) => Ok(Some(Constant::lit(&lit.kind))),
+      ExprKind::Interpolation(segments) => {
+        self.eval_expr_interpolation(segments)
+      }
+      ExprKind::Identifier(identifier) => {
+        self.eval_expr_identifier(expr.span, identifier)
+      }
+      ExprKind::UnOp(op, rhs) => {
+        let rhs = self.eval_constant(rhs)?;
+
+        match ops::eval_un_op(&op.node, rhs) {
+          Some(constant) => Ok(Some(constant)),
+          None => Err(self.not_constant(expr.span).into()),
+        }
+      }
+      ExprKind::BinOp(lhs, op, rhs) => {
+        self.eval_expr_bin_op(expr.span, lhs, op, rhs)
+      }
+      ExprKind::Cast(value, ty) => self.eval_expr_cast(expr.span, value, ty),
+      ExprKind::Call(callee, inputs) => self.eval_expr_call(callee, inputs),
+      ExprKind::Decl(decl) => self.eval_decl(decl),
+      ExprKind::Stmt(stmt) => match &stmt.kind {
+        StmtKind::Val(decl) => self.eval_decl(decl),
+        _ => Err(self.not_constant(expr.span).into()),
+      },
+      ExprKind::Assign(lhs, _, rhs) => {
+        let value = self.eval_constant(rhs)?;
+
+        self.assign(lhs, value)
+      }
+      ExprKind::AssignOp(lhs, op, rhs) => {
+        let value = self.eval_expr_bin_op(expr.span, lhs, op, rhs)?;
+
+        self.assign(lhs, self.expect_constant(rhs.span, value)?)
+      }
+      ExprKind::Block(block) => self.eval_block(block),
+      ExprKind::Loop(body) => self.eval_loop(None, false, body),
+      ExprKind::While(condition, body) => {
+        self.eval_loop(Some(condition), true, body)
+      }
+      ExprKind::Until(condition, body) => {
+        self.eval_loop(Some(condition), false, body)
+      }
+      ExprKind::Return(value) => {
+        let value = self.eval_maybe(value)?;
+
+        Err(Interrupt::Return(value))
+      }
+      ExprKind::Break(value) => {
+        let value = self.eval_maybe(value)?;
+
+        Err(Interrupt::Break(value))
+      }
+      ExprKind::Continue => Err(Interrupt::Continue),
+      ExprKind::When(condition, consequence, alternative) => {
+        self.eval_branch(condition, consequence, Some(alternative))
+      }
+      ExprKind::IfElse(condition, consequence, maybe_alternative) => {
+        self.eval_branch(condition, consequence, maybe_alternative.as_deref())
+      }
+      // a tuple of one element is an expression between parentheses
+      ExprKind::Tuple(elements) if elements.len() == 1 => {
+        self.eval_expr(&elements[0])
+      }
+      _ => Err(self.not_constant(expr.span).into()),
+    }
+  }
+
+  fn eval_bool(&mut self, expr: &'a Expr) -> Flow<Value, bool> {
+    match self.eval_constant(expr)? {
+      Constant::Bool(boolean) => Ok(boolean),
+      _ => Err(self.not_constant(expr.span).into()),
+    }
+  }
+
+  fn eval_block(&mut self, block: &'a Block) -> Eval {
+    self
+      .frames
+      .last_mut()
+      .expect("a frame")
+      .push(FxHashMap::default());
+
+    let mut value = Ok(None);
+
+    for expr in &block.exprs {
+      value = self.eval_expr(expr);
+
+      if value.is_err() {
+        break;
+      }
+    }
+
+    self.frames.last_mut().expect("a frame").pop();
+
+    value
+  }
+
+  fn step(&mut self, span: Span) -> Flow<Value, ()> {
+    self.steps += 1;
+
+    match self.steps > STEPS_LIMIT {
+      true => Err(self.limit(span, "steps", STEPS_LIMIT)),
+      false => Ok(()),
+    }
+  }
+}
+
+/// folds the sizes of the array types of a program into their values, they
+/// are folded before the names are resolved so the symbols of the functions
+/// are mangled from them
+pub(crate) fn fold_sizes(program: &Program, hir: &mut hir::Program) {
+  let resolutions = Resolutions::default();
+
+  hir.visit_tys_mut(&mut |ty| fold_size(program, &resolutions, ty));
+}
+
+fn fold_size(program: &Program, resolutions: &Resolutions, ty: &mut Ty) {
+  match &mut ty.kind {
+    TyKind::Array(element, maybe_size) => {
+      fold_size(program, resolutions, element);
+
+      if let Some(Size::Expr(expr)) = maybe_size {
+        let mut evaluator = Evaluator::new(program, resolutions);
+
+        match evaluator.eval_size(expr) {
+          Ok(int) => *maybe_size = Some(Size::Int(int)),
+          Err(report) => program.reporter.add_report(report),
+        }
+      }
+    }
+    TyKind::Tuple(tys) => {
+      for ty in tys {
+        fold_size(program, resolutions, ty);
+      }
+    }
+    TyKind::Fn(inputs, output) => {
+      for ty in inputs.iter_mut().chain(Some(output)) {
+        fold_size(program, resolutions, ty);
+      }
+    }
+    _ => {}
+  }
+}
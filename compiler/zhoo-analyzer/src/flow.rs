@@ -0,0 +1,104 @@
+//! the control flow of an evaluation, the compile-time evaluation engine and
+//! the interpreter share it
+
+use zhoo_ast::ast::{Block, Expr};
+use zhoo_ast::ptr::Fsp;
+use zhoo_errors::Report;
+use zhoo_span::span::Span;
+
+/// the evaluation of an expression is interrupted by the control flow, by
+/// `exit` or by an error
+pub enum Interrupt<V> {
+  Break(V),
+  Continue,
+  Return(V),
+  Exit(i32),
+  Report(Report),
+}
+
+impl<V> From<Report> for Interrupt<V> {
+  fn from(report: Report) -> Self {
+    Self::Report(report)
+  }
+}
+
+/// the value of an evaluation or its interruption
+pub type Flow<V, T = V> = Result<T, Interrupt<V>>;
+
+/// an evaluator of the expressions of a program
+pub trait Evaluate<'a> {
+  type Value;
+
+  /// the value of the expressions without value
+  fn void() -> Self::Value;
+
+  fn eval_expr(&mut self, expr: &'a Expr) -> Flow<Self::Value>;
+
+  fn eval_bool(&mut self, expr: &'a Expr) -> Flow<Self::Value, bool>;
+
+  fn eval_block(&mut self, block: &'a Block) -> Flow<Self::Value>;
+
+  /// an iteration of a loop, an empty body evaluates no expression so an
+  /// evaluator which counts its steps counts the iterations too
+  fn step(&mut self, _span: Span) -> Flow<Self::Value, ()> {
+    Ok(())
+  }
+
+  fn eval_maybe(
+    &mut self,
+    maybe_expr: &'a Option<Fsp<Expr>>,
+  ) -> Flow<Self::Value> {
+    match maybe_expr {
+      Some(expr) => self.eval_expr(expr),
+      None => Ok(Self::void()),
+    }
+  }
+
+  /// the branch of a `when` or of an `if`, an `if` without `else` has no
+  /// value when its condition is false
+  fn eval_branch(
+    &mut self,
+    condition: &'a Expr,
+    consequence: &'a Expr,
+    maybe_alternative: Option<&'a Expr>,
+  ) -> Flow<Self::Value> {
+    match (self.eval_bool(condition)?, maybe_alternative) {
+      (true, _) => self.eval_expr(consequence),
+      (false, Some(alternative)) => self.eval_expr(alternative),
+      (false, None) => Ok(Self::void()),
+    }
+  }
+
+  /// evaluates a loop, the condition of a `while` is expected to be `true`
+  /// and the one of an `until` to be `false`
+  fn eval_loop(
+    &mut self,
+    condition: Option<&'a Fsp<Expr>>,
+    expected: bool,
+    body: &'a Block,
+  ) -> Flow<Self::Value> {
+    loop {
+      self.step(body.span)?;
+
+      if let Some(condition) = condition {
+        if self.eval_bool(condition)? != expected {
+          return Ok(Self::void());
+        }
+      }
+
+      match self.eval_block(body) {
+        Ok(_) | Err(Interrupt::Continue) => {}
+        Err(Interrupt::Break(value)) => return Ok(value),
+        Err(interrupt) => return Err(interrupt),
+      }
+    }
+  }
+}
+
+/// the value of a call, a `return` ends the body of the function called
+pub fn returned<V>(value: Flow<V>) -> Flow<V> {
+  match value {
+    Ok(value) | Err(Interrupt::Return(value)) => Ok(value),
+    Err(interrupt) => Err(interrupt),
+  }
+}
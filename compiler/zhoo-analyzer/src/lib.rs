@@ -1,6 +1,15 @@
+#[cfg(test)]
+mod tests;
+
 mod checker;
 mod context;
+mod eval;
 mod scope;
 
 pub mod analyzer;
 pub mod builtins;
+pub mod constant;
+pub mod flow;
+pub mod num;
+pub mod ops;
+pub mod resolver;
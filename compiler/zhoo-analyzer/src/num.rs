@@ -0,0 +1,96 @@
+//! the types of the numbers, the constants and the values of the interpreter
+//! are computed like the compiled program computes them
+
+use zhoo_ast::ast::TyKind;
+
+/// the type of a number, an unsigned integer of 64 bits is kept in an `i64`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Num {
+  Int,
+  S8,
+  S16,
+  S32,
+  U8,
+  U16,
+  U32,
+  U64,
+  Real,
+  F32,
+}
+
+impl Num {
+  pub fn from_ty(ty: &TyKind) -> Option<Self> {
+    match ty {
+      TyKind::Int => Some(Self::Int),
+      TyKind::S8 => Some(Self::S8),
+      TyKind::S16 => Some(Self::S16),
+      TyKind::S32 => Some(Self::S32),
+      TyKind::U8 => Some(Self::U8),
+      TyKind::U16 => Some(Self::U16),
+      TyKind::U32 => Some(Self::U32),
+      TyKind::U64 => Some(Self::U64),
+      TyKind::Real => Some(Self::Real),
+      TyKind::F32 => Some(Self::F32),
+      _ => None,
+    }
+  }
+
+  pub fn is_unsigned(self) -> bool {
+    matches!(self, Self::U8 | Self::U16 | Self::U32 | Self::U64)
+  }
+
+  pub fn is_float(self) -> bool {
+    matches!(self, Self::Real | Self::F32)
+  }
+
+  pub fn bits(self) -> u32 {
+    match self {
+      Self::S8 | Self::U8 => 8,
+      Self::S16 | Self::U16 => 16,
+      Self::S32 | Self::U32 | Self::F32 => 32,
+      Self::Int | Self::U64 | Self::Real => 64,
+    }
+  }
+
+  /// truncates an integer to the bits of the type, the bits above are the
+  /// sign of a signed integer and zeros for an unsigned integer
+  pub fn wrap(self, int: i64) -> i64 {
+    let shift = 64 - self.bits();
+
+    match self.is_unsigned() {
+      true => ((int as u64) << shift >> shift) as i64,
+      false => int << shift >> shift,
+    }
+  }
+
+  /// rounds a real to the precision of the type
+  pub fn round(self, real: f64) -> f64 {
+    match self {
+      Self::F32 => real as f32 as f64,
+      _ => real,
+    }
+  }
+
+  /// converts an integer of this type to a real
+  pub fn to_real(self, int: i64) -> f64 {
+    match self.is_unsigned() {
+      true => int as u64 as f64,
+      false => int as f64,
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Int => "int",
+      Self::S8 => "s8",
+      Self::S16 => "s16",
+      Self::S32 => "s32",
+      Self::U8 => "u8",
+      Self::U16 => "u16",
+      Self::U32 => "u32",
+      Self::U64 => "u64",
+      Self::Real => "real",
+      Self::F32 => "f32",
+    }
+  }
+}
@@ -0,0 +1,263 @@
+//! the operators and the casts of the constants, the compile-time evaluation
+//! engine and the interpreter share them so an expression has the same value
+//! at compile time and at runtime
+
+use super::constant::Constant;
+use super::num::Num;
+
+use zhoo_ast::ast::{BinOpKind, Expr, ExprKind, LitKind, TyKind, UnOpKind};
+
+/// an operation which traps, its report is made by the evaluator
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpError {
+  DivisionByZero,
+  /// the division of the minimum of a signed integer by `-1`, the expression
+  /// which overflows
+  Overflow(String),
+}
+
+/// the logical operators do not evaluate their rhs when their lhs decides
+/// the value
+pub fn short_circuits(op: &BinOpKind, lhs: &Constant) -> bool {
+  matches!(
+    (op, lhs),
+    (BinOpKind::And, Constant::Bool(false))
+      | (BinOpKind::Or, Constant::Bool(true))
+  )
+}
+
+/// `None` for an operator which does not apply to its operand
+pub fn eval_un_op(op: &UnOpKind, rhs: Constant) -> Option<Constant> {
+  let constant = match (op, rhs) {
+    (UnOpKind::Neg, Constant::Int(int, num)) => {
+      Constant::Int(num.wrap(int.wrapping_neg()), num)
+    }
+    (UnOpKind::Neg, Constant::Real(real, num)) => Constant::Real(-real, num),
+    (UnOpKind::Not, Constant::Bool(boolean)) => Constant::Bool(!boolean),
+    (UnOpKind::Not, Constant::Int(int, num)) => {
+      Constant::Int(num.wrap(!int), num)
+    }
+    _ => return None,
+  };
+
+  Some(constant)
+}
+
+/// the operands are unified before, `None` for an operator which does not
+/// apply to its operands. a range is not a constant, it's evaluated by the
+/// interpreter
+pub fn eval_bin_op(
+  op: &BinOpKind,
+  lhs: Constant,
+  rhs: Constant,
+) -> Result<Option<Constant>, OpError> {
+  use BinOpKind::*;
+
+  let constant = match (lhs, rhs) {
+    (Constant::Int(lhs, num), Constant::Int(rhs, _)) => {
+      return eval_int_bin_op(op, lhs, rhs, num)
+    }
+    (Constant::Real(lhs, num), Constant::Real(rhs, _)) => match op {
+      Add => Constant::Real(num.round(lhs + rhs), num),
+      Sub => Constant::Real(num.round(lhs - rhs), num),
+      Mul => Constant::Real(num.round(lhs * rhs), num),
+      Div => Constant::Real(num.round(lhs / rhs), num),
+      Rem => Constant::Real(num.round(lhs % rhs), num),
+      Lt => Constant::Bool(lhs < rhs),
+      Gt => Constant::Bool(lhs > rhs),
+      Le => Constant::Bool(lhs <= rhs),
+      Ge => Constant::Bool(lhs >= rhs),
+      Eq => Constant::Bool(lhs == rhs),
+      Ne => Constant::Bool(lhs != rhs),
+      _ => return Ok(None),
+    },
+    // an integer mixed with a real is converted to the real
+    (lhs @ Constant::Int(..), Constant::Real(rhs, num)) => {
+      return eval_bin_op(op, lhs.convert(num), Constant::Real(rhs, num))
+    }
+    (Constant::Real(lhs, num), rhs @ Constant::Int(..)) => {
+      return eval_bin_op(op, Constant::Real(lhs, num), rhs.convert(num))
+    }
+    (Constant::Bool(lhs), Constant::Bool(rhs)) => match op {
+      And => Constant::Bool(lhs && rhs),
+      Or => Constant::Bool(lhs || rhs),
+      BitAnd => Constant::Bool(lhs & rhs),
+      BitOr => Constant::Bool(lhs | rhs),
+      BitXor => Constant::Bool(lhs ^ rhs),
+      Eq => Constant::Bool(lhs == rhs),
+      Ne => Constant::Bool(lhs != rhs),
+      _ => return Ok(None),
+    },
+    (Constant::Char(lhs), Constant::Char(rhs)) => match op {
+      Lt => Constant::Bool(lhs < rhs),
+      Gt => Constant::Bool(lhs > rhs),
+      Le => Constant::Bool(lhs <= rhs),
+      Ge => Constant::Bool(lhs >= rhs),
+      Eq => Constant::Bool(lhs == rhs),
+      Ne => Constant::Bool(lhs != rhs),
+      _ => return Ok(None),
+    },
+    (Constant::Str(lhs), Constant::Str(rhs)) => match op {
+      Concat => Constant::Str(lhs + &rhs),
+      Lt => Constant::Bool(lhs < rhs),
+      Gt => Constant::Bool(lhs > rhs),
+      Le => Constant::Bool(lhs <= rhs),
+      Ge => Constant::Bool(lhs >= rhs),
+      Eq => Constant::Bool(lhs == rhs),
+      Ne => Constant::Bool(lhs != rhs),
+      _ => return Ok(None),
+    },
+    _ => return Ok(None),
+  };
+
+  Ok(Some(constant))
+}
+
+/// the integers are computed on 64 bits then wrapped to their type, the
+/// unsigned integers are divided, shifted and compared as unsigned. the shifts
+/// take their amount modulo the bits of the type
+fn eval_int_bin_op(
+  op: &BinOpKind,
+  lhs: i64,
+  rhs: i64,
+  num: Num,
+) -> Result<Option<Constant>, OpError> {
+  use BinOpKind::*;
+
+  if matches!(op, Div | Rem) {
+    if rhs == 0 {
+      return Err(OpError::DivisionByZero);
+    }
+
+    // the division of the minimum of a signed integer by `-1` traps like a
+    // division by zero
+    let min = i64::MIN >> (64 - num.bits());
+
+    if !num.is_unsigned() && lhs == min && rhs == -1 {
+      return Err(OpError::Overflow(format!("{lhs} {op} {rhs}")));
+    }
+  }
+
+  let (ulhs, urhs) = (lhs as u64, rhs as u64);
+  let unsigned = num.is_unsigned();
+  let shift = rhs as u32 % num.bits();
+
+  let int = match op {
+    Add => lhs.wrapping_add(rhs),
+    Sub => lhs.wrapping_sub(rhs),
+    Mul => lhs.wrapping_mul(rhs),
+    Div if unsigned => (ulhs / urhs) as i64,
+    Div => lhs.wrapping_div(rhs),
+    Rem if unsigned => (ulhs % urhs) as i64,
+    Rem => lhs.wrapping_rem(rhs),
+    Shl => lhs.wrapping_shl(shift),
+    Shr if unsigned => ulhs.wrapping_shr(shift) as i64,
+    Shr => lhs.wrapping_shr(shift),
+    BitAnd => lhs & rhs,
+    BitOr => lhs | rhs,
+    BitXor => lhs ^ rhs,
+    Lt if unsigned => return Ok(Some(Constant::Bool(ulhs < urhs))),
+    Gt if unsigned => return Ok(Some(Constant::Bool(ulhs > urhs))),
+    Le if unsigned => return Ok(Some(Constant::Bool(ulhs <= urhs))),
+    Ge if unsigned => return Ok(Some(Constant::Bool(ulhs >= urhs))),
+    Lt => return Ok(Some(Constant::Bool(lhs < rhs))),
+    Gt => return Ok(Some(Constant::Bool(lhs > rhs))),
+    Le => return Ok(Some(Constant::Bool(lhs <= rhs))),
+    Ge => return Ok(Some(Constant::Bool(lhs >= rhs))),
+    Eq => return Ok(Some(Constant::Bool(lhs == rhs))),
+    Ne => return Ok(Some(Constant::Bool(lhs != rhs))),
+    Range | And | Or | Concat => return Ok(None),
+  };
+
+  Ok(Some(Constant::Int(num.wrap(int), num)))
+}
+
+/// converts a constant to the type of `as`, an integer is wrapped to its new
+/// type, sign-extended from a signed integer and zero-extended from an
+/// unsigned one. a real is truncated toward zero then wrapped. a constant is
+/// converted to a `str` like the core library writes it. `None` for a cast the
+/// type checker rejects
+pub fn eval_cast(constant: Constant, ty: &TyKind) -> Option<Constant> {
+  let num = Num::from_ty(ty);
+
+  let constant = match (ty, num, constant) {
+    (_, Some(num), constant @ (Constant::Int(..) | Constant::Real(..))) => {
+      constant.convert(num)
+    }
+    (_, Some(num), Constant::Bool(boolean)) if !num.is_float() => {
+      Constant::Int(boolean as i64, num)
+    }
+    (_, Some(num), Constant::Char(char)) if !num.is_float() => {
+      Constant::Int(num.wrap(char as i64), num)
+    }
+    (TyKind::Bool, _, Constant::Int(int, _)) => Constant::Bool(int != 0),
+    (TyKind::Bool, _, Constant::Bool(boolean)) => Constant::Bool(boolean),
+    (TyKind::Char, _, Constant::Char(char)) => Constant::Char(char),
+    (TyKind::Char, _, Constant::Int(int, _)) => {
+      Constant::Char(int_to_char(int))
+    }
+    (TyKind::Str, _, constant) => Constant::Str(constant.to_string()),
+    _ => return None,
+  };
+
+  Some(constant)
+}
+
+/// the `char` of an integer, an integer which is not a unicode scalar value is
+/// the replacement char `U+FFFD`
+pub fn int_to_char(int: i64) -> char {
+  u32::try_from(int)
+    .ok()
+    .and_then(char::from_u32)
+    .unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// the same choice of type as the type checker, an unsuffixed number takes
+/// the type of the other operand, an integer mixed with a real is converted
+/// to the real and the smaller operand is widened
+pub fn unify(
+  lhs: &Expr,
+  lhs_value: Constant,
+  rhs: &Expr,
+  rhs_value: Constant,
+) -> (Constant, Constant) {
+  let (Some(lhs_num), Some(rhs_num)) = (lhs_value.num(), rhs_value.num())
+  else {
+    return (lhs_value, rhs_value);
+  };
+
+  let num = match (lhs_num, rhs_num) {
+    (lhs_num, rhs_num) if lhs_num == rhs_num => lhs_num,
+    (lhs_num, _) if takes_num(rhs, lhs_num) => lhs_num,
+    (_, rhs_num) if takes_num(lhs, rhs_num) => rhs_num,
+    (lhs_num, rhs_num) if lhs_num.is_float() != rhs_num.is_float() => {
+      match lhs_num.is_float() {
+        true => lhs_num,
+        false => rhs_num,
+      }
+    }
+    (lhs_num, rhs_num) if lhs_num.bits() < rhs_num.bits() => rhs_num,
+    (lhs_num, _) => lhs_num,
+  };
+
+  (lhs_value.convert(num), rhs_value.convert(num))
+}
+
+/// an unsuffixed integer takes an integer type and an unsuffixed real a real
+/// type, an expression between parentheses takes the type of its expression
+fn takes_num(expr: &Expr, num: Num) -> bool {
+  match &expr.kind {
+    ExprKind::Lit(lit) => match lit.kind {
+      LitKind::Int(_, None) => !num.is_float(),
+      LitKind::Real(_, None) => num.is_float(),
+      _ => false,
+    },
+    ExprKind::UnOp(op, rhs) if matches!(op.node, UnOpKind::Neg) => {
+      takes_num(rhs, num)
+    }
+    ExprKind::Tuple(elements) if elements.len() == 1 => {
+      takes_num(&elements[0], num)
+    }
+    _ => false,
+  }
+}
@@ -0,0 +1,459 @@
+//! the resolution of the names of a program, every builtin, extern, function,
+//! `val`, input and binding is a definition and every name refers to one
+//!
+//! the items of a scope are visible from the whole scope, so a function can
+//! call a function declared after it. the bindings are resolved by the hir
+//!
+//! a function declared inside of a function gets a symbol mangled from the
+//! symbol of the function which contains it, `main$helper` for `helper` in
+//! `main`. two functions with the same symbol are told apart by a number,
+//! `main$helper$1`
+//!
+//! the functions of a scope can share a name when the types of their inputs
+//! differ, the type checker selects the overload of every call. an overload
+//! gets a symbol mangled from the types of its inputs, `add$int$int` for
+//! `add(int, int)` and `add$void` for `add()`
+//!
+//! a symbol is made of identifiers, numbers and `$` so every assembler and
+//! linker takes it. a type name is a keyword, it never clashes with the name
+//! of a function
+
+use super::builtins::builtins;
+use super::scope::ScopeMap;
+
+use zhoo_ast::ast::{Size, Ty, TyKind};
+use zhoo_ast::ptr::Fsp;
+use zhoo_errors::{Report, Reporter, SemanticKind};
+use zhoo_helper::constant::ENTRY_POINT;
+use zhoo_hir::hir::{Block, Expr, ExprKind, Ident, Item, ItemKind, LocalId};
+use zhoo_hir::hir::{Fun, Program};
+use zhoo_span::span::Span;
+
+use fxhash::{FxHashMap, FxHashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DefId(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DefKind {
+  Builtin,
+  Ext,
+  Fun,
+  Val,
+  Input,
+  Local,
+}
+
+impl DefKind {
+  /// a definition which can be called
+  pub fn is_fun(&self) -> bool {
+    matches!(self, Self::Builtin | Self::Ext | Self::Fun)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Def {
+  pub name: String,
+  pub kind: DefKind,
+  /// the name of the definition in the generated code
+  pub symbol: String,
+  /// the span of the name of the definition, a builtin has no span
+  pub span: Span,
+}
+
+/// the definitions of a program and the def-use index of their names
+#[derive(Clone, Debug, Default)]
+pub struct Resolutions {
+  defs: Vec<Def>,
+  locals: FxHashMap<LocalId, DefId>,
+  /// the definition named at every span of a name
+  names: FxHashMap<Span, DefId>,
+  /// the spans which name every definition, its declaration first
+  references: Vec<Vec<Span>>,
+  /// the overloads named at every span of the name of an overloaded
+  /// function, until the type checker selects one of them
+  overloads: FxHashMap<Span, Vec<DefId>>,
+}
+
+impl Resolutions {
+  pub fn def(&self, def: DefId) -> &Def {
+    &self.defs[def.0]
+  }
+
+  pub fn defs(&self) -> impl Iterator<Item = (DefId, &Def)> {
+    self.defs.iter().enumerate().map(|(x, def)| (DefId(x), def))
+  }
+
+  /// the definition of a binding of the hir
+  pub fn local(&self, local: LocalId) -> DefId {
+    self.locals[&local]
+  }
+
+  /// the definition named at a span, `None` when the name is not resolved
+  pub fn resolution(&self, span: Span) -> Option<DefId> {
+    self.names.get(&span).copied()
+  }
+
+  /// the overloads named at a span, `None` when the name is not overloaded
+  pub fn overloads(&self, span: Span) -> Option<&[DefId]> {
+    self.overloads.get(&span).map(Vec::as_slice)
+  }
+
+  /// resolves the name of an overloaded function to one of its overloads
+  pub(crate) fn select(&mut self, span: Span, def: DefId) {
+    self.refer(span, def);
+  }
+
+  pub fn references(&self, def: DefId) -> &[Span] {
+    &self.references[def.0]
+  }
+
+  /// the definition named at an offset of the source
+  pub fn def_at(&self, offset: u32) -> Option<DefId> {
+    self
+      .names
+      .iter()
+      .find(|(span, _)| span.lo <= offset && offset < span.hi)
+      .map(|(_, def)| *def)
+  }
+
+  /// records a use of a definition, a desugared expression can repeat a name
+  fn refer(&mut self, span: Span, def: DefId) {
+    if self.names.insert(span, def).is_none() {
+      self.references[def.0].push(span);
+    }
+  }
+}
+
+/// resolves the names of a program, the names which are not resolved are
+/// reported
+pub fn resolve(program: &Program, reporter: &Reporter) -> Resolutions {
+  let mut resolver = Resolver {
+    program,
+    reporter,
+    resolutions: Resolutions::default(),
+    scope_map: ScopeMap::default(),
+    parents: vec![],
+    symbols: FxHashSet::default(),
+    signatures: FxHashMap::default(),
+  };
+
+  for builtin in builtins() {
+    resolver.define(builtin.name, DefKind::Builtin, Span::ZERO);
+  }
+
+  // a top-level item can not have the name of a builtin
+  resolver.declare_items(program.items.iter());
+
+  for item in &program.items {
+    resolver.resolve_item(item);
+  }
+
+  resolver.resolutions
+}
+
+struct Resolver<'a> {
+  program: &'a Program,
+  reporter: &'a Reporter,
+  resolutions: Resolutions,
+  scope_map: ScopeMap,
+  /// the symbols of the functions which contain the current expression
+  parents: Vec<String>,
+  /// the symbols of the nested functions
+  symbols: FxHashSet<String>,
+  /// the types of the inputs of every overload
+  signatures: FxHashMap<DefId, String>,
+}
+
+impl<'a> Resolver<'a> {
+  fn declare_items<'b>(&mut self, items: impl Iterator<Item = &'b Item>) {
+    let items = items.collect::<Vec<_>>();
+    let mut funs = FxHashMap::<&str, usize>::default();
+
+    for item in &items {
+      if let ItemKind::Fun(fun) = &item.kind {
+        *funs.entry(&fun.name.name).or_default() += 1;
+      }
+    }
+
+    for item in items {
+      let (name, kind) = match &item.kind {
+        // the entry point can not be overloaded
+        ItemKind::Fun(fun)
+          if funs[fun.name.name.as_str()] > 1
+            && !(self.parents.is_empty() && fun.name.name == ENTRY_POINT) =>
+        {
+          self.define_overload(fun);
+          continue;
+        }
+        ItemKind::Ext(ext) => (&ext.name, DefKind::Ext),
+        ItemKind::Fun(fun) => (&fun.name, DefKind::Fun),
+        ItemKind::Val(val) => (&val.name, DefKind::Val),
+      };
+
+      self.define(name.name.to_string(), kind, name.span);
+    }
+  }
+
+  /// defines a function which shares its name with other functions of the
+  /// scope, two overloads can not take the same types
+  fn define_overload(&mut self, fun: &Fun) {
+    let name = fun.name.name.to_string();
+
+    let inputs = fun
+      .inputs
+      .iter()
+      .map(|input| self.program.local(*input).ty.as_ref().expect("a type"))
+      .map(|ty| mangle_ty(ty))
+      .collect::<Vec<_>>();
+
+    let inputs = match inputs.is_empty() {
+      true => String::from("void"),
+      false => inputs.join("$"),
+    };
+
+    let is_overload = self.scope_map.get_local(&name).iter().all(|def| {
+      self
+        .signatures
+        .get(def)
+        .filter(|signature| **signature != inputs)
+        .is_some()
+    });
+
+    let def = self.new_def(name.to_owned(), DefKind::Fun, fun.name.span);
+    let symbol = format!("{}${inputs}", self.resolutions.def(def).symbol);
+
+    self.resolutions.defs[def.0].symbol = symbol;
+    self.signatures.insert(def, inputs);
+
+    if !is_overload || self.scope_map.set_fun(name.to_owned(), def).is_err() {
+      self
+        .reporter
+        .add_report(Report::Semantic(SemanticKind::NameClash(
+          fun.name.span,
+          name,
+        )));
+    }
+  }
+
+  fn resolve_item(&mut self, item: &Item) {
+    match &item.kind {
+      ItemKind::Ext(_) => {}
+      ItemKind::Fun(fun) => self.resolve_fun(fun),
+      ItemKind::Val(val) => self.resolve_expr(&val.value),
+    }
+  }
+
+  fn resolve_fun(&mut self, fun: &Fun) {
+    let def = self
+      .resolutions
+      .resolution(fun.name.span)
+      .expect("a function");
+
+    self
+      .parents
+      .push(self.resolutions.def(def).symbol.to_string());
+    for (x, input) in fun.inputs.iter().enumerate() {
+      let decl = self.program.local(*input);
+
+      if fun.inputs[..x]
+        .iter()
+        .any(|previous| self.program.local(*previous).name == decl.name)
+      {
+        self
+          .reporter
+          .add_report(Report::Semantic(SemanticKind::NameClash(
+            decl.span,
+            decl.name.to_string(),
+          )));
+      }
+
+      self.define_local(*input, DefKind::Input);
+    }
+
+    self.resolve_block(&fun.body);
+    self.parents.pop();
+  }
+
+  fn resolve_block(&mut self, block: &Block) {
+    self.scope_map.enter_scope();
+
+    self.declare_items(block.exprs.iter().filter_map(
+      |expr| match &expr.kind {
+        ExprKind::Item(item) => Some(item.as_ref()),
+        _ => None,
+      },
+    ));
+
+    for expr in &block.exprs {
+      self.resolve_expr(expr);
+    }
+
+    self.scope_map.exit_scope();
+  }
+
+  fn resolve_expr(&mut self, expr: &Expr) {
+    match &expr.kind {
+      ExprKind::Lit(_) | ExprKind::Continue => {}
+      ExprKind::Local(local) => {
+        let def = self.resolutions.local(*local);
+
+        self.resolutions.refer(expr.span, def);
+      }
+      ExprKind::Global(ident) => self.resolve_global(ident, false),
+      ExprKind::Call(callee, inputs) => {
+        match &callee.kind {
+          ExprKind::Global(ident) => self.resolve_global(ident, true),
+          _ => self.resolve_expr(callee),
+        }
+
+        inputs.iter().for_each(|input| self.resolve_expr(input));
+      }
+      ExprKind::UnOp(_, rhs) | ExprKind::Cast(rhs, _) => self.resolve_expr(rhs),
+      ExprKind::BinOp(lhs, _, rhs)
+      | ExprKind::Assign(lhs, rhs)
+      | ExprKind::Index(lhs, rhs)
+      | ExprKind::Field(lhs, rhs) => {
+        self.resolve_expr(lhs);
+        self.resolve_expr(rhs);
+      }
+      // the value is resolved before the binding is defined
+      ExprKind::Let(local, value) => {
+        self.resolve_expr(value);
+        self.define_local(*local, DefKind::Local);
+      }
+      ExprKind::Block(block) | ExprKind::Loop(block, _) => {
+        self.resolve_block(block)
+      }
+      ExprKind::If(condition, consequence, maybe_alternative) => {
+        self.resolve_expr(condition);
+        self.resolve_expr(consequence);
+
+        if let Some(alternative) = maybe_alternative {
+          self.resolve_expr(alternative);
+        }
+      }
+      ExprKind::Return(maybe_expr) | ExprKind::Break(maybe_expr) => {
+        if let Some(expr) = maybe_expr {
+          self.resolve_expr(expr);
+        }
+      }
+      ExprKind::Lambda(inputs, body) => {
+        for input in inputs {
+          self.define_local(*input, DefKind::Input);
+        }
+
+        self.resolve_expr(body);
+      }
+      ExprKind::Interpolation(elements)
+      | ExprKind::Array(elements)
+      | ExprKind::Tuple(elements) => {
+        elements
+          .iter()
+          .for_each(|element| self.resolve_expr(element));
+      }
+      ExprKind::Item(item) => self.resolve_item(item),
+    }
+  }
+
+  /// a name of an overloaded function is resolved by the type checker
+  fn resolve_global(&mut self, ident: &Ident, is_callee: bool) {
+    match self.scope_map.get(&ident.name) {
+      Some(&[def]) => self.resolutions.refer(ident.span, def),
+      Some(defs) => {
+        self.resolutions.overloads.insert(ident.span, defs.to_vec());
+      }
+      None if is_callee => self.reporter.add_report(Report::Semantic(
+        SemanticKind::FunctionNotFound(ident.span, ident.name.to_string()),
+      )),
+      None => self.reporter.add_report(Report::Semantic(
+        SemanticKind::IdentifierNotFound(ident.span, ident.name.to_string()),
+      )),
+    }
+  }
+
+  /// defines an item in the current scope
+  fn define(&mut self, name: String, kind: DefKind, span: Span) -> DefId {
+    let def = self.new_def(name.to_owned(), kind, span);
+
+    if self.scope_map.set(name.to_owned(), def).is_err() {
+      self
+        .reporter
+        .add_report(Report::Semantic(SemanticKind::NameClash(span, name)));
+    }
+
+    def
+  }
+
+  fn define_local(&mut self, local: LocalId, kind: DefKind) {
+    let decl = self.program.local(local);
+    let def = self.new_def(decl.name.to_string(), kind, decl.span);
+
+    self.resolutions.locals.insert(local, def);
+  }
+
+  fn new_def(&mut self, name: String, kind: DefKind, span: Span) -> DefId {
+    let def = DefId(self.resolutions.defs.len());
+    let symbol = self.mangle(&name, kind);
+
+    self.resolutions.defs.push(Def {
+      name,
+      kind,
+      symbol,
+      span,
+    });
+    self.resolutions.references.push(vec![]);
+
+    if kind != DefKind::Builtin {
+      self.resolutions.refer(span, def);
+    }
+
+    def
+  }
+
+  /// the symbol of a definition, only a nested function is mangled
+  fn mangle(&mut self, name: &str, kind: DefKind) -> String {
+    let Some(parent) = self.parents.last().filter(|_| kind == DefKind::Fun)
+    else {
+      return name.to_string();
+    };
+
+    let mut symbol = format!("{parent}${name}");
+    let mut x = 0;
+
+    while self.symbols.contains(&symbol) {
+      x += 1;
+      symbol = format!("{parent}${name}${x}");
+    }
+
+    self.symbols.insert(symbol.to_owned());
+
+    symbol
+  }
+}
+
+/// the symbol of a type, a compound type is prefixed by its kind and its size
+/// then its types follow: `A3_int` for `int[3]`, `T2_int_bool` for
+/// `(int, bool)` and `F1_int_void` for `Fn(int): void`
+fn mangle_ty(ty: &Ty) -> String {
+  let mangle_tys =
+    |tys: &[Fsp<Ty>]| tys.iter().map(|ty| mangle_ty(ty)).collect::<Vec<_>>();
+
+  match &ty.kind {
+    TyKind::Array(element, Some(Size::Int(size))) => {
+      format!("A{size}_{}", mangle_ty(element))
+    }
+    TyKind::Array(element, _) => format!("A_{}", mangle_ty(element)),
+    TyKind::Tuple(elements) => {
+      format!("T{}_{}", elements.len(), mangle_tys(elements).join("_"))
+    }
+    TyKind::Fn(inputs, output) => {
+      let mut tys = mangle_tys(inputs);
+
+      tys.push(mangle_ty(output));
+
+      format!("F{}_{}", inputs.len(), tys.join("_"))
+    }
+    kind => kind.to_string(),
+  }
+}
@@ -1,60 +1,17 @@
-use zhoo_ast::ast::Ty;
-use zhoo_ast::ptr::Fsp;
+use super::resolver::DefId;
 
 use fxhash::FxHashMap;
 
-#[derive(Clone, Debug, Default)]
-struct Scope {
-  decls: FxHashMap<String, Fsp<Ty>>,
-  funs: FxHashMap<String, (Vec<Fsp<Ty>>, Fsp<Ty>)>,
-}
-
-impl Scope {
-  fn decl(&self, name: &str) -> Option<&Fsp<Ty>> {
-    self.decls.get(name)
-  }
-
-  fn fun(&self, name: &str) -> Option<&(Vec<Fsp<Ty>>, Fsp<Ty>)> {
-    self.funs.get(name)
-  }
-
-  fn remove_decl(&mut self, name: &str) -> Option<Fsp<Ty>> {
-    self.decls.remove(name)
-  }
-
-  fn set_decl(&mut self, name: String, ty: Fsp<Ty>) -> Result<(), String> {
-    match self.decls.get(&name) {
-      Some(_) => Err(format!("variable `{name}` already exists")),
-      None => {
-        self.decls.insert(name, ty);
-        Ok(())
-      }
-    }
-  }
-
-  fn set_fun(
-    &mut self,
-    name: String,
-    ty: (Vec<Fsp<Ty>>, Fsp<Ty>),
-  ) -> Result<(), String> {
-    match self.funs.get(&name) {
-      Some(_) => Err(format!("function `{name}` already exists")),
-      None => {
-        self.funs.insert(name, ty);
-        Ok(())
-      }
-    }
-  }
-}
-
+/// the names of the items visible from a scope, a scope is opened by every
+/// function and every block. the overloads of a function share its name
 #[derive(Clone, Debug)]
 pub(crate) struct ScopeMap {
-  maps: Vec<Scope>,
+  maps: Vec<FxHashMap<String, Vec<DefId>>>,
 }
 
 impl ScopeMap {
   pub fn enter_scope(&mut self) {
-    self.maps.push(Scope::default());
+    self.maps.push(FxHashMap::default());
   }
 
   pub fn exit_scope(&mut self) {
@@ -63,51 +20,47 @@ impl ScopeMap {
     }
   }
 
-  pub fn decl(&self, name: &str) -> Option<&Fsp<Ty>> {
+  /// the definitions of the innermost scope which has the name
+  pub fn get(&self, name: &str) -> Option<&[DefId]> {
     for map in self.maps.iter().rev() {
-      if let Some(decl) = map.decl(name) {
-        return Some(decl);
+      if let Some(defs) = map.get(name) {
+        return Some(defs);
       }
     }
 
     None
   }
 
-  pub fn fun(&self, name: &str) -> Option<&(Vec<Fsp<Ty>>, Fsp<Ty>)> {
-    for map in self.maps.iter().rev() {
-      if let Some(fun) = map.fun(name) {
-        return Some(fun);
-      }
-    }
-
-    None
+  /// the definitions of the name in the current scope
+  pub fn get_local(&self, name: &str) -> &[DefId] {
+    self
+      .maps
+      .last()
+      .and_then(|map| map.get(name))
+      .map_or(&[], |defs| defs.as_slice())
   }
 
-  pub fn remove_decl(&mut self, name: &str) -> Option<Fsp<Ty>> {
-    for map in self.maps.iter_mut().rev() {
-      if let Some(decl) = map.remove_decl(name) {
-        return Some(decl);
-      }
-    }
-
-    None
-  }
-
-  pub fn set_decl(&mut self, name: String, ty: Fsp<Ty>) -> Result<(), String> {
+  pub fn set(&mut self, name: String, def: DefId) -> Result<(), String> {
     match self.maps.last_mut() {
-      Some(map) => map.set_decl(name, ty),
-      None => Err(format!("variable `{name}` value do not exist")),
+      Some(map) if map.contains_key(&name) => {
+        Err(format!("`{name}` already exists"))
+      }
+      Some(map) => {
+        map.insert(name, vec![def]);
+        Ok(())
+      }
+      None => Err(format!("`{name}` has no scope")),
     }
   }
 
-  pub fn set_fun(
-    &mut self,
-    name: String,
-    ty: (Vec<Fsp<Ty>>, Fsp<Ty>),
-  ) -> Result<(), String> {
+  /// adds an overload to the functions of the current scope named `name`
+  pub fn set_fun(&mut self, name: String, def: DefId) -> Result<(), String> {
     match self.maps.last_mut() {
-      Some(map) => map.set_fun(name, ty),
-      None => Err(format!("function `{name}` value do not exist")),
+      Some(map) => {
+        map.entry(name).or_default().push(def);
+        Ok(())
+      }
+      None => Err(format!("`{name}` has no scope")),
     }
   }
 }
@@ -115,7 +68,7 @@ impl ScopeMap {
 impl Default for ScopeMap {
   fn default() -> Self {
     Self {
-      maps: vec![Scope::default()],
+      maps: vec![FxHashMap::default()],
     }
   }
 }
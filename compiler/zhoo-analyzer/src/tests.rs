@@ -0,0 +1,321 @@
+use super::analyzer::resolve;
+use super::constant::{eval_vals, Constant};
+use super::num::Num;
+use super::resolver::DefKind;
+
+use zhoo_errors::{Report, Result, SemanticKind};
+use zhoo_parser::parser::parse_source;
+
+const VALS: &str = "
+val B: int = A * 2;
+val A: int = count(3);
+val S: str = \"a\" ++ \"b\";
+
+const fun count(n: int): int {
+  mut i := 0;
+  until i == n -> i += 1;
+  i
+}
+
+fun main(): int {
+  B
+}
+";
+
+const FOLDS: &str = "
+val P: int = (2 + 3) * 2;
+val X: u8 = 200u8 + 100u8;
+val Y: int = X as int;
+val Z: real = (1 + 2) as real / 2.0;
+
+fun main(): int {
+  P + Y
+}
+";
+
+const LITERALS: &str = "
+val U: u64 = 18446744073709551615;
+val M: int = -9223372036854775808;
+val S: s8 = -128s8;
+val H: u8 = 0xff_u8;
+
+fun main(): int {
+  0
+}
+";
+
+const CASTS: &str = "
+val A: char = 0x41 as char;
+val B: char = 0xd800 as char;
+val C: char = -1s8 as char;
+val S: str = 2.5 as str;
+val T: str = 'z' as str;
+val U: str = 18446744073709551615 as str;
+
+fun main(): int {
+  0
+}
+";
+
+const DIVISION_BY_ZERO: &str = "
+val D: int = 10 / (5 - 5);
+
+fun main(): int {
+  D
+}
+";
+
+const SIZES: &str = "
+val N: int = 2 + 1;
+
+const fun twice(n: int): int { n * 2 }
+
+fun first(xs: int[N]): int { xs[0] }
+fun first(): int { 0 }
+
+fun main(): int {
+  imu xs: int[N] = [1, 2, 3];
+  imu ys: int[twice(N)] = [1, 2, 3, 4, 5, 6];
+
+  first(xs) + ys[5]
+}
+";
+
+const LAMBDAS: &str = "
+fun apply(f: Fn(int): int, x: int): int { f(x) }
+
+fun main(): int {
+  imu add: Fn(int): Fn(int): int = fn(x) -> fn(y) -> x + y;
+  imu pair: (int, str) = (add(1)(2), \"a\");
+  imu one := fn() -> 1;
+
+  apply(fn(x) -> x * pair.0, one())
+}
+";
+
+const INDEXES: &str = "
+fun main(): int {
+  imu reals: real[2] = [1.5, 2.5];
+  imu words: str[1] = [\"ab\"];
+  imu r: real = reals[1];
+  imu w: str = words[0][1];
+
+  0
+}
+";
+
+const PRINTS: &str = "
+fun main() {
+  imu pair := (1, 'a');
+  imu reals: real[2] = [1.5, 2.5];
+
+  println(pair);
+  println(\"{reals} and {pair.1}\");
+}
+";
+
+const SYMBOLS: &str = "
+fun area(side: int): int { side * side }
+fun area(width: int, height: int): int { width * height }
+fun area(): int { 0 }
+
+fun main(): int {
+  fun helper(pair: (int, bool), xs: int[3]): int { 1 }
+  fun helper(): int { 2 }
+
+  area(1) + helper()
+}
+";
+
+const SHADOWS: &str = "
+fun main(): int {
+  imu x := 1;
+  mut y: int = x;
+
+  if y == 1 {
+    imu x := 2;
+    y += x;
+  }
+
+  imu x := x + y;
+
+  x
+}
+";
+
+fn eval_source(name: &str, source: &str) -> Result<Vec<(String, Constant)>> {
+  let program = parse_source(name, source);
+
+  resolve(&program).and_then(|resolutions| eval_vals(&program, &resolutions))
+}
+
+fn assert_vals(name: &str, source: &str, expected: &[(&str, Constant)]) {
+  let vals = match eval_source(name, source) {
+    Ok(vals) => vals,
+    Err(_) => panic!("the vals should be evaluated"),
+  };
+
+  for (name, constant) in expected {
+    let val = vals.iter().find(|(val, _)| val == name);
+
+    assert_eq!(val.map(|(_, val)| val), Some(constant), "the val `{name}`");
+  }
+}
+
+#[test]
+fn test_eval_vals() {
+  assert_vals(
+    "vals",
+    VALS,
+    &[
+      ("B", Constant::Int(6, Num::Int)),
+      ("A", Constant::Int(3, Num::Int)),
+      ("S", Constant::Str(String::from("ab"))),
+    ],
+  );
+}
+
+#[test]
+fn test_eval_folds() {
+  assert_vals(
+    "folds",
+    FOLDS,
+    &[
+      ("P", Constant::Int(10, Num::Int)),
+      ("X", Constant::Int(44, Num::U8)),
+      ("Y", Constant::Int(44, Num::Int)),
+      ("Z", Constant::Real(1.5, Num::Real)),
+    ],
+  );
+}
+
+#[test]
+fn test_eval_literals() {
+  assert_vals(
+    "literals",
+    LITERALS,
+    &[
+      ("U", Constant::Int(-1, Num::U64)),
+      ("M", Constant::Int(i64::MIN, Num::Int)),
+      ("S", Constant::Int(-128, Num::S8)),
+      ("H", Constant::Int(255, Num::U8)),
+    ],
+  );
+}
+
+#[test]
+fn test_eval_casts() {
+  assert_vals(
+    "casts",
+    CASTS,
+    &[
+      ("A", Constant::Char('A')),
+      ("B", Constant::Char(char::REPLACEMENT_CHARACTER)),
+      ("C", Constant::Char(char::REPLACEMENT_CHARACTER)),
+      ("S", Constant::Str(String::from("2.5"))),
+      ("T", Constant::Str(String::from("z"))),
+      ("U", Constant::Str(String::from("18446744073709551615"))),
+    ],
+  );
+}
+
+#[test]
+fn test_eval_division_by_zero() {
+  assert!(matches!(
+    eval_source("division_by_zero", DIVISION_BY_ZERO),
+    Err(Report::Semantic(SemanticKind::DivisionByZero(_)))
+  ));
+}
+
+#[test]
+fn test_fold_sizes() {
+  let program = parse_source("sizes", SIZES);
+
+  let resolutions = match resolve(&program) {
+    Ok(resolutions) => resolutions,
+    Err(_) => panic!("the sizes should be folded"),
+  };
+
+  let symbols = resolutions
+    .defs()
+    .filter(|(_, def)| def.name == "first")
+    .map(|(_, def)| def.symbol.as_str())
+    .collect::<Vec<_>>();
+
+  assert_eq!(symbols, ["first$A3_int", "first$void"]);
+}
+
+#[test]
+fn test_check_lambdas_and_fields() {
+  let program = parse_source("lambdas", LAMBDAS);
+
+  assert!(resolve(&program).is_ok());
+}
+
+#[test]
+fn test_check_indexes() {
+  let program = parse_source("indexes", INDEXES);
+
+  assert!(resolve(&program).is_ok());
+}
+
+#[test]
+fn test_check_prints() {
+  let program = parse_source("prints", PRINTS);
+
+  assert!(resolve(&program).is_ok());
+}
+
+#[test]
+fn test_resolve_symbols() {
+  let program = parse_source("symbols", SYMBOLS);
+
+  let resolutions = match resolve(&program) {
+    Ok(resolutions) => resolutions,
+    Err(_) => panic!("the names should be resolved"),
+  };
+
+  let mut symbols = resolutions
+    .defs()
+    .filter(|(_, def)| def.kind == DefKind::Fun)
+    .map(|(_, def)| def.symbol.as_str())
+    .collect::<Vec<_>>();
+
+  symbols.sort_unstable();
+
+  assert_eq!(
+    symbols,
+    [
+      "area$int",
+      "area$int$int",
+      "area$void",
+      "main",
+      "main$helper$1$void",
+      "main$helper$T2_int_bool$A3_int",
+    ]
+  );
+}
+
+#[test]
+fn test_resolve_shadows() {
+  let program = parse_source("shadows", SHADOWS);
+
+  let resolutions = match resolve(&program) {
+    Ok(resolutions) => resolutions,
+    Err(_) => panic!("the names should be resolved"),
+  };
+
+  let defs = SHADOWS
+    .match_indices('x')
+    .map(|(offset, _)| resolutions.def_at(offset as u32))
+    .collect::<Vec<_>>();
+
+  // the outer `x`, the `x` of the block then the `x` which shadows the outer
+  // one, the block is exited before its initializer
+  let (outer, inner, shadow) = (defs[0], defs[2], defs[4]);
+
+  assert!(outer.is_some() && inner.is_some() && shadow.is_some());
+  assert!(outer != inner && outer != shadow && inner != shadow);
+  assert_eq!(defs, [outer, outer, inner, inner, shadow, outer, shadow]);
+  assert_eq!(outer.map(|def| resolutions.references(def).len()), Some(3));
+}
@@ -11,6 +11,12 @@ pub enum Public {
   No,
 }
 
+#[derive(Clone, Debug)]
+pub enum Const {
+  Yes(Span),
+  No,
+}
+
 #[derive(Clone, Debug)]
 pub enum Async {
   Yes(Span),
@@ -204,6 +210,7 @@ pub enum DeclKind {
 #[derive(Clone, Debug)]
 pub struct Fun {
   pub public: Public,
+  pub constness: Const,
   pub asyncness: Async,
   pub unsafeness: Unsafe,
   pub wasm: Wasm,
@@ -213,8 +220,10 @@ pub struct Fun {
 }
 
 impl Fun {
+  #[allow(clippy::too_many_arguments)]
   pub const fn new(
     public: Public,
+    constness: Const,
     asyncness: Async,
     unsafeness: Unsafe,
     wasm: Wasm,
@@ -224,6 +233,7 @@ impl Fun {
   ) -> Self {
     Self {
       public,
+      constness,
       asyncness,
       unsafeness,
       wasm,
@@ -234,6 +244,12 @@ impl Fun {
   }
 }
 
+impl Fun {
+  pub fn is_const(&self) -> bool {
+    matches!(self.constness, Const::Yes(_))
+  }
+}
+
 impl AsTy for Fun {
   fn as_ty(&self) -> Fsp<Ty> {
     self.prototype.as_ty()
@@ -357,9 +373,13 @@ impl Expr {
 #[derive(Clone, Debug)]
 pub enum ExprKind {
   Lit(Fsp<Lit>),
+  /// the segments of an interpolated `str`, the texts are `str` literals and
+  /// the other segments are the values written between braces: `"x = {x}"`
+  Interpolation(Vec<Fsp<Expr>>),
   Identifier(String),
   UnOp(UnOp, Fsp<Expr>),
   BinOp(Fsp<Expr>, BinOp, Fsp<Expr>),
+  Cast(Fsp<Expr>, Fsp<Ty>),
   Call(Fsp<Expr>, Vec<Fsp<Expr>>),
   Decl(Fsp<Decl>),
   Assign(Fsp<Expr>, BinOp, Fsp<Expr>),
@@ -396,11 +416,44 @@ impl Lit {
 #[derive(Clone, Debug)]
 pub enum LitKind {
   Bool(bool),
-  Int(i64),
-  Real(f64),
+  /// an integer, its suffix gives its type: `255u8`. it's kept in an `i128`
+  /// so the largest `u64` and its negation are whole
+  Int(i128, Option<TyKind>),
+  /// a real, its suffix gives its type: `1.5f32`
+  Real(f64, Option<TyKind>),
+  /// a unicode scalar value: `'a'`, `'\u{1f916}'`
+  Char(char),
   Str(String),
 }
 
+impl LitKind {
+  /// the type of an integer, its suffix or `int` for an unsuffixed integer.
+  /// an unsuffixed integer beyond `int` is a `u64`: `18446744073709551615`
+  pub fn int_ty(int: i128, suffix: &Option<TyKind>) -> TyKind {
+    match suffix {
+      Some(ty) => ty.to_owned(),
+      None if int > i64::MAX as i128 => TyKind::U64,
+      None => TyKind::Int,
+    }
+  }
+
+  /// a number takes the type it's given to when it fits in it, an integer
+  /// fits in the integer types whose range holds it and a real in the real
+  /// types
+  pub fn fits(&self, ty: &TyKind) -> bool {
+    match (self, ty.bits()) {
+      (Self::Int(int, _), Some(bits)) if ty.is_integer() => {
+        match ty.is_signed() {
+          true => (-(1 << (bits - 1))..(1 << (bits - 1))).contains(int),
+          false => (0..(1 << bits)).contains(int),
+        }
+      }
+      (Self::Real(..), _) => ty.is_float(),
+      _ => false,
+    }
+  }
+}
+
 pub type UnOp = Spanned<UnOpKind>;
 
 #[derive(Clone, Debug)]
@@ -415,6 +468,7 @@ pub type BinOp = Spanned<BinOpKind>;
 pub enum BinOpKind {
   Add,    // +
   Sub,    // -
+  Concat, // ++
   Mul,    // *
   Div,    // /
   Rem,    // %
@@ -431,7 +485,6 @@ pub enum BinOpKind {
   BitAnd, // &
   BitOr,  // |
   BitXor, // ^
-  As,     // as
   Range,  // ..
 }
 
@@ -455,12 +508,20 @@ pub trait AsTy: Sized {
   fn as_ty(&self) -> Fsp<Ty>;
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Ty {
   pub kind: TyKind,
   pub span: Span,
 }
 
+/// two types are equal whatever their spans, so `(int, char)` is the type of
+/// every tuple of an `int` and a `char`
+impl PartialEq for Ty {
+  fn eq(&self, other: &Self) -> bool {
+    self.kind == other.kind
+  }
+}
+
 impl Ty {
   pub const VOID: Self = Self::new(TyKind::Void, Span::ZERO);
 
@@ -472,6 +533,12 @@ impl Ty {
 
   pub const STR: Self = Self::new(TyKind::Str, Span::ZERO);
 
+  pub const CHAR: Self = Self::new(TyKind::Char, Span::ZERO);
+
+  pub const U64: Self = Self::new(TyKind::U64, Span::ZERO);
+
+  pub const F32: Self = Self::new(TyKind::F32, Span::ZERO);
+
   pub const INFER: Self = Self::new(TyKind::Infer, Span::ZERO);
 
   pub const fn new(kind: TyKind, span: Span) -> Self {
@@ -493,6 +560,10 @@ impl Ty {
   pub fn is_int(&self) -> bool {
     self.kind.is_int()
   }
+
+  pub fn is_integer(&self) -> bool {
+    self.kind.is_integer()
+  }
 }
 
 impl From<Ty> for Fsp<Ty> {
@@ -507,6 +578,8 @@ impl From<&Ty> for Fsp<Ty> {
   }
 }
 
+/// `int` is a signed integer of 64 bits and `real` a float of 64 bits, they
+/// are also named `s64` and `f64`
 #[derive(Clone, Debug, PartialEq)]
 pub enum TyKind {
   Void,
@@ -514,15 +587,143 @@ pub enum TyKind {
   Int,
   Real,
   Str,
+  Char,
+  S8,
+  S16,
+  S32,
+  U8,
+  U16,
+  U32,
+  U64,
+  F32,
   Infer,
   Fn(Vec<Fsp<Ty>>, Fsp<Ty>),
-  Array(Fsp<Ty>, Option<i64>),
+  Array(Fsp<Ty>, Option<Size>),
   Tuple(Vec<Fsp<Ty>>),
 }
 
+/// the size of an array type, a constant expression is folded into its value
+/// by the analyzer: `int[N * 2]`
+#[derive(Clone, Debug)]
+pub enum Size {
+  Int(i64),
+  Expr(Fsp<Expr>),
+}
+
+impl Size {
+  /// an unsuffixed integer literal is the size itself
+  pub fn new(expr: Fsp<Expr>) -> Self {
+    match &expr.kind {
+      ExprKind::Lit(lit) => match lit.kind {
+        LitKind::Int(int, None) if int <= i64::MAX as i128 => {
+          Self::Int(int as i64)
+        }
+        _ => Self::Expr(expr),
+      },
+      _ => Self::Expr(expr),
+    }
+  }
+}
+
+/// an expression not folded yet is only equal to itself
+impl PartialEq for Size {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::Int(lhs), Self::Int(rhs)) => lhs == rhs,
+      (Self::Expr(lhs), Self::Expr(rhs)) => lhs.span == rhs.span,
+      _ => false,
+    }
+  }
+}
+
 impl TyKind {
+  /// the type named by the suffix of a number literal
+  pub fn from_suffix(suffix: &str) -> Option<Self> {
+    match suffix {
+      "s8" => Some(Self::S8),
+      "s16" => Some(Self::S16),
+      "s32" => Some(Self::S32),
+      "s64" => Some(Self::Int),
+      "u8" => Some(Self::U8),
+      "u16" => Some(Self::U16),
+      "u32" => Some(Self::U32),
+      "u64" => Some(Self::U64),
+      "f32" => Some(Self::F32),
+      "f64" => Some(Self::Real),
+      _ => None,
+    }
+  }
+
   fn is_numeric(&self) -> bool {
-    matches!(self, Self::Int | Self::Real)
+    self.is_integer() || self.is_float()
+  }
+
+  /// `int` and the sized integers
+  pub fn is_integer(&self) -> bool {
+    self.is_signed()
+      || matches!(self, Self::U8 | Self::U16 | Self::U32 | Self::U64)
+  }
+
+  pub fn is_signed(&self) -> bool {
+    matches!(self, Self::Int | Self::S8 | Self::S16 | Self::S32)
+  }
+
+  pub fn is_float(&self) -> bool {
+    matches!(self, Self::Real | Self::F32)
+  }
+
+  /// the size of a number type in bits
+  pub fn bits(&self) -> Option<u32> {
+    match self {
+      Self::S8 | Self::U8 => Some(8),
+      Self::S16 | Self::U16 => Some(16),
+      Self::S32 | Self::U32 | Self::F32 => Some(32),
+      Self::Int | Self::U64 | Self::Real => Some(64),
+      _ => None,
+    }
+  }
+
+  /// a number is implicitly widened to a larger type of its kind, an unsigned
+  /// integer is also widened to a larger signed integer
+  pub fn widens_to(&self, ty: &Self) -> bool {
+    let (Some(from), Some(to)) = (self.bits(), ty.bits()) else {
+      return false;
+    };
+
+    if self.is_float() || ty.is_float() {
+      return self.is_float() && ty.is_float() && from < to;
+    }
+
+    from < to && (self.is_signed() == ty.is_signed() || ty.is_signed())
+  }
+
+  /// `as` converts a number to another number, an integer to a `bool` or a
+  /// `bool` to an integer, a `char` to an integer and an integer to a `char`.
+  /// a printable value is converted to a `str`
+  pub fn casts_to(&self, ty: &Self) -> bool {
+    self == ty
+      || self.is_numeric() && ty.is_numeric()
+      || self.is_integer() && ty.is_boolean()
+      || self.is_boolean() && ty.is_integer()
+      || self.is_char() && ty.is_integer()
+      || self.is_integer() && ty.is_char()
+      || self.is_printable() && matches!(ty, Self::Str)
+  }
+
+  pub fn is_char(&self) -> bool {
+    matches!(self, Self::Char)
+  }
+
+  /// the types whose values can be written in a `str`, an array or a tuple
+  /// is written from its elements
+  pub fn is_printable(&self) -> bool {
+    match self {
+      Self::Array(ty, _) => ty.kind.is_printable(),
+      Self::Tuple(tys) => tys.iter().all(|ty| ty.kind.is_printable()),
+      ty => {
+        ty.is_numeric() || matches!(ty, Self::Bool | Self::Char | Self::Str)
+      }
+    }
   }
 
   fn is_boolean(&self) -> bool {
@@ -532,4 +733,8 @@ impl TyKind {
   pub fn is_int(&self) -> bool {
     matches!(self, Self::Int)
   }
+
+  pub fn is_void(&self) -> bool {
+    matches!(self, Self::Void)
+  }
 }
@@ -1,7 +1,7 @@
 use super::ast::{
-  Arg, Async, BinOpKind, Block, Decl, Expr, ExprKind, Ext, Fun, Lit, LitKind,
-  Mutability, Pattern, PatternKind, Program, Prototype, Public, ReturnTy, Stmt,
-  StmtKind, Ty, TyKind, UnOpKind, Unit, Unsafe, Wasm,
+  Arg, Async, BinOpKind, Block, Const, Decl, Expr, ExprKind, Ext, Fun, Lit,
+  LitKind, Mutability, Pattern, PatternKind, Program, Prototype, Public,
+  ReturnTy, Size, Stmt, StmtKind, Ty, TyKind, UnOpKind, Unit, Unsafe, Wasm,
 };
 
 use std::fmt;
@@ -30,6 +30,15 @@ impl fmt::Display for Public {
   }
 }
 
+impl fmt::Display for Const {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Yes(_) => write!(f, "const"),
+      Self::No => write!(f, ""),
+    }
+  }
+}
+
 impl fmt::Display for Async {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
@@ -137,6 +146,11 @@ impl fmt::Display for Fun {
       Public::No => write!(f, "")?,
     };
 
+    match &self.constness {
+      Const::Yes(_) => write!(f, "const ").ok(),
+      Const::No => write!(f, "").ok(),
+    };
+
     match &self.asyncness {
       Async::Yes(_) => write!(f, "async ").ok(),
       Async::No => write!(f, "").ok(),
@@ -225,10 +239,23 @@ impl fmt::Display for ExprKind {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
       Self::Lit(lit) => write!(f, "{lit}"),
+      Self::Interpolation(segments) => {
+        write!(f, "\"")?;
+
+        for segment in segments {
+          match &segment.kind {
+            Self::Lit(lit) => write!(f, "{lit}")?,
+            _ => write!(f, "{{{segment}}}")?,
+          }
+        }
+
+        write!(f, "\"")
+      }
       Self::Identifier(identifier) => write!(f, "{identifier}"),
       Self::Call(callee, args) => write!(f, "{callee}({})", Sep(args, ", ")),
       Self::UnOp(op, rhs) => write!(f, "{}({})", op.node, rhs),
       Self::BinOp(lhs, op, rhs) => write!(f, "({lhs} {op} {rhs})"),
+      Self::Cast(expr, ty) => write!(f, "({expr} as {ty})"),
       Self::Assign(lhs, op, rhs) => write!(f, "{lhs} {op} {rhs}"),
       Self::AssignOp(lhs, op, rhs) => write!(f, "{lhs} {op} {rhs}"),
       Self::Block(body) => write!(f, "{body}"),
@@ -279,13 +306,29 @@ impl fmt::Display for LitKind {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
       Self::Bool(boolean) => write!(f, "{boolean}"),
-      Self::Int(int) => write!(f, "{int}"),
-      Self::Real(real) => write!(f, "{real}"),
+      Self::Int(int, None) => write!(f, "{int}"),
+      Self::Int(int, Some(ty)) => write!(f, "{int}{}", Suffix(ty)),
+      Self::Real(real, None) => write!(f, "{real}"),
+      Self::Real(real, Some(ty)) => write!(f, "{real}{}", Suffix(ty)),
+      Self::Char(char) => write!(f, "{char:?}"),
       Self::Str(string) => write!(f, "{string}"),
     }
   }
 }
 
+/// the suffix of a number literal, `int` and `real` are `s64` and `f64`
+struct Suffix<'a>(&'a TyKind);
+
+impl<'a> fmt::Display for Suffix<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.0 {
+      TyKind::Int => write!(f, "s64"),
+      TyKind::Real => write!(f, "f64"),
+      ty => write!(f, "{ty}"),
+    }
+  }
+}
+
 impl fmt::Display for BinOpKind {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
@@ -308,7 +351,7 @@ impl fmt::Display for BinOpKind {
       Self::BitOr => write!(f, "|"),
       Self::BitXor => write!(f, "^"),
       Self::Range => write!(f, ".."),
-      Self::As => write!(f, "as"),
+      Self::Concat => write!(f, "++"),
     }
   }
 }
@@ -336,6 +379,15 @@ impl fmt::Display for TyKind {
       Self::Int => write!(f, "int"),
       Self::Real => write!(f, "real"),
       Self::Str => write!(f, "str"),
+      Self::Char => write!(f, "char"),
+      Self::S8 => write!(f, "s8"),
+      Self::S16 => write!(f, "s16"),
+      Self::S32 => write!(f, "s32"),
+      Self::U8 => write!(f, "u8"),
+      Self::U16 => write!(f, "u16"),
+      Self::U32 => write!(f, "u32"),
+      Self::U64 => write!(f, "u64"),
+      Self::F32 => write!(f, "f32"),
       Self::Infer => write!(f, "infer"),
       Self::Fn(args, ty) => write!(f, "Fn({}): {ty}", Sep(args, ", ")),
       Self::Array(indexed, maybe_size) => {
@@ -349,3 +401,12 @@ impl fmt::Display for TyKind {
     }
   }
 }
+
+impl fmt::Display for Size {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Int(int) => write!(f, "{int}"),
+      Self::Expr(expr) => write!(f, "{expr}"),
+    }
+  }
+}
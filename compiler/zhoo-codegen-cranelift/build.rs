@@ -0,0 +1,73 @@
+//! build the core library of `library/core` as a static library, it is
+//! embedded into the compiler and extracted when a program is linked. the
+//! sources are embedded too, the core library of a cross target is built
+//! from them
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Command;
+
+fn main() {
+  let path_manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+  let path_source = path_manifest.join("../../library/core/src");
+  let path_output = PathBuf::from(env::var("OUT_DIR").unwrap());
+  let path_library_core = path_output.join("libzhoo_core.a");
+
+  let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+  let target = env::var("TARGET").unwrap();
+  let opt_level = env::var("OPT_LEVEL").unwrap_or_else(|_| String::from("0"));
+
+  let status = Command::new(rustc)
+    .args(["--edition", "2021", "--crate-type", "staticlib"])
+    .args(["--crate-name", "zhoo_core", "--target", &target])
+    .arg(format!("-Copt-level={opt_level}"))
+    .arg("-o")
+    .arg(&path_library_core)
+    .arg(path_source.join("lib.rs"))
+    .status()
+    .expect("run rustc");
+
+  if !status.success() {
+    panic!("could not build the core library");
+  }
+
+  // the hash names the cache directory of the extracted library, a new core
+  // library is never shadowed by a stale one
+  let mut hasher = DefaultHasher::new();
+
+  fs::read(&path_library_core)
+    .expect("read the core library")
+    .hash(&mut hasher);
+
+  println!("cargo:rustc-env=ZHOO_CORE_HASH={:016x}", hasher.finish());
+  println!("cargo:rustc-env=ZHOO_CORE_OPT_LEVEL={opt_level}");
+
+  let mut sources = fs::read_dir(&path_source)
+    .expect("read the sources of the core library")
+    .map(|entry| entry.expect("read a source of the core library").path())
+    .collect::<Vec<_>>();
+
+  sources.sort();
+
+  let sources = sources
+    .iter()
+    .map(|path| {
+      format!(
+        "({:?}, include_str!({:?})),\n",
+        path.file_name().unwrap(),
+        path
+          .canonicalize()
+          .expect("locate a source of the core library"),
+      )
+    })
+    .collect::<String>();
+
+  fs::write(path_output.join("sources.rs"), format!("&[\n{sources}]\n"))
+    .expect("write the sources of the core library");
+
+  println!("cargo:rerun-if-changed={}", path_source.display());
+  println!("cargo:rerun-if-changed=build.rs");
+}
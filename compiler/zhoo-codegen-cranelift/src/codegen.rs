@@ -1,69 +1,108 @@
 use super::interface::{
-  CompiledFunction, DataBuilder, TypeBuilder, VariableBuilder,
+  CompiledFunction, CompiledVal, DataBuilder, TypeBuilder, VariableBuilder,
 };
 
+use super::debuginfo::DebugContext;
+use super::disasm;
+use super::linker::Linker;
+use super::runtime;
+use super::settings::{Emit, Mode, Settings};
 use super::translator::Translator;
 
-use zhoo_analyzer::builtins::{c_builtins, io_builtins, sys_builtins, Builtin};
+use zhoo_analyzer::builtins::{builtins, Builtin, Proto};
+use zhoo_analyzer::constant::Constant;
+use zhoo_ast::ast::{self, Program};
 
-use zhoo_ast::ast::{
-  AsTy, Ext, Fun, Program, Prototype, ReturnTy, Stmt, StmtKind,
-};
+use zhoo_mir::lower;
+use zhoo_mir::mir::{self, Body, Ty};
+use zhoo_mir::optimizer;
 
-use zhoo_helper::constant::{
-  COMPILER_NAME, ENTRY_POINT, PATH_LIBRARY, PATH_LIBRARY_CORE,
-  PATH_OUTPUT_DIRECTORY,
-};
+use zhoo_helper::constant::{COMPILER_NAME, ENTRY_POINT, PATH_OUTPUT_DIRECTORY};
 
+use zhoo_errors::Report;
 use zhoo_helper::pack;
 
 use cranelift::prelude::{
-  types, AbiParam, Block as CBlock, Configurable, FunctionBuilder,
-  FunctionBuilderContext, InstBuilder, Variable,
+  Configurable, FunctionBuilder, FunctionBuilderContext, Signature,
 };
 
-use cranelift_codegen::ir::GlobalValue;
-use cranelift_codegen::settings::Flags;
-use cranelift_codegen::{settings, Context};
-use cranelift_module::{FuncId, Linkage, Module};
+use cranelift_codegen::ir::{Endianness, UserFuncName};
+use cranelift_codegen::settings::{Flags, SetError};
+use cranelift_codegen::{isa, settings, Context};
+use cranelift_module::{DataContext, DataId, FuncId, Linkage, Module};
 use cranelift_object::{ObjectBuilder, ObjectModule};
-use cranelift_preopt::optimize;
 use fxhash::FxHashMap;
+use target_lexicon::Triple;
 
-pub type BuildResult = Result<Box<dyn FnOnce()>, String>;
+use std::str::FromStr;
 
-pub fn generate(program: &Program) -> Codegen {
-  Codegen::new().generate(program)
+pub type BuildResult = Result<Box<dyn FnOnce() -> Result<(), Report>>, String>;
+
+pub fn generate(
+  program: &Program,
+  mir: mir::Program,
+  settings: &Settings,
+) -> Result<Codegen, String> {
+  Codegen::new(settings)?.generate(program, mir)
 }
 
 pub struct Codegen {
   function_builder_context: FunctionBuilderContext,
   module: ObjectModule,
-  blocks: Vec<CBlock>,
   context: Context,
   ir: String,
+  asm: String,
+  ast: String,
+  mir: String,
   funs: FxHashMap<String, CompiledFunction>,
-  globals: FxHashMap<String, GlobalValue>,
-  vars: FxHashMap<String, Variable>,
+  globals: FxHashMap<String, DataId>,
+  vals: FxHashMap<String, CompiledVal>,
   data_builder: DataBuilder,
   variable_builder: VariableBuilder,
+  is_cross: bool,
+  linker: Option<String>,
+  emit: Vec<Emit>,
+  mode: Mode,
+  name: String,
+  out_dir: String,
+  debug: bool,
+  debug_context: Option<DebugContext>,
 }
 
 impl Codegen {
-  fn new() -> Self {
+  fn new(codegen_settings: &Settings) -> Result<Self, String> {
     let mut flag_builder = settings::builder();
 
-    flags_settings(&mut flag_builder);
+    let mut isa_builder = match &codegen_settings.target {
+      Some(target) => {
+        let triple = Triple::from_str(target)
+          .map_err(|error| format!("invalid target `{target}`: {error}"))?;
+
+        isa::lookup(triple)
+          .map_err(|error| format!("unsupported target `{target}`: {error}"))?
+      }
+      None => cranelift_native::builder()?,
+    };
+
+    flags_settings(
+      &mut flag_builder,
+      &mut isa_builder,
+      codegen_settings.mode,
+      &codegen_settings.flags,
+    )?;
+
+    let isa = isa_builder
+      .finish(Flags::new(flag_builder))
+      .map_err(|error| format!("{error}"))?;
 
-    let isa_builder = cranelift_native::builder().unwrap();
-    let isa = isa_builder.finish(Flags::new(flag_builder)).unwrap();
+    let is_cross = *isa.triple() != Triple::host();
 
     let object_builder = ObjectBuilder::new(
       isa,
       String::from(COMPILER_NAME),
       cranelift_module::default_libcall_names(),
     )
-    .unwrap();
+    .map_err(|error| format!("{error}"))?;
 
     let module = ObjectModule::new(object_builder);
 
@@ -71,47 +110,167 @@ impl Codegen {
       context: module.make_context(),
       function_builder_context: FunctionBuilderContext::new(),
       module,
-      blocks: vec![],
       ir: String::new(),
+      asm: String::new(),
+      ast: String::new(),
+      mir: String::new(),
       funs: FxHashMap::default(),
       globals: FxHashMap::default(),
-      vars: FxHashMap::default(),
+      vals: FxHashMap::default(),
       data_builder: DataBuilder::default(),
       variable_builder: VariableBuilder::default(),
+      is_cross,
+      linker: codegen_settings.linker.to_owned(),
+      emit: codegen_settings.emit.to_owned(),
+      mode: codegen_settings.mode,
+      name: codegen_settings
+        .name
+        .to_owned()
+        .unwrap_or_else(|| String::from(ENTRY_POINT)),
+      out_dir: codegen_settings
+        .out_dir
+        .to_owned()
+        .unwrap_or_else(|| String::from(PATH_OUTPUT_DIRECTORY)),
+      debug: codegen_settings.debug,
+      debug_context: None,
     };
 
     register_builtins(&mut me);
 
-    me
+    Ok(me)
   }
 
-  fn generate(mut self, program: &Program) -> Self {
-    for stmt in &program.stmts {
-      self.generate_stmt(stmt);
+  fn generate(
+    mut self,
+    program: &Program,
+    mut mir: mir::Program,
+  ) -> Result<Self, String> {
+    if self.emit.contains(&Emit::Ast) {
+      self.ast = program.to_string();
     }
 
-    self
-  }
+    // the constants are only folded by the mir, it knows the unsigned and the
+    // sized integers
+    if self.mode == Mode::Release {
+      optimizer::optimize(&mut mir);
+    }
 
-  fn generate_stmt(&mut self, stmt: &Stmt) {
-    match &stmt.kind {
-      StmtKind::Ext(ext) => self.generate_stmt_ext(ext),
-      StmtKind::Fun(fun) => self.generate_stmt_fun(fun),
-      _ => unimplemented!(),
+    if self.emit.contains(&Emit::Mir) {
+      self.mir = mir.to_string();
     }
+
+    if self.debug {
+      self.debug_context = Some(DebugContext::new(self.module.isa(), program));
+    }
+
+    self.generate_vals(&mir.vals)?;
+
+    for ext in &mir.externs {
+      self.generate_prototype(
+        &ext.name,
+        &ext.inputs,
+        ext.output,
+        Linkage::Import,
+      )?;
+    }
+
+    // the functions are declared before their bodies, a function can call
+    // the functions defined after it
+    for body in &mir.bodies {
+      let inputs = body.locals[..body.inputs]
+        .iter()
+        .map(|local| local.ty)
+        .collect::<Vec<_>>();
+
+      let linkage = match body.is_local {
+        true => Linkage::Local,
+        false => Linkage::Export,
+      };
+
+      self.generate_prototype(&body.name, &inputs, body.output, linkage)?;
+    }
+
+    for body in &mir.bodies {
+      self.generate_body(body)?;
+    }
+
+    Ok(self)
   }
 
-  fn generate_stmt_ext(&mut self, ext: &Ext) {
-    let _ = self.generate_prototype(&ext.prototype, Linkage::Import);
+  /// the top-level `val`s are defined as read-only data
+  fn generate_vals(
+    &mut self,
+    vals: &[(String, Constant)],
+  ) -> Result<(), String> {
+    let endianness = self.module.isa().endianness();
+
+    for (name, constant) in vals {
+      // a `str` is the address of its header, which is defined with its bytes
+      if let Constant::Str(string) = constant {
+        let data_id = DataBuilder::define_str(&mut self.module, name, string)?;
+
+        self.vals.insert(
+          name.to_string(),
+          CompiledVal {
+            id: data_id,
+            constant: constant.to_owned(),
+          },
+        );
+
+        continue;
+      }
+
+      let (bytes, align) = match constant {
+        Constant::Bool(boolean) => (vec![*boolean as u8], 1),
+        Constant::Int(int, _) => match endianness {
+          Endianness::Little => (int.to_le_bytes().to_vec(), 8),
+          Endianness::Big => (int.to_be_bytes().to_vec(), 8),
+        },
+        Constant::Real(real, _) => match endianness {
+          Endianness::Little => (real.to_le_bytes().to_vec(), 8),
+          Endianness::Big => (real.to_be_bytes().to_vec(), 8),
+        },
+        Constant::Char(char) => match endianness {
+          Endianness::Little => ((*char as u32).to_le_bytes().to_vec(), 4),
+          Endianness::Big => ((*char as u32).to_be_bytes().to_vec(), 4),
+        },
+        Constant::Str(_) => unreachable!(),
+      };
+
+      let data_id = self
+        .module
+        .declare_data(name, Linkage::Local, false, false)
+        .map_err(|error| format!("{error}"))?;
+
+      let mut data_context = DataContext::new();
+
+      data_context.set_align(align);
+      data_context.define(bytes.into_boxed_slice());
+
+      self
+        .module
+        .define_data(data_id, &data_context)
+        .map_err(|error| format!("{error}"))?;
+
+      self.vals.insert(
+        name.to_string(),
+        CompiledVal {
+          id: data_id,
+          constant: constant.to_owned(),
+        },
+      );
+    }
+
+    Ok(())
   }
 
   fn generate_prototype(
     &mut self,
-    prototype: &Prototype,
+    fun_name: &str,
+    inputs: &[Ty],
+    output: Ty,
     linkage: Linkage,
   ) -> Result<FuncId, String> {
-    let fun_name = &prototype.name.to_string();
-    let inputs = &prototype.inputs;
     let inputs_len = inputs.len();
 
     match self.funs.get(fun_name) {
@@ -132,21 +291,7 @@ impl Codegen {
         Ok(compiled_function.id)
       }
       None => {
-        let mut signature = self.module.make_signature();
-
-        for input in inputs.iter() {
-          let clif_type = TypeBuilder::from(&mut self.module, &input.ty);
-
-          signature.params.push(AbiParam::new(clif_type));
-        }
-
-        if let ReturnTy::Ty(ty) = &prototype.output {
-          let clif_type = TypeBuilder::from(&mut self.module, ty);
-
-          signature.returns.push(AbiParam::new(clif_type));
-        } else {
-          signature.returns.push(AbiParam::new(types::I64));
-        }
+        let signature = self.make_signature(inputs, output);
 
         let func_id =
           match self.module.declare_function(fun_name, linkage, &signature) {
@@ -164,210 +309,268 @@ impl Codegen {
     }
   }
 
-  fn generate_stmt_fun(&mut self, fun: &Fun) {
-    self.generate_fun(fun);
-  }
-
-  fn generate_fun(&mut self, fun: &Fun) {
-    let inputs = &fun.prototype.inputs;
-    let signature = &mut self.context.func.signature;
+  /// a `void` function returns a zero
+  fn make_signature(&mut self, inputs: &[Ty], output: Ty) -> Signature {
+    let mut signature = self.module.make_signature();
 
     for input in inputs {
-      let clif_type = TypeBuilder::from(&mut self.module, &input.ty);
+      let param = TypeBuilder::abi_param(&mut self.module, *input);
 
-      signature.params.push(AbiParam::new(clif_type));
+      signature.params.push(param);
     }
 
-    let clif_type =
-      TypeBuilder::from(&mut self.module, &fun.prototype.output.as_ty());
-
-    signature.returns.push(AbiParam::new(clif_type));
+    let param = TypeBuilder::abi_param(&mut self.module, output);
 
-    let func_name = fun.prototype.name.to_string();
-
-    let func_id = self
-      .generate_prototype(&fun.prototype, Linkage::Export)
-      .unwrap();
-
-    let mut builder = FunctionBuilder::new(
-      &mut self.context.func,
-      &mut self.function_builder_context,
-    );
+    signature.returns.push(param);
+    signature
+  }
 
-    for builtin in c_builtins() {
-      register_builtin_c(
-        &mut self.module,
-        &mut builder,
-        &mut self.funs,
-        builtin,
-      );
-    }
+  fn generate_body(&mut self, body: &Body) -> Result<(), String> {
+    let func_name = body.name.to_string();
 
-    let entry_block = builder.create_block();
-    builder.append_block_params_for_function_params(entry_block);
-    builder.switch_to_block(entry_block);
-    builder.seal_block(entry_block);
+    let func_id = match self.funs.get_mut(&func_name) {
+      Some(compiled_function) if !compiled_function.is_defined => {
+        compiled_function.is_defined = true;
+        compiled_function.id
+      }
+      _ => return Err(format!("redefinition of function: {func_name}")),
+    };
 
-    for (i, input) in inputs.iter().enumerate() {
-      let value = builder.block_params(entry_block)[i];
+    self.context.func.signature = self
+      .module
+      .declarations()
+      .get_function_decl(func_id)
+      .signature
+      .to_owned();
 
-      let variable =
-        self
-          .variable_builder
-          .create_variable(&mut builder, value, types::I64);
+    self.context.func.name = UserFuncName::user(0, func_id.as_u32());
 
-      self.vars.insert(input.pattern.to_string(), variable);
+    if self.debug {
+      self.context.func.collect_debug_info();
     }
 
-    if let Some(ref mut compiled_function) = self.funs.get_mut(&func_name) {
-      compiled_function.is_defined = true;
-    }
+    let builder = FunctionBuilder::new(
+      &mut self.context.func,
+      &mut self.function_builder_context,
+    );
 
     let mut translator = Translator {
       builder,
       module: &mut self.module,
-      funs: &mut self.funs,
+      funs: &self.funs,
       globals: &mut self.globals,
-      vars: &mut self.vars,
-      ty: types::I64,
-      blocks: &mut self.blocks,
+      vals: &self.vals,
+      body,
+      blocks: vec![],
+      vars: vec![],
       data_builder: &mut self.data_builder,
       variable_builder: &mut self.variable_builder,
     };
 
-    let return_value = match translator.translate(&fun.body) {
-      Ok(value) => value,
-      Err(_) => {
-        translator.builder.finalize();
-        self.funs.remove(&func_name);
-
-        return; // todo (?): error
-      }
-    };
-
-    translator.builder.ins().return_(&[return_value]);
+    translator.translate()?;
     translator.builder.finalize();
-    optimize(&mut self.context, self.module.isa()).unwrap();
 
-    self.ir = self.context.func.display().to_string();
+    let labels = std::mem::take(&mut self.variable_builder.labels);
+
+    self
+      .ir
+      .push_str(&format!("; {func_name}\n{}\n", self.context.func));
 
     self
       .module
       .define_function(func_id, &mut self.context)
-      .unwrap();
+      .map_err(|error| format!("{error}"))?;
+
+    if let Some(debug_context) = &mut self.debug_context {
+      debug_context.define_function(
+        self.module.isa(),
+        func_id,
+        body,
+        &self.context,
+        &labels,
+      );
+    }
+
+    if self.emit.contains(&Emit::Asm) {
+      if let Some(compiled_code) = self.context.compiled_code() {
+        let asm = disasm::disassemble(
+          self.module.isa(),
+          &func_name,
+          compiled_code.buffer.data(),
+        )?;
+
+        self.asm.push_str(&asm);
+        self.asm.push('\n');
+      }
+    }
 
     self.module.clear_context(&mut self.context);
+
+    Ok(())
   }
 
-  pub fn build(self, output_ir: bool) -> BuildResult {
-    let object = self.module.finish();
-    let bytes = object.emit().unwrap();
+  pub fn build(mut self, output_ir: bool) -> BuildResult {
+    let triple = self.module.isa().triple().clone();
+    let is_pic = self.module.isa().flags().is_pic();
+    let mut object = self.module.finish();
+
+    if let Some(debug_context) = self.debug_context.take() {
+      debug_context.emit(&mut object)?;
+    }
+
+    let bytes = object.emit().map_err(|error| format!("{error}"))?;
 
     Ok(Box::new(move || {
-      let path_object_file = format!("{PATH_OUTPUT_DIRECTORY}/{ENTRY_POINT}.o");
-      let path_core_lib = format!("{PATH_LIBRARY}/{PATH_LIBRARY_CORE}");
-      let path_exe_file = format!("{PATH_OUTPUT_DIRECTORY}/{ENTRY_POINT}");
+      let path_exe_file = format!("{}/{}", self.out_dir, self.name);
+      let path_file =
+        |emit: Emit| format!("{path_exe_file}.{}", emit.extension());
+      let path_object_file = path_file(Emit::Obj);
+      let should_link = self.emit.contains(&Emit::Exe);
 
-      pack::make_dir(PATH_OUTPUT_DIRECTORY);
-      pack::make_file(&path_object_file, &bytes);
+      pack::make_dir(&self.out_dir);
 
-      pack::make_exe_with_link(
-        &path_object_file,
-        &path_core_lib,
-        &path_exe_file,
-      );
+      if should_link || self.emit.contains(&Emit::Obj) {
+        pack::make_file("obj", &path_object_file, &bytes);
+      }
+
+      if self.emit.contains(&Emit::Clif) {
+        pack::make_file("clif", &path_file(Emit::Clif), self.ir.as_bytes());
+      }
+
+      if self.emit.contains(&Emit::Asm) {
+        pack::make_file("asm", &path_file(Emit::Asm), self.asm.as_bytes());
+      }
+
+      if self.emit.contains(&Emit::Ast) {
+        pack::make_file("ast", &path_file(Emit::Ast), self.ast.as_bytes());
+      }
+
+      if self.emit.contains(&Emit::Mir) {
+        pack::make_file("mir", &path_file(Emit::Mir), self.mir.as_bytes());
+      }
+
+      // the host core library is embedded, the one of a cross target is built
+      // from the embedded sources the first time it's linked
+      let path_library_core = || {
+        let path = match self.is_cross {
+          true => runtime::library_core_cross(&triple)?,
+          false => runtime::library_core()?,
+        };
+
+        Ok(path.display().to_string())
+      };
+
+      let linked =
+        match (
+          should_link,
+          Linker::new(self.linker.as_deref(), &triple, is_pic),
+        ) {
+          (false, _) => Ok(()),
+          (true, Some(linker)) => pack::make_exe_with_link(
+            || {
+              linker.link(
+                &[&path_object_file, &path_library_core()?],
+                &path_exe_file,
+              )
+            },
+            &path_exe_file,
+          ),
+          (true, None) => {
+            pack::skip_exe(&format!(
+              "no linker given for the cross target `{triple}`"
+            ));
+
+            Ok(())
+          }
+        };
+
+      pack::make_end();
 
       if output_ir {
         println!("\n{}", self.ir);
       }
+
+      linked.map_err(Report::Link)
     }))
   }
 
+  /// declares a builtin of the core library or the libc, the signature
+  /// follows the types of its prototype, a `void` builtin returns nothing.
+  /// its small integers are extended like the ones of a function
   fn register_builtin(&mut self, builtin: Builtin) {
-    let inputs = builtin
-      .proto
-      .0
-      .iter()
-      .enumerate()
-      .map(|(x, input)| (format!("_input{x}"), input))
-      .collect::<Vec<_>>();
+    let Proto(inputs, output) = &builtin.proto;
+    let mut signature = self.module.make_signature();
+
+    let lower_ty = |ty: &ast::Ty| {
+      lower::lower_ty(ty).unwrap_or_else(|_| {
+        panic!("the builtin {} takes a scalar type", builtin.name)
+      })
+    };
 
-    for _ in inputs.iter() {
-      let clif_type = self.module.target_config().pointer_type();
+    for input in inputs {
+      let param = TypeBuilder::abi_param(&mut self.module, lower_ty(input));
 
-      self
-        .context
-        .func
-        .signature
-        .params
-        .push(AbiParam::new(clif_type));
+      signature.params.push(param);
     }
 
-    let clif_type = self.module.target_config().pointer_type();
+    if !output.kind.is_void() {
+      let param = TypeBuilder::abi_param(&mut self.module, lower_ty(output));
 
-    self
-      .context
-      .func
-      .signature
-      .returns
-      .push(AbiParam::new(clif_type));
+      signature.returns.push(param);
+    }
 
     let func_id = self
       .module
-      .declare_function(
-        &builtin.name,
-        Linkage::Import,
-        &self.context.func.signature,
-      )
-      .unwrap();
+      .declare_function(&builtin.name, Linkage::Import, &signature)
+      .unwrap_or_else(|_| panic!("declare {} function", builtin.name));
 
     self.funs.insert(
       builtin.name,
       CompiledFunction::new(func_id, false, inputs.len()),
     );
-
-    self.module.clear_context(&mut self.context);
   }
 }
 
 // @see https://docs.rs/cranelift/latest/cranelift/prelude/settings/struct.Flags.html
-fn flags_settings(flag_builder: &mut settings::Builder) {
+fn flags_settings(
+  flag_builder: &mut settings::Builder,
+  isa_builder: &mut isa::Builder,
+  mode: Mode,
+  flags: &[(String, String)],
+) -> Result<(), String> {
+  let (opt_level, enable_verifier) = match mode {
+    Mode::Dev => ("none", "true"),
+    Mode::Release => ("speed_and_size", "false"),
+  };
+
   flag_builder
-    .set("opt_level", "speed_and_size")
+    .set("opt_level", opt_level)
     .expect("set optlevel");
-}
 
-fn register_builtins(codegen: &mut Codegen) {
-  let builtins = vec![io_builtins(), sys_builtins()];
-
-  for builtin in builtins.into_iter().flatten() {
-    codegen.register_builtin(builtin);
+  flag_builder
+    .set("enable_verifier", enable_verifier)
+    .expect("set verifier");
+
+  // data and functions are reached through the got or relative to the pc, the
+  // executable is linked as a position independent executable
+  flag_builder.set("is_pic", "true").expect("set pic");
+
+  // a setting unknown by the shared flags could be one of the target
+  for (key, value) in flags {
+    match flag_builder.set(key, value) {
+      Err(SetError::BadName(_)) => isa_builder.set(key, value),
+      result => result,
+    }
+    .map_err(|error| {
+      format!("invalid codegen option `{key}={value}`: {error}")
+    })?;
   }
-}
-
-// @see https://github.com/bytecodealliance/wasmtime/blob/main/cranelift/object/tests/basic.rs#L179-L185
-fn register_builtin_c(
-  module: &mut ObjectModule,
-  builder: &mut FunctionBuilder,
-  funs: &mut FxHashMap<String, CompiledFunction>,
-  builtin: Builtin,
-) {
-  let mut signature = module.make_signature();
 
-  for input in builtin.proto.0 {
-    let clif_type = TypeBuilder::from(module, &input);
+  Ok(())
+}
 
-    signature.params.push(AbiParam::new(clif_type));
+fn register_builtins(codegen: &mut Codegen) {
+  for builtin in builtins() {
+    codegen.register_builtin(builtin);
   }
-
-  let clif_type = module.target_config().pointer_type();
-
-  signature.returns.push(AbiParam::new(clif_type));
-
-  let func_id = module
-    .declare_function(&builtin.name, Linkage::Export, &signature)
-    .unwrap_or_else(|_| panic!("declare {} function", builtin.name));
-
-  funs.insert(builtin.name, CompiledFunction::new(func_id, false, 1));
-  module.declare_func_in_func(func_id, builder.func);
 }
@@ -0,0 +1,428 @@
+//! the dwarf debug info of a program, built from the spans of the ast
+//! @see https://github.com/bjorn3/rustc_codegen_cranelift/tree/master/src/debuginfo
+
+mod emit;
+
+use super::interface::VariableLabel;
+
+use zhoo_ast::ast::Program;
+use zhoo_helper::constant::COMPILER_NAME;
+use zhoo_mir::mir::{Body, Ty};
+
+use cranelift_codegen::ir::{LabelValueLoc, ValueLabel};
+use cranelift_codegen::isa::unwind::UnwindInfo;
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::Context;
+use cranelift_module::FuncId;
+use cranelift_object::ObjectProduct;
+use fxhash::FxHashMap;
+
+use gimli::constants;
+use gimli::write::{
+  Address, AttributeValue, CieId, DwarfUnit, Expression, FileId, FrameTable,
+  LineProgram, LineString, Location, LocationList, Range, RangeList,
+  UnitEntryId,
+};
+
+use gimli::{Encoding, Format, LineEncoding, Register, RunTimeEndian};
+use target_lexicon::Endianness;
+
+use std::env;
+
+pub(crate) struct DebugContext {
+  endian: RunTimeEndian,
+  dwarf: DwarfUnit,
+  frame_table: FrameTable,
+  cie_id: Option<CieId>,
+  file_id: FileId,
+  line_starts: Vec<u32>,
+  ranges: Vec<Range>,
+  types: FxHashMap<&'static str, UnitEntryId>,
+}
+
+impl DebugContext {
+  pub fn new(isa: &dyn TargetIsa, program: &Program) -> Self {
+    let encoding = Encoding {
+      format: Format::Dwarf32,
+      version: 4,
+      address_size: isa.frontend_config().pointer_bytes(),
+    };
+
+    let endian = match isa.triple().endianness() {
+      Ok(Endianness::Big) => RunTimeEndian::Big,
+      _ => RunTimeEndian::Little,
+    };
+
+    let path = program.reporter.path(program.span).display().to_string();
+
+    let comp_dir = env::current_dir()
+      .map(|path| path.display().to_string())
+      .unwrap_or_default();
+
+    let mut dwarf = DwarfUnit::new(encoding);
+
+    let comp_dir_line =
+      LineString::new(comp_dir.as_bytes(), encoding, &mut dwarf.line_strings);
+
+    let path_line =
+      LineString::new(path.as_bytes(), encoding, &mut dwarf.line_strings);
+
+    let mut line_program = LineProgram::new(
+      encoding,
+      LineEncoding::default(),
+      comp_dir_line,
+      path_line.clone(),
+      None,
+    );
+
+    let directory_id = line_program.default_directory();
+    let file_id = line_program.add_file(path_line, directory_id, None);
+
+    dwarf.unit.line_program = line_program;
+
+    let producer = dwarf
+      .strings
+      .add(format!("{COMPILER_NAME} {}", env!("CARGO_PKG_VERSION")));
+
+    let name = dwarf.strings.add(path);
+    let comp_dir = dwarf.strings.add(comp_dir);
+    let root = dwarf.unit.root();
+    let compile_unit = dwarf.unit.get_mut(root);
+
+    compile_unit.set(
+      constants::DW_AT_producer,
+      AttributeValue::StringRef(producer),
+    );
+
+    compile_unit.set(
+      constants::DW_AT_language,
+      AttributeValue::Language(constants::DW_LANG_C),
+    );
+
+    compile_unit.set(constants::DW_AT_name, AttributeValue::StringRef(name));
+
+    compile_unit.set(
+      constants::DW_AT_comp_dir,
+      AttributeValue::StringRef(comp_dir),
+    );
+
+    compile_unit.set(
+      constants::DW_AT_low_pc,
+      AttributeValue::Address(Address::Constant(0)),
+    );
+
+    let mut frame_table = FrameTable::default();
+
+    let cie_id = isa.create_systemv_cie().map(|cie| frame_table.add_cie(cie));
+
+    Self {
+      endian,
+      dwarf,
+      frame_table,
+      cie_id,
+      file_id,
+      line_starts: line_starts(program.reporter.code(0)),
+      ranges: vec![],
+      types: FxHashMap::default(),
+    }
+  }
+
+  /// describes a compiled function, its lines, its variables and its frame
+  pub fn define_function(
+    &mut self,
+    isa: &dyn TargetIsa,
+    func_id: FuncId,
+    body: &Body,
+    context: &Context,
+    labels: &[VariableLabel],
+  ) {
+    let Some(compiled_code) = context.compiled_code() else {
+      return;
+    };
+
+    let symbol = func_id.as_u32() as usize;
+    let size = compiled_code.buffer.total_size() as u64;
+    let address = Address::Symbol { symbol, addend: 0 };
+    let (line, column) = self.line_column(body.span.lo);
+
+    // -- lines --
+
+    let line_program = &mut self.dwarf.unit.line_program;
+
+    line_program.begin_sequence(Some(address));
+    line_program.row().file = self.file_id;
+    line_program.row().line = line;
+    line_program.row().column = column;
+    line_program.generate_row();
+
+    for srcloc in compiled_code.buffer.get_srclocs_sorted() {
+      if srcloc.loc.is_default() {
+        continue;
+      }
+
+      let (line, column) = line_column(&self.line_starts, srcloc.loc.bits());
+      let line_program = &mut self.dwarf.unit.line_program;
+
+      line_program.row().address_offset = srcloc.start as u64;
+      line_program.row().line = line;
+      line_program.row().column = column;
+      line_program.generate_row();
+    }
+
+    self.dwarf.unit.line_program.end_sequence(size);
+
+    self.ranges.push(Range::StartLength {
+      begin: address,
+      length: size,
+    });
+
+    // -- subprogram --
+
+    let root = self.dwarf.unit.root();
+    let name = self.dwarf.strings.add(body.name.as_str());
+    let output_id = self.ty_with(body.output, isa);
+    let subprogram_id = self.dwarf.unit.add(root, constants::DW_TAG_subprogram);
+
+    let mut frame_base = Expression::new();
+
+    frame_base.op(constants::DW_OP_call_frame_cfa);
+
+    let subprogram = self.dwarf.unit.get_mut(subprogram_id);
+
+    subprogram.set(constants::DW_AT_name, AttributeValue::StringRef(name));
+
+    subprogram.set(
+      constants::DW_AT_decl_file,
+      AttributeValue::FileIndex(Some(self.file_id)),
+    );
+
+    subprogram.set(constants::DW_AT_decl_line, AttributeValue::Udata(line));
+    subprogram.set(constants::DW_AT_external, AttributeValue::Flag(true));
+    subprogram.set(constants::DW_AT_low_pc, AttributeValue::Address(address));
+    subprogram.set(constants::DW_AT_high_pc, AttributeValue::Udata(size));
+
+    subprogram.set(
+      constants::DW_AT_frame_base,
+      AttributeValue::Exprloc(frame_base),
+    );
+
+    if let Some(output_id) = output_id {
+      subprogram.set(constants::DW_AT_type, AttributeValue::UnitRef(output_id));
+    }
+
+    // -- variables --
+
+    for label in labels {
+      self.define_variable(isa, symbol, subprogram_id, label, context);
+    }
+
+    // -- frame --
+
+    if let (Some(cie_id), Ok(Some(UnwindInfo::SystemV(unwind_info)))) =
+      (self.cie_id, context.create_unwind_info(isa))
+    {
+      self
+        .frame_table
+        .add_fde(cie_id, unwind_info.to_fde(address));
+    }
+  }
+
+  fn define_variable(
+    &mut self,
+    isa: &dyn TargetIsa,
+    symbol: usize,
+    subprogram_id: UnitEntryId,
+    label: &VariableLabel,
+    context: &Context,
+  ) {
+    let Some(compiled_code) = context.compiled_code() else {
+      return;
+    };
+
+    let tag = if label.is_input {
+      constants::DW_TAG_formal_parameter
+    } else {
+      constants::DW_TAG_variable
+    };
+
+    let (line, _) = self.line_column(label.span.lo);
+    let name = self.dwarf.strings.add(label.name.as_str());
+    let ty_id = self.ty_with(label.ty, isa);
+
+    // regalloc2 only reports the registers of a value, a spilled value is
+    // described as optimized out
+    let locations = compiled_code
+      .value_labels_ranges
+      .get(&ValueLabel::from_u32(label.variable.as_u32()))
+      .into_iter()
+      .flatten()
+      .filter_map(|range| {
+        let LabelValueLoc::Reg(reg) = range.loc else {
+          return None;
+        };
+        let register = isa.map_regalloc_reg_to_dwarf(reg).ok()?;
+        let mut expression = Expression::new();
+
+        expression.op_reg(Register(register));
+
+        Some(Location::StartLength {
+          begin: Address::Symbol {
+            symbol,
+            addend: range.start as i64,
+          },
+          length: (range.end - range.start) as u64,
+          data: expression,
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let location_list_id = if locations.is_empty() {
+      None
+    } else {
+      Some(self.dwarf.unit.locations.add(LocationList(locations)))
+    };
+
+    let variable_id = self.dwarf.unit.add(subprogram_id, tag);
+    let variable = self.dwarf.unit.get_mut(variable_id);
+
+    variable.set(constants::DW_AT_name, AttributeValue::StringRef(name));
+
+    variable.set(
+      constants::DW_AT_decl_file,
+      AttributeValue::FileIndex(Some(self.file_id)),
+    );
+
+    variable.set(constants::DW_AT_decl_line, AttributeValue::Udata(line));
+
+    if let Some(ty_id) = ty_id {
+      variable.set(constants::DW_AT_type, AttributeValue::UnitRef(ty_id));
+    }
+
+    if let Some(location_list_id) = location_list_id {
+      variable.set(
+        constants::DW_AT_location,
+        AttributeValue::LocationListRef(location_list_id),
+      );
+    }
+  }
+
+  /// gets the type entry of a type, a `void` has no entry
+  fn ty_with(&mut self, ty: Ty, isa: &dyn TargetIsa) -> Option<UnitEntryId> {
+    let name = match ty {
+      Ty::Bool => "bool",
+      Ty::Int => "int",
+      Ty::Real => "real",
+      Ty::Str => "str",
+      Ty::Char => "char",
+      Ty::S8 => "s8",
+      Ty::S16 => "s16",
+      Ty::S32 => "s32",
+      Ty::U8 => "u8",
+      Ty::U16 => "u16",
+      Ty::U32 => "u32",
+      Ty::U64 => "u64",
+      Ty::F32 => "f32",
+      Ty::Void => return None,
+    };
+
+    if let Some(ty_id) = self.types.get(name) {
+      return Some(*ty_id);
+    }
+
+    let ty_id = match name {
+      "str" => {
+        let char_id =
+          self.base_ty_with("char", constants::DW_ATE_signed_char, 1);
+
+        let ty_id = self
+          .dwarf
+          .unit
+          .add(self.dwarf.unit.root(), constants::DW_TAG_pointer_type);
+
+        let name = self.dwarf.strings.add(name);
+        let pointer_bytes = isa.frontend_config().pointer_bytes();
+        let ty = self.dwarf.unit.get_mut(ty_id);
+
+        ty.set(constants::DW_AT_name, AttributeValue::StringRef(name));
+        ty.set(constants::DW_AT_type, AttributeValue::UnitRef(char_id));
+
+        ty.set(
+          constants::DW_AT_byte_size,
+          AttributeValue::Data1(pointer_bytes),
+        );
+
+        ty_id
+      }
+      "bool" => self.base_ty_with(name, constants::DW_ATE_boolean, 1),
+      "char" => self.base_ty_with(name, constants::DW_ATE_UTF, 4),
+      _ => {
+        let encoding = match ty {
+          _ if ty.is_float() => constants::DW_ATE_float,
+          _ if ty.is_unsigned() => constants::DW_ATE_unsigned,
+          _ => constants::DW_ATE_signed,
+        };
+
+        self.base_ty_with(name, encoding, (ty.bits() / 8) as u8)
+      }
+    };
+
+    self.types.insert(name, ty_id);
+
+    Some(ty_id)
+  }
+
+  fn base_ty_with(
+    &mut self,
+    name: &'static str,
+    encoding: constants::DwAte,
+    byte_size: u8,
+  ) -> UnitEntryId {
+    let root = self.dwarf.unit.root();
+    let ty_id = self.dwarf.unit.add(root, constants::DW_TAG_base_type);
+    let name = self.dwarf.strings.add(name);
+    let ty = self.dwarf.unit.get_mut(ty_id);
+
+    ty.set(constants::DW_AT_name, AttributeValue::StringRef(name));
+    ty.set(
+      constants::DW_AT_encoding,
+      AttributeValue::Encoding(encoding),
+    );
+    ty.set(constants::DW_AT_byte_size, AttributeValue::Data1(byte_size));
+
+    ty_id
+  }
+
+  fn line_column(&self, offset: u32) -> (u64, u64) {
+    line_column(&self.line_starts, offset)
+  }
+
+  /// writes the debug sections into the object
+  pub fn emit(mut self, product: &mut ObjectProduct) -> Result<(), String> {
+    let root = self.dwarf.unit.root();
+    let ranges = self.dwarf.unit.ranges.add(RangeList(self.ranges.clone()));
+
+    self.dwarf.unit.get_mut(root).set(
+      constants::DW_AT_ranges,
+      AttributeValue::RangeListRef(ranges),
+    );
+
+    emit::emit(self.endian, &mut self.dwarf, &self.frame_table, product)
+  }
+}
+
+/// the byte offsets where the lines of a source code start
+fn line_starts(code: &str) -> Vec<u32> {
+  std::iter::once(0)
+    .chain(code.match_indices('\n').map(|(x, _)| x as u32 + 1))
+    .collect()
+}
+
+/// the line and the column of a byte offset, both start at one
+fn line_column(line_starts: &[u32], offset: u32) -> (u64, u64) {
+  let line = match line_starts.binary_search(&offset) {
+    Ok(line) => line,
+    Err(line) => line - 1,
+  };
+
+  (line as u64 + 1, (offset - line_starts[line]) as u64 + 1)
+}
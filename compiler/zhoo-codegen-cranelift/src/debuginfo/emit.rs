@@ -0,0 +1,219 @@
+//! write the dwarf sections and their relocations into the object
+
+use cranelift_module::FuncId;
+use cranelift_object::ObjectProduct;
+
+use gimli::write::{
+  Address, DebugFrame, DwarfUnit, EndianVec, FrameTable, Sections, Writer,
+};
+
+use gimli::{RunTimeEndian, SectionId};
+
+use object::write::{Relocation, StandardSegment};
+use object::{BinaryFormat, RelocationEncoding, RelocationKind, SectionKind};
+
+use fxhash::FxHashMap;
+
+pub(super) fn emit(
+  endian: RunTimeEndian,
+  dwarf: &mut DwarfUnit,
+  frame_table: &FrameTable,
+  product: &mut ObjectProduct,
+) -> Result<(), String> {
+  let mut sections = Sections::new(WriterRelocate::new(endian));
+
+  dwarf
+    .write(&mut sections)
+    .map_err(|error| format!("{error}"))?;
+
+  let mut debug_frame = DebugFrame::from(WriterRelocate::new(endian));
+
+  frame_table
+    .write_debug_frame(&mut debug_frame)
+    .map_err(|error| format!("{error}"))?;
+
+  let mut section_map = FxHashMap::default();
+  let mut section_relocs = vec![];
+
+  let _: Result<(), ()> = sections.for_each_mut(|id, section| {
+    if !section.writer.slice().is_empty() {
+      let writer = std::mem::replace(section, WriterRelocate::new(endian));
+      let section_id = add_section(product, id, writer.writer.into_vec());
+
+      section_map.insert(id, section_id);
+      section_relocs.push((section_id, writer.relocs));
+    }
+
+    Ok(())
+  });
+
+  if !debug_frame.writer.slice().is_empty() {
+    let writer =
+      std::mem::replace(&mut debug_frame.0, WriterRelocate::new(endian));
+
+    let section_id =
+      add_section(product, SectionId::DebugFrame, writer.writer.into_vec());
+
+    section_map.insert(SectionId::DebugFrame, section_id);
+    section_relocs.push((section_id, writer.relocs));
+  }
+
+  for (section_id, relocs) in section_relocs {
+    for reloc in relocs {
+      let symbol = match reloc.name {
+        DebugRelocName::Section(id) => {
+          product.object.section_symbol(section_map[&id])
+        }
+        DebugRelocName::Symbol(symbol) => {
+          product.function_symbol(FuncId::from_u32(symbol as u32))
+        }
+      };
+
+      product
+        .object
+        .add_relocation(
+          section_id,
+          Relocation {
+            offset: reloc.offset as u64,
+            size: reloc.size * 8,
+            kind: RelocationKind::Absolute,
+            encoding: RelocationEncoding::Generic,
+            symbol,
+            addend: reloc.addend,
+          },
+        )
+        .map_err(|error| format!("{error}"))?;
+    }
+  }
+
+  Ok(())
+}
+
+fn add_section(
+  product: &mut ObjectProduct,
+  id: SectionId,
+  data: Vec<u8>,
+) -> object::write::SectionId {
+  let object = &mut product.object;
+  let segment = object.segment_name(StandardSegment::Debug).to_vec();
+
+  let name = match object.format() {
+    BinaryFormat::MachO => id.name().replace('.', "__"),
+    _ => id.name().to_string(),
+  };
+
+  let section_id =
+    object.add_section(segment, name.into_bytes(), SectionKind::Debug);
+
+  object.append_section_data(section_id, &data, 1);
+
+  section_id
+}
+
+#[derive(Clone)]
+enum DebugRelocName {
+  Section(SectionId),
+  Symbol(usize),
+}
+
+#[derive(Clone)]
+struct DebugReloc {
+  offset: u32,
+  size: u8,
+  name: DebugRelocName,
+  addend: i64,
+}
+
+/// a gimli writer that records the addresses and the section offsets it
+/// writes, they are relocated by the linker
+#[derive(Clone)]
+struct WriterRelocate {
+  relocs: Vec<DebugReloc>,
+  writer: EndianVec<RunTimeEndian>,
+}
+
+impl WriterRelocate {
+  fn new(endian: RunTimeEndian) -> Self {
+    Self {
+      relocs: vec![],
+      writer: EndianVec::new(endian),
+    }
+  }
+}
+
+impl Writer for WriterRelocate {
+  type Endian = RunTimeEndian;
+
+  fn endian(&self) -> Self::Endian {
+    self.writer.endian()
+  }
+
+  fn len(&self) -> usize {
+    self.writer.len()
+  }
+
+  fn write(&mut self, bytes: &[u8]) -> gimli::write::Result<()> {
+    self.writer.write(bytes)
+  }
+
+  fn write_at(
+    &mut self,
+    offset: usize,
+    bytes: &[u8],
+  ) -> gimli::write::Result<()> {
+    self.writer.write_at(offset, bytes)
+  }
+
+  fn write_address(
+    &mut self,
+    address: Address,
+    size: u8,
+  ) -> gimli::write::Result<()> {
+    match address {
+      Address::Constant(value) => self.write_udata(value, size),
+      Address::Symbol { symbol, addend } => {
+        self.relocs.push(DebugReloc {
+          offset: self.len() as u32,
+          size,
+          name: DebugRelocName::Symbol(symbol),
+          addend,
+        });
+
+        self.write_udata(0, size)
+      }
+    }
+  }
+
+  fn write_offset(
+    &mut self,
+    value: usize,
+    section: SectionId,
+    size: u8,
+  ) -> gimli::write::Result<()> {
+    self.relocs.push(DebugReloc {
+      offset: self.len() as u32,
+      size,
+      name: DebugRelocName::Section(section),
+      addend: value as i64,
+    });
+
+    self.write_udata(0, size)
+  }
+
+  fn write_offset_at(
+    &mut self,
+    offset: usize,
+    value: usize,
+    section: SectionId,
+    size: u8,
+  ) -> gimli::write::Result<()> {
+    self.relocs.push(DebugReloc {
+      offset: offset as u32,
+      size,
+      name: DebugRelocName::Section(section),
+      addend: value as i64,
+    });
+
+    self.write_udata_at(offset, 0, size)
+  }
+}
@@ -0,0 +1,59 @@
+//! disassemble the machine code of a function through capstone
+//! @see https://github.com/bytecodealliance/wasmtime/blob/main/cranelift/src/disasm.rs
+
+use capstone::prelude::*;
+use cranelift_codegen::isa::TargetIsa;
+use target_lexicon::Architecture;
+
+use std::fmt::Write;
+
+pub(crate) fn disassemble(
+  isa: &dyn TargetIsa,
+  name: &str,
+  code: &[u8],
+) -> Result<String, String> {
+  let capstone = make_capstone(isa)?;
+
+  let instructions = capstone
+    .disasm_all(code, 0)
+    .map_err(|error| format!("{error}"))?;
+
+  let mut asm = format!("{name}:\n");
+
+  for instruction in instructions.iter() {
+    let mnemonic = instruction.mnemonic().unwrap_or_default();
+    let operands = instruction.op_str().unwrap_or_default();
+
+    writeln!(asm, "  {:#06x}: {mnemonic:7} {operands}", instruction.address())
+      .map_err(|error| format!("{error}"))?;
+  }
+
+  Ok(asm)
+}
+
+fn make_capstone(isa: &dyn TargetIsa) -> Result<Capstone, String> {
+  let capstone = match isa.triple().architecture {
+    Architecture::X86_64 => Capstone::new()
+      .x86()
+      .mode(arch::x86::ArchMode::Mode64)
+      .syntax(arch::x86::ArchSyntax::Att)
+      .build(),
+    Architecture::Aarch64 { .. } => Capstone::new()
+      .arm64()
+      .mode(arch::arm64::ArchMode::Arm)
+      .build(),
+    Architecture::S390x => Capstone::new()
+      .sysz()
+      .mode(arch::sysz::ArchMode::Default)
+      .build(),
+    Architecture::Riscv64 { .. } => Capstone::new()
+      .riscv()
+      .mode(arch::riscv::ArchMode::RiscV64)
+      .build(),
+    architecture => {
+      return Err(format!("no disassembler for `{architecture}`"));
+    }
+  };
+
+  capstone.map_err(|error| format!("{error}"))
+}
@@ -1,12 +1,14 @@
-use zhoo_ast::ast::{Ty, TyKind};
-use zhoo_ast::ptr::Fsp;
+use zhoo_analyzer::constant::Constant;
+use zhoo_mir::mir;
 
 use cranelift::prelude::{
-  types, FunctionBuilder, InstBuilder, Value, Variable,
+  types, AbiParam, FunctionBuilder, InstBuilder, Value, Variable,
 };
 
-use cranelift_codegen::ir::GlobalValue;
-use cranelift_module::{DataContext, FuncId, Linkage, Module};
+use zhoo_span::span::Span;
+
+use cranelift_codegen::ir::{Endianness, ValueLabel};
+use cranelift_module::{DataContext, DataId, FuncId, Linkage, Module};
 use cranelift_object::ObjectModule;
 use fxhash::FxHashMap;
 
@@ -26,27 +28,71 @@ impl CompiledFunction {
   }
 }
 
+/// a top-level `val`, its constant is emitted as read-only data
+pub(crate) struct CompiledVal {
+  pub id: DataId,
+  pub constant: Constant,
+}
+
+/// a variable of a function, described by the debug info
+pub(crate) struct VariableLabel {
+  pub variable: Variable,
+  pub name: String,
+  pub ty: mir::Ty,
+  pub span: Span,
+  pub is_input: bool,
+}
+
 #[derive(Default)]
 pub(crate) struct VariableBuilder {
   pub index: u32,
+  pub labels: Vec<VariableLabel>,
 }
 
 impl VariableBuilder {
-  pub fn create_variable(
+  pub fn declare_variable(
     &mut self,
     builder: &mut FunctionBuilder,
-    value: Value,
     ty: types::Type,
   ) -> Variable {
     let variable = Variable::from_u32(self.index);
 
     builder.declare_var(variable, ty);
-    builder.def_var(variable, value);
 
     self.index += 1;
 
     variable
   }
+
+  /// assigns a value to a variable, the value is labelled when the function
+  /// collects debug info
+  pub fn define_variable(
+    &mut self,
+    builder: &mut FunctionBuilder,
+    variable: Variable,
+    value: Value,
+  ) {
+    builder.def_var(variable, value);
+    builder.set_val_label(value, ValueLabel::from_u32(variable.as_u32()));
+  }
+
+  /// records the name and the type of a variable for the debug info
+  pub fn label_variable(
+    &mut self,
+    variable: Variable,
+    name: &str,
+    span: Span,
+    ty: mir::Ty,
+    is_input: bool,
+  ) {
+    self.labels.push(VariableLabel {
+      variable,
+      name: name.to_string(),
+      ty,
+      span,
+      is_input,
+    });
+  }
 }
 
 #[derive(Default)]
@@ -55,28 +101,20 @@ pub(crate) struct DataBuilder {
 }
 
 impl DataBuilder {
+  /// a string literal is defined once and shared by the functions, each
+  /// function declares its own global value of it
   pub fn create_data(
     &mut self,
     builder: &mut FunctionBuilder,
     module: &mut ObjectModule,
-    globals: &mut FxHashMap<String, GlobalValue>,
-    data: &String,
-  ) -> Value {
+    globals: &mut FxHashMap<String, DataId>,
+    data: &str,
+  ) -> Result<Value, String> {
     let data_id = match globals.get(data) {
       Some(data_id) => *data_id,
       None => {
         let data_name = format!("__data{}", self.index);
-
-        let data_id = module
-          .declare_data(&data_name, Linkage::Local, false, false)
-          .unwrap();
-
-        let mut data_context = DataContext::new();
-
-        data_context.define(data.as_bytes().to_vec().into_boxed_slice());
-        module.define_data(data_id, &data_context).unwrap();
-
-        let data_id = module.declare_data_in_func(data_id, builder.func);
+        let data_id = Self::define_str(module, &data_name, data)?;
 
         globals.insert(data.to_string(), data_id);
 
@@ -86,20 +124,87 @@ impl DataBuilder {
       }
     };
 
-    builder.ins().symbol_value(types::I64, data_id)
+    let global_value = module.declare_data_in_func(data_id, builder.func);
+    let pointer_type = module.target_config().pointer_type();
+
+    Ok(builder.ins().symbol_value(pointer_type, global_value))
+  }
+
+  /// defines the bytes of a `str` and its header, the header holds the
+  /// address of the bytes and their length like the `Str` of the core library.
+  /// a nul follows the bytes, so an empty `str` has an address too
+  pub fn define_str(
+    module: &mut ObjectModule,
+    name: &str,
+    string: &str,
+  ) -> Result<DataId, String> {
+    let bytes_id = module
+      .declare_data(&format!("{name}.bytes"), Linkage::Local, false, false)
+      .map_err(|error| format!("{error}"))?;
+
+    let mut bytes = string.as_bytes().to_vec();
+    let mut data_context = DataContext::new();
+
+    bytes.push(0);
+    data_context.define(bytes.into_boxed_slice());
+    module
+      .define_data(bytes_id, &data_context)
+      .map_err(|error| format!("{error}"))?;
+
+    let pointer_bytes = module.isa().pointer_bytes() as usize;
+    let len = string.len() as u64;
+
+    let len = match module.isa().endianness() {
+      Endianness::Little => len.to_le_bytes()[..pointer_bytes].to_vec(),
+      Endianness::Big => len.to_be_bytes()[8 - pointer_bytes..].to_vec(),
+    };
+
+    let header_id = module
+      .declare_data(name, Linkage::Local, false, false)
+      .map_err(|error| format!("{error}"))?;
+    let mut data_context = DataContext::new();
+
+    data_context.set_align(pointer_bytes as u64);
+    data_context.define([vec![0; pointer_bytes], len].concat().into());
+
+    let bytes = module.declare_data_in_data(bytes_id, &mut data_context);
+
+    data_context.write_data_addr(0, bytes, 0);
+    module
+      .define_data(header_id, &data_context)
+      .map_err(|error| format!("{error}"))?;
+
+    Ok(header_id)
   }
 }
 
 pub(crate) struct TypeBuilder;
 
 impl TypeBuilder {
-  pub fn from(module: &mut ObjectModule, ty: &Fsp<Ty>) -> types::Type {
-    match ty.kind {
-      TyKind::Void => types::I64,
-      TyKind::Bool => types::B1,
-      TyKind::Int => types::I64,
-      TyKind::Real => types::F64,
-      _ => module.target_config().pointer_type(),
+  pub fn from_mir(module: &mut ObjectModule, ty: mir::Ty) -> types::Type {
+    match ty {
+      mir::Ty::Void => types::I64,
+      mir::Ty::Bool => types::B1,
+      mir::Ty::Int | mir::Ty::U64 => types::I64,
+      mir::Ty::Real => types::F64,
+      mir::Ty::Str => module.target_config().pointer_type(),
+      mir::Ty::S8 | mir::Ty::U8 => types::I8,
+      mir::Ty::S16 | mir::Ty::U16 => types::I16,
+      mir::Ty::S32 | mir::Ty::U32 | mir::Ty::Char => types::I32,
+      mir::Ty::F32 => types::F32,
+    }
+  }
+
+  /// a parameter of a signature, an integer smaller than a register is
+  /// extended by the caller like the c abi does, a `char` is unsigned
+  pub fn abi_param(module: &mut ObjectModule, ty: mir::Ty) -> AbiParam {
+    let param = AbiParam::new(Self::from_mir(module, ty));
+
+    match ty.bits() {
+      64 => param,
+      _ if ty.is_signed() => param.sext(),
+      _ if ty.is_unsigned() || ty == mir::Ty::Char => param.uext(),
+      _ => param,
     }
   }
 }
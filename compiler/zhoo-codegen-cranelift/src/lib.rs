@@ -1,7 +1,13 @@
 mod codegen;
+mod debuginfo;
+mod disasm;
 mod interface;
+mod linker;
+mod runtime;
+mod settings;
 mod translator;
 
 pub mod cranelift {
   pub use super::codegen::generate;
+  pub use super::settings::{Emit, Mode, Settings};
 }
@@ -0,0 +1,241 @@
+//! link the object of a program with the core library through a c compiler
+//! driver (`cc`, `clang`) or a linker (`ld`, `lld`)
+
+use zhoo_errors::LinkKind;
+use zhoo_helper::constant::CC_PROGRAM;
+
+use target_lexicon::{Architecture, Triple};
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// the linkers the backend is able to drive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LinkerFlavor {
+  /// a c compiler driver like `cc` or `gcc`
+  Cc,
+  /// the clang driver, it receives the target triple
+  Clang,
+  /// the gnu linker
+  Ld,
+  /// the llvm linker
+  Lld,
+}
+
+impl From<&str> for LinkerFlavor {
+  fn from(program: &str) -> Self {
+    let name = Path::new(program)
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+
+    if name.contains("clang") {
+      Self::Clang
+    } else if name.contains("lld") {
+      Self::Lld
+    } else if name == "ld" || name.starts_with("ld.") || name.ends_with("-ld") {
+      Self::Ld
+    } else {
+      Self::Cc
+    }
+  }
+}
+
+pub(crate) struct Linker {
+  program: String,
+  flavor: LinkerFlavor,
+  triple: Triple,
+  /// link a position independent executable, the object must be built with
+  /// the `is_pic` setting
+  pie: bool,
+}
+
+impl Linker {
+  /// the linker given by `--linker`, otherwise the one of the `CC` env.
+  ///
+  /// the host falls back to `cc`, a cross target has no default linker.
+  pub fn new(linker: Option<&str>, triple: &Triple, pie: bool) -> Option<Self> {
+    let program = linker
+      .map(String::from)
+      .or_else(|| env::var("CC").ok().filter(|cc| !cc.is_empty()))
+      .or_else(|| {
+        (*triple == Triple::host()).then(|| String::from(CC_PROGRAM))
+      })?;
+
+    Some(Self {
+      flavor: LinkerFlavor::from(program.as_str()),
+      program,
+      triple: triple.to_owned(),
+      pie: pie && is_pic_supported(triple),
+    })
+  }
+
+  pub fn link(
+    &self,
+    path_objects: &[&str],
+    path_output: &str,
+  ) -> Result<(), LinkKind> {
+    let args = match self.flavor {
+      LinkerFlavor::Cc => self.args_cc(path_objects, path_output),
+      LinkerFlavor::Clang => {
+        let mut args = vec![format!("--target={}", self.triple)];
+
+        args.extend(self.args_cc(path_objects, path_output));
+        args
+      }
+      LinkerFlavor::Ld => self.args_ld(path_objects, path_output)?,
+      LinkerFlavor::Lld => {
+        let mut args = vec![];
+
+        // `lld` is a generic driver, it needs to know the flavor to emulate
+        if Path::new(&self.program).file_name() == Some("lld".as_ref()) {
+          args.extend([String::from("-flavor"), String::from("gnu")]);
+        }
+
+        args.extend(self.args_ld(path_objects, path_output)?);
+        args
+      }
+    };
+
+    let command = format!("{} {}", self.program, args.join(" "));
+
+    let output =
+      Command::new(&self.program)
+        .args(&args)
+        .output()
+        .map_err(|error| {
+          LinkKind::LinkerNotFound(self.program.to_string(), error.to_string())
+        })?;
+
+    if output.status.success() {
+      return Ok(());
+    }
+
+    Err(LinkKind::LinkFailed(
+      self.program.to_string(),
+      command,
+      String::from_utf8_lossy(&output.stderr).to_string(),
+    ))
+  }
+
+  fn args_cc(&self, path_objects: &[&str], path_output: &str) -> Vec<String> {
+    let mut args = path_objects
+      .iter()
+      .map(|path| path.to_string())
+      .collect::<Vec<_>>();
+
+    args.push(String::from(if self.pie { "-pie" } else { "-no-pie" }));
+
+    args.extend(
+      ["-o", path_output, "-lpthread", "-ldl", "-lm"].map(String::from),
+    );
+
+    args
+  }
+
+  /// a linker needs the c runtime files and libraries of the target which
+  /// are given by its c compiler driver. a file which the driver of the host
+  /// does not find is left to the search of the linker, a cross target must
+  /// have its own files
+  fn args_ld(
+    &self,
+    path_objects: &[&str],
+    path_output: &str,
+  ) -> Result<Vec<String>, LinkKind> {
+    let driver = self.driver();
+    let is_host = self.triple == Triple::host();
+
+    let print_file_name = |file: &str| {
+      let path = Command::new(&driver)
+        .arg(format!("-print-file-name={file}"))
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+          String::from_utf8_lossy(&output.stdout).trim().to_string()
+        });
+
+      // the driver gives back the name of a file it does not find
+      match path {
+        Some(path) if path != file => Ok(path),
+        _ if is_host => Ok(file.to_string()),
+        _ => Err(LinkKind::CrossRuntimeNotFound(
+          self.triple.to_string(),
+          driver.to_string(),
+          file.to_string(),
+        )),
+      }
+    };
+
+    let path_libgcc = print_file_name("libgcc_s.so")?;
+
+    // the start files of a position independent executable are `Scrt1.o`
+    // and `crtbeginS.o`, its end file is `crtendS.o`
+    let (crt1, crtbegin, crtend) = match self.pie {
+      true => ("Scrt1.o", "crtbeginS.o", "crtendS.o"),
+      false => ("crt1.o", "crtbegin.o", "crtend.o"),
+    };
+
+    let mut args = vec![String::from("--eh-frame-hdr")];
+
+    if self.pie {
+      args.push(String::from("-pie"));
+    }
+
+    args.extend([
+      String::from("-dynamic-linker"),
+      String::from(dynamic_linker(&self.triple)),
+      String::from("-o"),
+      String::from(path_output),
+      print_file_name(crt1)?,
+      print_file_name("crti.o")?,
+      print_file_name(crtbegin)?,
+    ]);
+
+    args.extend(path_objects.iter().map(|path| path.to_string()));
+
+    if let Some(path_directory) = Path::new(&path_libgcc).parent() {
+      args.push(format!("-L{}", path_directory.display()));
+    }
+
+    args
+      .extend(["-lpthread", "-ldl", "-lm", "-lc", "-lgcc_s"].map(String::from));
+
+    args.push(print_file_name(crtend)?);
+    args.push(print_file_name("crtn.o")?);
+
+    Ok(args)
+  }
+
+  /// the c compiler driver of the target, the one of a cross target is named
+  /// after it like the gnu toolchains: `aarch64-linux-gnu-gcc`
+  fn driver(&self) -> String {
+    if self.triple == Triple::host() {
+      return String::from(CC_PROGRAM);
+    }
+
+    format!(
+      "{}-{}-{}-gcc",
+      self.triple.architecture,
+      self.triple.operating_system,
+      self.triple.environment
+    )
+  }
+}
+
+/// cranelift only emits got and pc relative accesses for `x86_64`, the other
+/// architectures keep absolute relocations which can not be linked as pie
+fn is_pic_supported(triple: &Triple) -> bool {
+  triple.architecture == Architecture::X86_64
+}
+
+/// the path of the dynamic linker of the glibc for an architecture
+fn dynamic_linker(triple: &Triple) -> &'static str {
+  match triple.architecture {
+    Architecture::Aarch64(_) => "/lib/ld-linux-aarch64.so.1",
+    Architecture::Riscv64(_) => "/lib/ld-linux-riscv64-lp64d.so.1",
+    Architecture::S390x => "/lib/ld64.so.1",
+    _ => "/lib64/ld-linux-x86-64.so.2",
+  }
+}
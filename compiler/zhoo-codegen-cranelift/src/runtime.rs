@@ -0,0 +1,133 @@
+//! the core library embedded into the compiler, it is extracted into the
+//! cache directory the first time a program is linked. the core library of a
+//! cross target is built from the embedded sources then cached the same way
+
+use zhoo_errors::LinkKind;
+use zhoo_helper::constant::{COMPILER_NAME, PATH_LIBRARY_CORE};
+
+use target_lexicon::Triple;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// the core library built by the build script for the host
+static LIBRARY_CORE: &[u8] =
+  include_bytes!(concat!(env!("OUT_DIR"), "/libzhoo_core.a"));
+
+/// the sources of the core library, by their file name
+static LIBRARY_CORE_SOURCES: &[(&str, &str)] =
+  include!(concat!(env!("OUT_DIR"), "/sources.rs"));
+
+/// the path of the core library, extracted on demand
+pub(crate) fn library_core() -> Result<PathBuf, LinkKind> {
+  let path_directory = cache_dir().join(env!("ZHOO_CORE_HASH"));
+  let path_library_core = path_directory.join(PATH_LIBRARY_CORE);
+
+  if path_library_core.is_file() {
+    return Ok(path_library_core);
+  }
+
+  let not_extracted = |error: std::io::Error| {
+    LinkKind::LibraryCoreNotExtracted(
+      path_library_core.display().to_string(),
+      error.to_string(),
+    )
+  };
+
+  fs::create_dir_all(&path_directory).map_err(not_extracted)?;
+
+  // the library is written aside then renamed, two compilations running at
+  // the same time never link a partial library
+  let path_partial = partial(&path_library_core);
+
+  fs::write(&path_partial, LIBRARY_CORE).map_err(not_extracted)?;
+  fs::rename(&path_partial, &path_library_core).map_err(not_extracted)?;
+
+  Ok(path_library_core)
+}
+
+/// the path of the core library of a cross target, built by `rustc` on
+/// demand. the standard library of the target is the one of `rustup target
+/// add <triple>`
+pub(crate) fn library_core_cross(triple: &Triple) -> Result<PathBuf, LinkKind> {
+  let path_directory = cache_dir().join(env!("ZHOO_CORE_HASH"));
+  let path_target = path_directory.join(triple.to_string());
+  let path_library_core = path_target.join(PATH_LIBRARY_CORE);
+
+  if path_library_core.is_file() {
+    return Ok(path_library_core);
+  }
+
+  let not_extracted = |error: std::io::Error| {
+    LinkKind::LibraryCoreNotExtracted(
+      path_target.display().to_string(),
+      error.to_string(),
+    )
+  };
+
+  let path_source = path_directory.join("src");
+
+  fs::create_dir_all(&path_source).map_err(not_extracted)?;
+  fs::create_dir_all(&path_target).map_err(not_extracted)?;
+
+  for (name, source) in LIBRARY_CORE_SOURCES {
+    fs::write(path_source.join(name), source).map_err(not_extracted)?;
+  }
+
+  let not_built =
+    |error: String| LinkKind::LibraryCoreNotBuilt(triple.to_string(), error);
+
+  let path_partial = partial(&path_library_core);
+  let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+
+  let output = Command::new(&rustc)
+    .args(["--edition", "2021", "--crate-type", "staticlib"])
+    .args(["--crate-name", "zhoo_core", "--target", &triple.to_string()])
+    .arg(format!("-Copt-level={}", env!("ZHOO_CORE_OPT_LEVEL")))
+    .arg("-o")
+    .arg(&path_partial)
+    .arg(path_source.join("lib.rs"))
+    .output()
+    .map_err(|error| not_built(format!("`{rustc}`: {error}")))?;
+
+  if !output.status.success() {
+    let _ = fs::remove_file(&path_partial);
+
+    return Err(not_built(
+      String::from_utf8_lossy(&output.stderr).to_string(),
+    ));
+  }
+
+  fs::rename(&path_partial, &path_library_core).map_err(not_extracted)?;
+
+  Ok(path_library_core)
+}
+
+/// the path a library is written to before being renamed to its own
+fn partial(path: &Path) -> PathBuf {
+  let mut path_partial = path.as_os_str().to_owned();
+
+  path_partial.push(format!(".{}", std::process::id()));
+
+  PathBuf::from(path_partial)
+}
+
+/// `$ZHOO_CACHE_DIR`, otherwise the cache directory of the user
+fn cache_dir() -> PathBuf {
+  let from_env = |key: &str| {
+    env::var_os(key)
+      .filter(|value| !value.is_empty())
+      .map(PathBuf::from)
+  };
+
+  if let Some(path) = from_env("ZHOO_CACHE_DIR") {
+    return path;
+  }
+
+  from_env("XDG_CACHE_HOME")
+    .or_else(|| from_env("HOME").map(|home| home.join(".cache")))
+    .unwrap_or_else(env::temp_dir)
+    .join(COMPILER_NAME)
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// the settings of the cranelift backend
+#[derive(Debug, Default)]
+pub struct Settings {
+  /// the target triple, the host is used when it's `None`
+  pub target: Option<String>,
+  /// the linker used to link a cross-compiled object
+  pub linker: Option<String>,
+  /// the artifacts to write in the output directory
+  pub emit: Vec<Emit>,
+  /// attach the dwarf debug info to the object
+  pub debug: bool,
+  /// the build mode
+  pub mode: Mode,
+  /// the cranelift settings given by `-C key=value`, applied after the mode
+  pub flags: Vec<(String, String)>,
+  /// the name of the artifacts, `main` when it's `None`
+  pub name: Option<String>,
+  /// the output directory, `program` when it's `None`
+  pub out_dir: Option<String>,
+}
+
+/// the build modes of a program
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+  /// no optimization and the verifier is enabled
+  #[default]
+  Dev,
+  /// the optimized build
+  Release,
+}
+
+impl fmt::Display for Mode {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Dev => write!(f, "dev"),
+      Self::Release => write!(f, "release"),
+    }
+  }
+}
+
+/// the artifacts the backend is able to write
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Emit {
+  /// the object file, without linking
+  Obj,
+  /// the cranelift ir of every function
+  Clif,
+  /// the disassembly of every function
+  Asm,
+  /// the pretty printed program
+  Ast,
+  /// the mid-level ir of every function, after the optimizations
+  Mir,
+  /// the linked executable
+  Exe,
+}
+
+impl Emit {
+  /// the extension of the artifact file
+  pub const fn extension(&self) -> &'static str {
+    match self {
+      Self::Obj => "o",
+      Self::Clif => "clif",
+      Self::Asm => "s",
+      Self::Ast => "ast",
+      Self::Mir => "mir",
+      Self::Exe => "",
+    }
+  }
+}
+
+impl FromStr for Emit {
+  type Err = String;
+
+  fn from_str(emit: &str) -> Result<Self, Self::Err> {
+    match emit {
+      "obj" => Ok(Self::Obj),
+      "clif" => Ok(Self::Clif),
+      "asm" => Ok(Self::Asm),
+      "ast" => Ok(Self::Ast),
+      "mir" => Ok(Self::Mir),
+      "exe" => Ok(Self::Exe),
+      _ => Err(format!(
+        "expected one of `obj`, `clif`, `asm`, `ast`, `mir`, `exe`, found `{emit}`"
+      )),
+    }
+  }
+}
@@ -1,766 +1,487 @@
-use super::interface::{CompiledFunction, DataBuilder, VariableBuilder};
-
-use zhoo_ast::ast::{
-  BinOp, BinOpKind, Block, Decl, Expr, ExprKind, Lit, LitKind, Stmt, StmtKind,
-  UnOp, UnOpKind,
+use super::interface::{
+  CompiledFunction, CompiledVal, DataBuilder, TypeBuilder, VariableBuilder,
 };
 
-use zhoo_ast::ptr::Fsp;
+use zhoo_analyzer::constant::Constant;
+
+use zhoo_mir::mir::{
+  BinOp, BlockId, Body, Const, Local, Operand, Rvalue, StmtKind,
+  TerminatorKind, Ty, UnOp,
+};
 
 use cranelift::prelude::{
-  types, Block as CBlock, FloatCC, FunctionBuilder, InstBuilder, IntCC, Value,
-  Variable,
+  types, Block as CBlock, FloatCC, FunctionBuilder, InstBuilder, IntCC,
+  MemFlags, TrapCode, Value, Variable,
 };
 
-use cranelift_codegen::ir::immediates::Offset32;
-use cranelift_codegen::ir::{GlobalValue, StackSlot};
-use cranelift_module::Module;
+use cranelift_codegen::ir::SourceLoc;
+use cranelift_module::{DataId, Module};
 use cranelift_object::ObjectModule;
 use fxhash::FxHashMap;
 
+use std::cmp::Ordering;
+
+/// translates a mir body into cranelift ir, a basic block becomes a block and
+/// a local becomes a variable
 pub(crate) struct Translator<'a> {
   pub builder: FunctionBuilder<'a>,
   pub module: &'a mut ObjectModule,
-  pub funs: &'a mut FxHashMap<String, CompiledFunction>,
-  pub globals: &'a mut FxHashMap<String, GlobalValue>,
-  pub vars: &'a mut FxHashMap<String, Variable>,
-  pub ty: types::Type,
-  pub blocks: &'a mut Vec<CBlock>,
+  pub funs: &'a FxHashMap<String, CompiledFunction>,
+  pub globals: &'a mut FxHashMap<String, DataId>,
+  pub vals: &'a FxHashMap<String, CompiledVal>,
+  pub body: &'a Body,
+  pub blocks: Vec<CBlock>,
+  pub vars: Vec<Variable>,
   pub variable_builder: &'a mut VariableBuilder,
   pub data_builder: &'a mut DataBuilder,
 }
 
 impl<'a> Translator<'a> {
-  pub fn translate(&mut self, block: &Block) -> Result<Value, String> {
-    let mut value = self.translate_expr_lit_int(&0);
+  pub fn translate(&mut self) -> Result<(), String> {
+    self.builder.set_srcloc(SourceLoc::new(self.body.span.lo));
 
-    for expr in &block.exprs {
-      value = self.translate_expr(expr);
-    }
+    for _ in &self.body.blocks {
+      let block = self.builder.create_block();
 
-    Ok(value)
-  }
-
-  fn translate_stmt(&mut self, stmt: &Stmt) -> Value {
-    match &stmt.kind {
-      StmtKind::Val(decl) => self.translate_stmt_val(decl),
-      _ => unimplemented!(),
+      self.blocks.push(block);
     }
-  }
 
-  fn translate_stmt_val(&mut self, decl: &Decl) -> Value {
-    self.translate_decl(decl)
-  }
+    let entry_block = self.blocks[BlockId::ENTRY.0];
 
-  fn translate_decl(&mut self, decl: &Decl) -> Value {
-    let value = self.translate_expr(&decl.value);
+    self
+      .builder
+      .append_block_params_for_function_params(entry_block);
 
-    let variable = self.variable_builder.create_variable(
-      &mut self.builder,
-      value,
-      types::I64,
-    );
+    self.builder.switch_to_block(entry_block);
 
-    self.vars.insert(decl.pattern.to_string(), variable);
+    for (x, local) in self.body.locals.iter().enumerate() {
+      let clif_type = TypeBuilder::from_mir(self.module, local.ty);
 
-    value
-  }
+      let variable = self
+        .variable_builder
+        .declare_variable(&mut self.builder, clif_type);
 
-  fn translate_expr(&mut self, expr: &Expr) -> Value {
-    match &expr.kind {
-      ExprKind::Lit(lit) => self.translate_expr_lit(lit),
-      ExprKind::Identifier(s) => self.translate_expr_id(s),
-      ExprKind::Call(callee, args) => self.translate_expr_call(callee, args),
-      ExprKind::UnOp(op, rhs) => self.translate_expr_un_op(op, rhs),
-      ExprKind::BinOp(lhs, op, rhs) => self.translate_expr_bin_op(lhs, op, rhs),
-      ExprKind::Decl(decl) => self.translate_expr_decl(decl),
-      ExprKind::Assign(id, op, rhs) => self.translate_expr_assign(id, op, rhs),
-      ExprKind::AssignOp(lhs, op, rhs) => {
-        self.translate_expr_assign_op(lhs, op, rhs)
-      }
-      ExprKind::Loop(body) => self.translate_expr_loop(body),
-      ExprKind::While(condition, body) => {
-        self.translate_expr_while(condition, body)
-      }
-      ExprKind::Until(_condition, _body) => todo!(),
-      ExprKind::Return(value) => self.translate_expr_return(value),
-      ExprKind::Break(value) => self.translate_expr_break(value),
-      ExprKind::Continue => self.translate_expr_continue(),
-      ExprKind::Block(block) => self.translate_expr_block(block),
-      ExprKind::When(condition, consequence, alternative) => {
-        self.translate_expr_when(condition, consequence, alternative)
-      }
-      ExprKind::IfElse(condition, consequence, maybe_alternative) => {
-        self.translate_expr_if_else(condition, consequence, maybe_alternative)
-      }
-      ExprKind::Lambda(args, block_or_expr) => {
-        self.translate_expr_lambda(args, block_or_expr)
+      if let Some(name) = &local.name {
+        self.variable_builder.label_variable(
+          variable,
+          name,
+          local.span,
+          local.ty,
+          x < self.body.inputs,
+        );
       }
-      ExprKind::Array(elements) => self.translate_expr_array(elements),
-      ExprKind::ArrayAccess(indexed, index) => {
-        self.translate_expr_array_access(indexed, index)
-      }
-      ExprKind::Stmt(stmt) => self.translate_expr_stmt(stmt),
-      _ => unimplemented!(),
+
+      self.vars.push(variable);
     }
-  }
 
-  fn translate_expr_lit(&mut self, lit: &Lit) -> Value {
-    match &lit.kind {
-      LitKind::Bool(boolean) => self.translate_expr_lit_bool(boolean),
-      LitKind::Int(int) => self.translate_expr_lit_int(int),
-      LitKind::Real(real) => self.translate_expr_lit_real(real),
-      LitKind::Str(string) => self.translate_expr_lit_str(string),
+    for x in 0..self.body.inputs {
+      let value = self.builder.block_params(entry_block)[x];
+
+      self.variable_builder.define_variable(
+        &mut self.builder,
+        self.vars[x],
+        value,
+      );
     }
-  }
 
-  fn translate_expr_lit_bool(&mut self, boolean: &bool) -> Value {
-    self.builder.ins().bconst(types::B1, *boolean)
-  }
+    for block in self.body.block_ids() {
+      self.translate_block(block)?;
+    }
 
-  fn translate_expr_lit_int(&mut self, num: &i64) -> Value {
-    self.builder.ins().iconst(types::I64, *num)
-  }
+    self.builder.seal_all_blocks();
 
-  fn translate_expr_lit_real(&mut self, num: &f64) -> Value {
-    self.builder.ins().f64const(*num)
+    Ok(())
   }
 
-  // fixme #1
-  fn translate_expr_lit_str(&mut self, data: &String) -> Value {
-    self.data_builder.create_data(
-      &mut self.builder,
-      self.module,
-      self.globals,
-      data,
-    )
-  }
+  fn translate_block(&mut self, block: BlockId) -> Result<(), String> {
+    let basic_block = self.body.block(block);
 
-  fn translate_expr_id(&mut self, name: &String) -> Value {
-    let Some(decl) = self.vars.get(&name.to_string()) else {
-      panic!("{}", format!("🤖 the name `{name}` not found"))
-    };
+    if block != BlockId::ENTRY {
+      self.builder.switch_to_block(self.blocks[block.0]);
+    }
 
-    self.builder.use_var(*decl)
-  }
+    for stmt in &basic_block.stmts {
+      self.builder.set_srcloc(SourceLoc::new(stmt.span.lo));
 
-  fn translate_expr_call(
-    &mut self,
-    callee: &Expr,
-    inputs: &[Fsp<Expr>],
-  ) -> Value {
-    match self.funs.get(&callee.to_string()) {
-      Some(fun) => {
-        let callee_ref =
-          self.module.declare_func_in_func(fun.id, self.builder.func);
+      let StmtKind::Assign(local, rvalue) = &stmt.kind;
+      let value = self.translate_rvalue(*local, rvalue)?;
 
-        let inputs = inputs
-          .iter()
-          .map(|arg| self.translate_expr(arg))
-          .collect::<Vec<_>>();
+      self.variable_builder.define_variable(
+        &mut self.builder,
+        self.vars[local.0],
+        value,
+      );
+    }
 
-        let call_instruction = self.builder.ins().call(callee_ref, &inputs);
-        let call_results = self.builder.inst_results(call_instruction);
+    let terminator = &basic_block.terminator;
 
-        if call_results.is_empty() {
-          return self.translate_expr_lit_int(&0);
-        }
+    self.builder.set_srcloc(SourceLoc::new(terminator.span.lo));
 
-        call_results[0]
+    match &terminator.kind {
+      TerminatorKind::Goto(target) => {
+        self.builder.ins().jump(self.blocks[target.0], &[]);
       }
-      None => panic!("{}", format!("🤖 this function `{callee}` do not exist")),
-    }
-  }
+      TerminatorKind::Branch(condition, consequence, alternative) => {
+        let condition = self.translate_operand(condition)?;
 
-  fn translate_expr_un_op(&mut self, op: &UnOp, rhs: &Expr) -> Value {
-    let rhs_new = self.translate_expr(rhs);
-
-    match &op.node {
-      UnOpKind::Neg => self.translate_expr_un_op_neg(&rhs.kind, rhs_new),
-      UnOpKind::Not => self.translate_expr_un_op_not(rhs_new),
-    }
-  }
+        self
+          .builder
+          .ins()
+          .brnz(condition, self.blocks[consequence.0], &[]);
 
-  fn translate_expr_un_op_neg(&mut self, kind: &ExprKind, rhs: Value) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+        self.builder.ins().jump(self.blocks[alternative.0], &[]);
+      }
+      TerminatorKind::Return(value) => {
+        let value = match value {
+          Operand::Const(Const::Void) => self.translate_zero(self.body.output),
+          _ => self.translate_operand(value)?,
+        };
 
-    match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().ineg(rhs),
-      LitKind::Real(_real) => self.builder.ins().fneg(rhs),
-      _ => panic!("{}", format!("🤖 unexpected unary operation: {kind}")),
+        self.builder.ins().return_(&[value]);
+      }
+      TerminatorKind::Unreachable => {
+        self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+      }
     }
-  }
-
-  fn translate_expr_un_op_not(&mut self, rhs: Value) -> Value {
-    let value = self.builder.ins().icmp_imm(IntCC::Equal, rhs, 0);
 
-    self.builder.ins().bint(self.ty, value)
+    Ok(())
   }
 
-  fn translate_expr_bin_op(
+  fn translate_rvalue(
     &mut self,
-    lhs: &Expr,
-    op: &BinOp,
-    rhs: &Expr,
-  ) -> Value {
-    let kind = &rhs.kind;
-    let lhs = self.translate_expr(lhs);
-    let rhs = self.translate_expr(rhs);
-
-    match &op.node {
-      BinOpKind::Add => self.translate_expr_bin_op_add(kind, lhs, rhs),
-      BinOpKind::Sub => self.translate_expr_bin_op_sub(kind, lhs, rhs),
-      BinOpKind::Mul => self.translate_expr_bin_op_mul(kind, lhs, rhs),
-      BinOpKind::Div => self.translate_expr_bin_op_div(kind, lhs, rhs),
-      BinOpKind::Rem => self.translate_expr_bin_op_rem(lhs, rhs),
-      BinOpKind::Lt => self.translate_expr_bin_op_lt(kind, lhs, rhs),
-      BinOpKind::Gt => self.translate_expr_bin_op_gt(kind, lhs, rhs),
-      BinOpKind::Le => self.translate_expr_bin_op_le(kind, lhs, rhs),
-      BinOpKind::Ge => self.translate_expr_bin_op_ge(kind, lhs, rhs),
-      BinOpKind::Eq => self.translate_expr_bin_op_eq(kind, lhs, rhs),
-      BinOpKind::Ne => self.translate_expr_bin_op_ne(kind, lhs, rhs),
-      BinOpKind::Or => self.translate_expr_bin_op_or(lhs, op, rhs),
-      BinOpKind::And => self.translate_expr_bin_op_and(lhs, op, rhs),
-      BinOpKind::Shl => self.translate_expr_bin_op_shl(lhs, rhs),
-      BinOpKind::Shr => self.translate_expr_bin_op_shr(lhs, rhs),
-      BinOpKind::BitAnd => self.translate_expr_bin_op_bit_and(lhs, rhs),
-      BinOpKind::BitXor => self.translate_expr_bin_op_bit_xor(lhs, rhs),
-      BinOpKind::BitOr => self.translate_expr_bin_op_bit_or(lhs, rhs),
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {op}")),
-    }
-  }
+    local: Local,
+    rvalue: &Rvalue,
+  ) -> Result<Value, String> {
+    let value = match rvalue {
+      Rvalue::Use(operand) => match operand {
+        Operand::Const(Const::Void) => {
+          self.translate_zero(self.body.local_ty(local))
+        }
+        _ => self.translate_operand(operand)?,
+      },
+      Rvalue::UnOp(op, operand) => self.translate_un_op(*op, operand)?,
+      Rvalue::BinOp(op, lhs, rhs) => self.translate_bin_op(*op, lhs, rhs)?,
+      Rvalue::Call(callee, inputs) => {
+        self.translate_call(local, callee, inputs)?
+      }
+      Rvalue::Val(name) => match self.vals.get(name) {
+        Some(val) => self.translate_val(val),
+        None => return Err(format!("the val `{name}` does not exist")),
+      },
+      Rvalue::Cast(operand, ty) => self.translate_cast(operand, *ty)?,
+    };
 
-  fn translate_expr_bin_op_add(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+    Ok(value)
+  }
 
-    match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().iadd(lhs, rhs),
-      LitKind::Real(_real) => self.builder.ins().fadd(lhs, rhs),
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
+  fn translate_operand(&mut self, operand: &Operand) -> Result<Value, String> {
+    match operand {
+      Operand::Copy(local) => Ok(self.builder.use_var(self.vars[local.0])),
+      Operand::Const(constant) => self.translate_const(constant),
     }
   }
 
-  fn translate_expr_bin_op_sub(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+  fn translate_const(&mut self, constant: &Const) -> Result<Value, String> {
+    let value = match constant {
+      Const::Void => self.translate_zero(Ty::Void),
+      Const::Bool(boolean) => self.builder.ins().bconst(types::B1, *boolean),
+      Const::Int(int, ty) => {
+        let clif_type = TypeBuilder::from_mir(self.module, *ty);
 
-    match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().isub(lhs, rhs),
-      LitKind::Real(_real) => self.builder.ins().fsub(lhs, rhs),
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
-    }
+        self.builder.ins().iconst(clif_type, *int)
+      }
+      Const::Real(real, Ty::F32) => self.builder.ins().f32const(*real as f32),
+      Const::Real(real, _) => self.builder.ins().f64const(*real),
+      Const::Char(char) => {
+        self.builder.ins().iconst(types::I32, *char as u32 as i64)
+      }
+      // fixme #1
+      Const::Str(string) => self.data_builder.create_data(
+        &mut self.builder,
+        self.module,
+        self.globals,
+        string,
+      )?,
+    };
+
+    Ok(value)
   }
 
-  fn translate_expr_bin_op_mul(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+  /// the value of a `void` is a zero of its cranelift type
+  fn translate_zero(&mut self, ty: Ty) -> Value {
+    match ty {
+      Ty::Bool => self.builder.ins().bconst(types::B1, false),
+      Ty::Real => self.builder.ins().f64const(0.0),
+      Ty::F32 => self.builder.ins().f32const(0.0),
+      _ => {
+        let clif_type = TypeBuilder::from_mir(self.module, ty);
 
-    match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().imul(lhs, rhs),
-      LitKind::Real(_real) => self.builder.ins().fmul(lhs, rhs),
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
+        self.builder.ins().iconst(clif_type, 0)
+      }
     }
   }
 
-  fn translate_expr_bin_op_div(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+  /// loads a top-level `val` from its read-only data, a `str` is its address
+  fn translate_val(&mut self, val: &CompiledVal) -> Value {
+    let pointer_type = self.module.target_config().pointer_type();
+    let data = self.module.declare_data_in_func(val.id, self.builder.func);
+    let address = self.builder.ins().symbol_value(pointer_type, data);
 
-    match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().sdiv(lhs, rhs),
-      LitKind::Real(_real) => self.builder.ins().fdiv(lhs, rhs),
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
-    }
-  }
+    let mut flags = MemFlags::trusted();
 
-  fn translate_expr_bin_op_rem(&mut self, lhs: Value, rhs: Value) -> Value {
-    self.builder.ins().srem(lhs, rhs)
-  }
+    flags.set_readonly();
 
-  fn translate_expr_bin_op_lt(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+    match val.constant {
+      Constant::Bool(_) => {
+        let value = self.builder.ins().load(types::I8, flags, address, 0);
 
-    let boolean = match &lit.kind {
-      LitKind::Int(_int) => {
-        self.builder.ins().icmp(IntCC::SignedLessThan, lhs, rhs)
+        self.builder.ins().icmp_imm(IntCC::NotEqual, value, 0)
       }
-      LitKind::Real(_real) => {
-        self.builder.ins().fcmp(FloatCC::LessThan, lhs, rhs)
+      Constant::Int(..) => {
+        self.builder.ins().load(types::I64, flags, address, 0)
       }
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
-    };
-
-    self.builder.ins().bint(self.ty, boolean)
-  }
-
-  fn translate_expr_bin_op_gt(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
-
-    let boolean = match &lit.kind {
-      LitKind::Int(_int) => {
-        self.builder.ins().icmp(IntCC::SignedGreaterThan, lhs, rhs)
+      Constant::Real(..) => {
+        self.builder.ins().load(types::F64, flags, address, 0)
       }
-      LitKind::Real(_real) => {
-        self.builder.ins().fcmp(FloatCC::GreaterThan, lhs, rhs)
+      Constant::Char(_) => {
+        self.builder.ins().load(types::I32, flags, address, 0)
       }
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
-    };
-
-    self.builder.ins().bint(self.ty, boolean)
+      Constant::Str(_) => address,
+    }
   }
 
-  fn translate_expr_bin_op_le(
+  /// a call of a function without output gives the zero of the assigned local
+  fn translate_call(
     &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
-
-    let boolean = match &lit.kind {
-      LitKind::Int(_int) => {
-        self
-          .builder
-          .ins()
-          .icmp(IntCC::SignedLessThanOrEqual, lhs, rhs)
-      }
-      LitKind::Real(_real) => {
-        self.builder.ins().fcmp(FloatCC::LessThanOrEqual, lhs, rhs)
-      }
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
+    local: Local,
+    callee: &str,
+    inputs: &[Operand],
+  ) -> Result<Value, String> {
+    let Some(fun) = self.funs.get(callee) else {
+      return Err(format!("the function `{callee}` does not exist"));
     };
 
-    self.builder.ins().bint(self.ty, boolean)
-  }
+    let callee_ref =
+      self.module.declare_func_in_func(fun.id, self.builder.func);
 
-  fn translate_expr_bin_op_ge(
-    &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
+    let inputs = inputs
+      .iter()
+      .map(|input| self.translate_operand(input))
+      .collect::<Result<Vec<_>, _>>()?;
 
-    let boolean = match &lit.kind {
-      LitKind::Int(_int) => {
-        self
-          .builder
-          .ins()
-          .icmp(IntCC::SignedGreaterThanOrEqual, lhs, rhs)
-      }
-      LitKind::Real(_real) => {
-        self
-          .builder
-          .ins()
-          .fcmp(FloatCC::GreaterThanOrEqual, lhs, rhs)
-      }
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
-    };
+    let call_instruction = self.builder.ins().call(callee_ref, &inputs);
 
-    self.builder.ins().bint(self.ty, boolean)
+    match self.builder.inst_results(call_instruction).first() {
+      Some(value) => Ok(*value),
+      None => Ok(self.translate_zero(self.body.local_ty(local))),
+    }
   }
 
-  fn translate_expr_bin_op_eq(
+  fn translate_un_op(
     &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
-
-    let boolean = match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().icmp(IntCC::Equal, lhs, rhs),
-      LitKind::Real(_real) => self.builder.ins().fcmp(FloatCC::Equal, lhs, rhs),
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
+    op: UnOp,
+    operand: &Operand,
+  ) -> Result<Value, String> {
+    let ty = self.body.operand_ty(operand);
+    let value = self.translate_operand(operand)?;
+
+    let value = match (op, ty) {
+      (UnOp::Neg, _) if ty.is_float() => self.builder.ins().fneg(value),
+      (UnOp::Neg, _) => self.builder.ins().ineg(value),
+      (UnOp::Not, Ty::Bool) => {
+        let value = self.builder.ins().bint(types::I8, value);
+
+        self.builder.ins().icmp_imm(IntCC::Equal, value, 0)
+      }
+      (UnOp::Not, _) => self.builder.ins().bnot(value),
     };
 
-    self.builder.ins().bint(self.ty, boolean)
+    Ok(value)
   }
 
-  fn translate_expr_bin_op_ne(
+  fn translate_bin_op(
     &mut self,
-    kind: &ExprKind,
-    lhs: Value,
-    rhs: Value,
-  ) -> Value {
-    let ExprKind::Lit(lit) = kind else { unreachable!() };
-
-    let boolean = match &lit.kind {
-      LitKind::Int(_int) => self.builder.ins().icmp(IntCC::NotEqual, lhs, rhs),
-      LitKind::Real(_real) => {
-        self.builder.ins().fcmp(FloatCC::NotEqual, lhs, rhs)
+    op: BinOp,
+    lhs: &Operand,
+    rhs: &Operand,
+  ) -> Result<Value, String> {
+    let ty = self.body.operand_ty(lhs);
+    let lhs = self.translate_operand(lhs)?;
+    let rhs = self.translate_operand(rhs)?;
+
+    let value = match ty {
+      Ty::Real | Ty::F32 => self.translate_bin_op_real(op, lhs, rhs),
+      Ty::Bool => {
+        // the comparisons of cranelift are defined on integers
+        let lhs = self.builder.ins().bint(types::I8, lhs);
+        let rhs = self.builder.ins().bint(types::I8, rhs);
+        let value = self.translate_bin_op_int(op, lhs, rhs, false);
+
+        match op.is_comparison() {
+          true => value,
+          false => self.builder.ins().icmp_imm(IntCC::NotEqual, value, 0),
+        }
       }
-      _ => panic!("{}", format!("🤖 unexpected binary operation: {kind}")),
+      _ => self.translate_bin_op_int(op, lhs, rhs, ty.is_unsigned()),
     };
 
-    self.builder.ins().bint(self.ty, boolean)
-  }
-
-  fn translate_expr_bin_op_or(
-    &mut self,
-    lhs: Value,
-    op: &BinOp,
-    rhs: Value,
-  ) -> Value {
-    self.translate_bin_op_logical(lhs, op, rhs)
+    Ok(value)
   }
 
-  fn translate_expr_bin_op_and(
+  /// the unsigned integers are divided, shifted and compared as unsigned
+  fn translate_bin_op_int(
     &mut self,
+    op: BinOp,
     lhs: Value,
-    op: &BinOp,
     rhs: Value,
+    is_unsigned: bool,
   ) -> Value {
-    self.translate_bin_op_logical(lhs, op, rhs)
+    let ins = self.builder.ins();
+
+    match (op, is_unsigned) {
+      (BinOp::Add, _) => ins.iadd(lhs, rhs),
+      (BinOp::Sub, _) => ins.isub(lhs, rhs),
+      (BinOp::Mul, _) => ins.imul(lhs, rhs),
+      (BinOp::Div, false) => ins.sdiv(lhs, rhs),
+      (BinOp::Div, true) => ins.udiv(lhs, rhs),
+      (BinOp::Rem, false) => ins.srem(lhs, rhs),
+      (BinOp::Rem, true) => ins.urem(lhs, rhs),
+      (BinOp::Shl, _) => ins.ishl(lhs, rhs),
+      (BinOp::Shr, false) => ins.sshr(lhs, rhs),
+      (BinOp::Shr, true) => ins.ushr(lhs, rhs),
+      (BinOp::BitAnd, _) => ins.band(lhs, rhs),
+      (BinOp::BitOr, _) => ins.bor(lhs, rhs),
+      (BinOp::BitXor, _) => ins.bxor(lhs, rhs),
+      (BinOp::Lt, false) => ins.icmp(IntCC::SignedLessThan, lhs, rhs),
+      (BinOp::Lt, true) => ins.icmp(IntCC::UnsignedLessThan, lhs, rhs),
+      (BinOp::Gt, false) => ins.icmp(IntCC::SignedGreaterThan, lhs, rhs),
+      (BinOp::Gt, true) => ins.icmp(IntCC::UnsignedGreaterThan, lhs, rhs),
+      (BinOp::Le, false) => ins.icmp(IntCC::SignedLessThanOrEqual, lhs, rhs),
+      (BinOp::Le, true) => ins.icmp(IntCC::UnsignedLessThanOrEqual, lhs, rhs),
+      (BinOp::Ge, false) => ins.icmp(IntCC::SignedGreaterThanOrEqual, lhs, rhs),
+      (BinOp::Ge, true) => {
+        ins.icmp(IntCC::UnsignedGreaterThanOrEqual, lhs, rhs)
+      }
+      (BinOp::Eq, _) => ins.icmp(IntCC::Equal, lhs, rhs),
+      (BinOp::Ne, _) => ins.icmp(IntCC::NotEqual, lhs, rhs),
+    }
   }
 
-  fn translate_bin_op_logical(
+  fn translate_bin_op_real(
     &mut self,
+    op: BinOp,
     lhs: Value,
-    op: &BinOp,
     rhs: Value,
   ) -> Value {
-    let body_block = self.builder.create_block();
-    let merge_block = self.builder.create_block();
-
-    self.builder.append_block_param(merge_block, self.ty);
-
-    match op.node {
-      BinOpKind::And => self.builder.ins().brnz(lhs, body_block, &[]),
-      BinOpKind::Or => self.builder.ins().brz(lhs, body_block, &[]),
+    let ins = self.builder.ins();
+
+    match op {
+      BinOp::Add => ins.fadd(lhs, rhs),
+      BinOp::Sub => ins.fsub(lhs, rhs),
+      BinOp::Mul => ins.fmul(lhs, rhs),
+      BinOp::Div => ins.fdiv(lhs, rhs),
+      BinOp::Lt => ins.fcmp(FloatCC::LessThan, lhs, rhs),
+      BinOp::Gt => ins.fcmp(FloatCC::GreaterThan, lhs, rhs),
+      BinOp::Le => ins.fcmp(FloatCC::LessThanOrEqual, lhs, rhs),
+      BinOp::Ge => ins.fcmp(FloatCC::GreaterThanOrEqual, lhs, rhs),
+      BinOp::Eq => ins.fcmp(FloatCC::Equal, lhs, rhs),
+      BinOp::Ne => ins.fcmp(FloatCC::NotEqual, lhs, rhs),
+      // the lowering rejects the other operations on the `real`s
       _ => unreachable!(),
-    };
-
-    self.builder.ins().jump(merge_block, &[lhs]);
-    self.builder.seal_block(body_block);
-    self.builder.switch_to_block(body_block);
-    self.builder.ins().jump(merge_block, &[rhs]);
-    self.builder.seal_block(merge_block);
-    self.builder.switch_to_block(merge_block);
-    self.builder.block_params(merge_block)[0]
-  }
-
-  fn translate_expr_bin_op_shl(&mut self, lhs: Value, rhs: Value) -> Value {
-    self.builder.ins().ishl(lhs, rhs)
-  }
-
-  fn translate_expr_bin_op_shr(&mut self, lhs: Value, rhs: Value) -> Value {
-    self.builder.ins().sshr(lhs, rhs)
-  }
-
-  fn translate_expr_bin_op_bit_and(&mut self, lhs: Value, rhs: Value) -> Value {
-    self.builder.ins().band(lhs, rhs)
-  }
-
-  fn translate_expr_bin_op_bit_xor(&mut self, lhs: Value, rhs: Value) -> Value {
-    self.builder.ins().bxor(lhs, rhs)
-  }
-
-  fn translate_expr_bin_op_bit_or(&mut self, lhs: Value, rhs: Value) -> Value {
-    self.builder.ins().bor(lhs, rhs)
+    }
   }
 
-  fn translate_expr_assign(
+  /// converts a value by `as`, an integer is extended by its sign or by zeros
+  /// and a real is truncated toward zero, saturated to 64 bits and wrapped
+  fn translate_cast(
     &mut self,
-    lhs: &Expr,
-    _op: &BinOp,
-    rhs: &Expr,
-  ) -> Value {
-    let rhs = self.translate_expr(rhs);
-    let variable = self.vars.get(&lhs.to_string()).unwrap();
-
-    self.builder.def_var(*variable, rhs);
-
-    rhs
-  }
+    operand: &Operand,
+    ty: Ty,
+  ) -> Result<Value, String> {
+    let from = self.body.operand_ty(operand);
+    let value = self.translate_operand(operand)?;
+    let clif_type = TypeBuilder::from_mir(self.module, ty);
+
+    // every `u8` is a unicode scalar value, the other integers are checked
+    if ty == Ty::Char && from.is_integer() && from != Ty::U8 {
+      return Ok(self.translate_int_to_char(value, from));
+    }
 
-  fn translate_expr_assign_op(
-    &mut self,
-    lhs: &Expr,
-    op: &BinOp,
-    rhs: &Expr,
-  ) -> Value {
-    let kind = &rhs.kind;
-    let rhs = self.translate_expr(rhs);
-
-    match &lhs.kind {
-      ExprKind::Identifier(name) => {
-        let variable = *self.vars.get(&name.to_string()).unwrap();
-        let lhs = self.translate_expr(lhs);
-
-        let rhs = match &op.node {
-          BinOpKind::Add => self.translate_expr_bin_op_add(kind, lhs, rhs),
-          BinOpKind::Sub => self.translate_expr_bin_op_sub(kind, lhs, rhs),
-          BinOpKind::Mul => self.translate_expr_bin_op_mul(kind, lhs, rhs),
-          BinOpKind::Div => self.translate_expr_bin_op_div(kind, lhs, rhs),
-          BinOpKind::Rem => self.translate_expr_bin_op_rem(lhs, rhs),
-          BinOpKind::BitAnd => self.translate_expr_bin_op_bit_and(lhs, rhs),
-          BinOpKind::BitXor => self.translate_expr_bin_op_bit_xor(lhs, rhs),
-          BinOpKind::BitOr => self.translate_expr_bin_op_bit_or(lhs, rhs),
-          _ => panic!("{}", format!("🤖 unexpected assign operation: {op}")),
+    // a `char` is converted as its scalar value, a `u32`
+    let scalar = |ty: Ty| if ty == Ty::Char { Ty::U32 } else { ty };
+    let (from, ty) = (scalar(from), scalar(ty));
+    let ins = self.builder.ins();
+
+    let value = match (from, ty) {
+      _ if from == ty => value,
+      _ if from.is_integer() && ty.is_integer() => {
+        match from.bits().cmp(&ty.bits()) {
+          Ordering::Less if from.is_signed() => ins.sextend(clif_type, value),
+          Ordering::Less => ins.uextend(clif_type, value),
+          Ordering::Greater => ins.ireduce(clif_type, value),
+          Ordering::Equal => value,
+        }
+      }
+      _ if from.is_signed() && ty.is_float() => {
+        ins.fcvt_from_sint(clif_type, value)
+      }
+      _ if from.is_unsigned() && ty.is_float() => {
+        ins.fcvt_from_uint(clif_type, value)
+      }
+      (Ty::F32, Ty::Real) => ins.fpromote(clif_type, value),
+      (Ty::Real, Ty::F32) => ins.fdemote(clif_type, value),
+      _ if from.is_float() && ty.is_integer() => {
+        let value = match ty.is_unsigned() {
+          true => ins.fcvt_to_uint_sat(types::I64, value),
+          false => ins.fcvt_to_sint_sat(types::I64, value),
         };
 
-        self.builder.def_var(variable, rhs);
-
-        rhs
+        match ty.bits() {
+          64 => value,
+          _ => self.builder.ins().ireduce(clif_type, value),
+        }
       }
-      _ => unreachable!(),
-    }
-  }
-
-  fn translate_expr_decl(&mut self, decl: &Decl) -> Value {
-    let value = self.translate_expr(&decl.value);
-
-    let variable = self.variable_builder.create_variable(
-      &mut self.builder,
-      value,
-      types::I64,
-    );
-
-    self.vars.insert(decl.pattern.to_string(), variable);
-
-    let variable_shadowed = self.vars.remove(&decl.pattern.to_string());
-
-    if let Some(variable) = variable_shadowed {
-      self.vars.insert(decl.pattern.to_string(), variable);
-    }
-
-    value
-  }
-
-  fn translate_expr_block(&mut self, block: &Block) -> Value {
-    let mut value = self.translate_expr_lit_int(&0);
-
-    for expr in &block.exprs {
-      value = self.translate_expr(expr);
-    }
-
-    value
-  }
-
-  fn translate_expr_loop(&mut self, body: &Block) -> Value {
-    let body_block = self.builder.create_block();
-    let end_block = self.builder.create_block();
-
-    self.builder.ins().jump(body_block, &[]);
-    self.builder.switch_to_block(body_block);
-    self.blocks.push(end_block);
-    self.builder.switch_to_block(body_block);
-
-    for expr in &body.exprs {
-      self.translate_expr(expr);
-    }
-
-    self.builder.ins().jump(body_block, &[]);
-    self.blocks.pop();
-    self.builder.seal_block(body_block);
-    self.builder.seal_block(end_block);
-    self.builder.switch_to_block(end_block);
-    self.builder.ins().iconst(self.ty, 0)
-  }
-
-  fn translate_expr_while(&mut self, condition: &Expr, body: &Block) -> Value {
-    let header_block = self.builder.create_block();
-    let body_block = self.builder.create_block();
-    let end_block = self.builder.create_block();
-
-    self.builder.ins().jump(header_block, &[]);
-    self.builder.switch_to_block(header_block);
-
-    let condition_value = self.translate_expr(condition);
-
-    self.builder.ins().brz(condition_value, end_block, &[]);
-    self.builder.ins().jump(body_block, &[]);
-    self.blocks.push(end_block);
-    self.builder.seal_block(body_block);
-    self.builder.switch_to_block(body_block);
-
-    for expr in &body.exprs {
-      self.translate_expr(expr);
-    }
-
-    self.builder.ins().jump(header_block, &[]);
-    self.blocks.pop();
-    self.builder.seal_block(header_block);
-    self.builder.seal_block(end_block);
-    self.builder.switch_to_block(end_block);
-    self.builder.ins().iconst(self.ty, 0)
-  }
-
-  fn translate_expr_return(&mut self, maybe_expr: &Option<Fsp<Expr>>) -> Value {
-    let mut value = self.translate_expr_lit_int(&0);
-
-    if let Some(e) = maybe_expr {
-      value = self.translate_expr(e);
-
-      self.builder.ins().return_(&[value]);
-    } else {
-      self.builder.ins().return_(&[]);
-    }
-
-    let new_block = self.builder.create_block();
-
-    self.builder.seal_block(new_block);
-    self.builder.switch_to_block(new_block);
-
-    value
-  }
-
-  fn translate_expr_break(&mut self, maybe_expr: &Option<Fsp<Expr>>) -> Value {
-    let mut value = self.translate_expr_lit_int(&0);
-    let end_block = *self.blocks.last().unwrap();
-
-    if let Some(expr) = maybe_expr {
-      value = self.translate_expr(expr);
-
-      self.builder.ins().jump(end_block, &[value]);
-    } else {
-      self.builder.ins().jump(end_block, &[]);
-    }
-
-    let new_block = self.builder.create_block();
-
-    self.builder.seal_block(new_block);
-    self.builder.switch_to_block(new_block);
-
-    value
-  }
-
-  fn translate_expr_continue(&mut self) -> Value {
-    let end_block = *self.blocks.last().unwrap();
-
-    self.builder.ins().jump(end_block, &[]);
-
-    let new_block = self.builder.create_block();
-
-    self.builder.seal_block(new_block);
-    self.builder.switch_to_block(new_block);
-    self.translate_expr_lit_int(&0)
-  }
-
-  fn translate_expr_when(
-    &mut self,
-    condition: &Fsp<Expr>,
-    consequence: &Fsp<Expr>,
-    alternative: &Fsp<Expr>,
-  ) -> Value {
-    self.translate_conditional(
-      condition,
-      consequence,
-      &Some(alternative.clone()),
-    )
-  }
+      (Ty::Bool, _) if ty.is_integer() => ins.bint(clif_type, value),
+      (_, Ty::Bool) if from.is_integer() => {
+        ins.icmp_imm(IntCC::NotEqual, value, 0)
+      }
+      _ => return Err(format!("the cast of `{from}` to `{ty}` is invalid")),
+    };
 
-  fn translate_expr_if_else(
-    &mut self,
-    condition: &Expr,
-    consequence: &Expr,
-    maybe_alternative: &Option<Fsp<Expr>>,
-  ) -> Value {
-    self.translate_conditional(condition, consequence, maybe_alternative)
+    Ok(value)
   }
 
-  fn translate_conditional(
-    &mut self,
-    condition: &Expr,
-    consequence: &Expr,
-    maybe_alternative: &Option<Fsp<Expr>>,
-  ) -> Value {
-    let cond_block = self.builder.create_block();
-    let cons_block = self.builder.create_block();
-    let merge_block = self.builder.create_block();
-
-    self.builder.append_block_param(merge_block, types::I64);
-
-    let condition = self.translate_expr(condition);
-
-    self.builder.ins().brz(condition, cons_block, &[]);
-    self.builder.ins().jump(cond_block, &[]);
-
-    self.builder.switch_to_block(cond_block);
-    self.builder.seal_block(cond_block);
-
-    let consequence = self.translate_expr(consequence);
-
-    self.builder.ins().jump(merge_block, &[consequence]);
-
-    self.builder.switch_to_block(cons_block);
-    self.builder.seal_block(cons_block);
-
-    let mut alternative = self.translate_expr_lit_int(&0);
-
-    if let Some(alt) = &maybe_alternative {
-      alternative = self.translate_expr(alt);
+  /// the `char` of an integer, an integer which is not a unicode scalar value
+  /// is the replacement char `U+FFFD`
+  fn translate_int_to_char(&mut self, value: Value, from: Ty) -> Value {
+    let value = match from.bits() {
+      64 => value,
+      _ if from.is_signed() => self.builder.ins().sextend(types::I64, value),
+      _ => self.builder.ins().uextend(types::I64, value),
     };
 
-    self.builder.ins().jump(merge_block, &[alternative]);
-
-    self.builder.switch_to_block(merge_block);
-    self.builder.seal_block(merge_block);
-
-    self.builder.block_params(merge_block)[0]
-  }
-
-  fn translate_expr_lambda(
-    &mut self,
-    _args: &[Fsp<Expr>],
-    _block_or_expr: &Expr,
-  ) -> Value {
-    todo!()
-  }
+    let replacement = char::REPLACEMENT_CHARACTER as i64;
+    let replacement = self.builder.ins().iconst(types::I64, replacement);
 
-  // todo: nyf! not yet finished
-  fn translate_expr_array(&mut self, elements: &[Fsp<Expr>]) -> Value {
-    for (x, element) in elements.iter().enumerate() {
-      let element_value = self.translate_expr(element);
-      let offset = types::I64.bytes() as i32 * x as i32;
+    // the scalar values are up to `10ffff` without the surrogates `d800` to
+    // `dfff`
+    let in_range = self.builder.ins().icmp_imm(
+      IntCC::UnsignedLessThanOrEqual,
+      value,
+      0x10ffff,
+    );
 
-      self.builder.ins().stack_store(
-        element_value,
-        StackSlot::from_u32(x as u32),
-        Offset32::new(offset),
-      );
-    }
+    let offset = self.builder.ins().iadd_imm(value, -0xd800);
 
-    self.translate_expr_lit_int(&0)
-  }
+    let surrogate =
+      self
+        .builder
+        .ins()
+        .icmp_imm(IntCC::UnsignedLessThan, offset, 0x800);
 
-  fn translate_expr_array_access(
-    &mut self,
-    _indexed: &Expr,
-    _index: &Expr,
-  ) -> Value {
-    todo!()
-  }
+    let value = self.builder.ins().select(surrogate, replacement, value);
+    let value = self.builder.ins().select(in_range, value, replacement);
 
-  fn translate_expr_stmt(&mut self, stmt: &Stmt) -> Value {
-    self.translate_stmt(stmt)
+    self.builder.ins().ireduce(types::I32, value)
   }
 }
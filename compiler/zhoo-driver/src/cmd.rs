@@ -1,5 +1,5 @@
 mod handler;
-mod settings;
+pub(crate) mod settings;
 
 use clap::{Parser, Subcommand};
 use pollster::block_on;
@@ -13,7 +13,13 @@ pub struct Cmd {
 
 #[derive(Subcommand)]
 pub enum Command {
+  /// compile a program into an executable
   Compile(handler::Compile),
+  /// build the project of the current directory from its `zhoo.toml`
+  Build(handler::Build),
+  /// create a project with its manifest and its entry file
+  New(handler::New),
+  /// run the executable of the project, or interpret a program
   Run(handler::Run),
 }
 
@@ -25,6 +31,8 @@ impl Cmd {
   async fn cmd(&self) {
     match self.command {
       Command::Compile(ref command) => command.handle().await,
+      Command::Build(ref command) => command.handle().await,
+      Command::New(ref command) => command.handle().await,
       Command::Run(ref command) => command.handle().await,
     }
   }
@@ -1,7 +1,11 @@
+mod build;
 mod compile;
+mod new;
 mod run;
 
+pub use build::Build;
 pub use compile::Compile;
+pub use new::New;
 pub use run::Run;
 
 use lazy_static::lazy_static;
@@ -0,0 +1,76 @@
+use super::compile::{compile, Options};
+
+use crate::manifest::Manifest;
+
+#[derive(clap::Parser)]
+pub struct Build {
+  #[clap(flatten)]
+  options: Options,
+}
+
+impl Build {
+  pub async fn handle(&self) {
+    use crate::cmd::settings::Backend;
+    use crate::common::{EXIT_FAILURE, EXIT_SUCCESS};
+
+    use zhoo_codegen_cranelift::cranelift::Mode;
+    use zhoo_helper::constant::{MANIFEST_FILE, PATH_TARGET_DIRECTORY};
+
+    use std::process;
+
+    let (path_root, manifest) = match Manifest::current() {
+      Ok(project) => project,
+      Err(error) => {
+        eprintln!("{error}");
+        eprintln!("🤖 i couldn't find a project to build\n");
+        process::exit(EXIT_FAILURE);
+      }
+    };
+
+    let package = &manifest.package;
+
+    let backend = match Backend::try_from(package.backend.as_str()) {
+      Ok(backend) => backend,
+      Err(error) => {
+        eprintln!(
+          "invalid `{}`: {error}",
+          path_root.join(MANIFEST_FILE).display()
+        );
+        eprintln!("🤖 i couldn't build `{}`\n", package.name);
+        process::exit(EXIT_FAILURE);
+      }
+    };
+
+    let mode = self.options.mode();
+
+    let profile = match mode {
+      Mode::Dev => &manifest.profile.dev,
+      Mode::Release => &manifest.profile.release,
+    };
+
+    let mut settings = self.options.to_settings(
+      path_root.join(&package.entry).display().to_string(),
+      package.name.to_string(),
+      backend,
+    );
+
+    settings.version = Some(package.version.to_string());
+    settings.debug |= profile.debug;
+    settings.flags = [profile.flags(), settings.flags].concat();
+    settings.output_name = settings
+      .output_name
+      .or_else(|| Some(package.name.to_string()));
+
+    settings.out_dir = settings.out_dir.or_else(|| {
+      let path_out_dir =
+        path_root.join(PATH_TARGET_DIRECTORY).join(mode.to_string());
+
+      Some(path_out_dir.display().to_string())
+    });
+
+    match compile(settings).await {
+      Ok(_) => process::exit(EXIT_SUCCESS),
+      Err(_) => process::exit(EXIT_FAILURE),
+    }
+  }
+}
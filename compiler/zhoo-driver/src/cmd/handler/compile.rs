@@ -4,43 +4,91 @@ use crate::cmd::handler::{
 };
 
 use crate::cmd::settings::compile::Settings;
+use crate::cmd::settings::Backend;
+
+use zhoo_codegen_cranelift::cranelift::{Emit, Mode};
 
 use std::any::Any;
+use std::path::Path;
+use std::str::FromStr;
 use std::thread;
 
 #[derive(clap::Parser)]
 pub struct Compile {
-  /// print the AST of the program
-  #[clap(short, long)]
-  ast: bool,
   /// specify the path name of the program
   #[clap(short, long)]
   input: String,
+  /// specify the backend you want to use
+  #[clap(
+    short,
+    long,
+    default_value = "cranelift",
+    value_parser = parse_backend
+  )]
+  backend: Backend,
+  #[clap(flatten)]
+  options: Options,
+}
+
+// the options shared by `compile` and `build`
+#[derive(clap::Args)]
+pub(crate) struct Options {
+  /// print the AST of the program
+  #[clap(short, long)]
+  ast: bool,
   /// print the ir of the program
   #[clap(long)]
   ir: bool,
+  /// specify the artifacts to write in the output directory (`obj`, `clif`,
+  /// `asm`, `ast`, `mir`, `exe`)
+  #[clap(
+    long,
+    value_delimiter = ',',
+    default_value = "exe",
+    value_parser = Emit::from_str
+  )]
+  emit: Vec<Emit>,
   /// disable output animations (unimplemented)
   #[clap(long)]
   no_motion: bool,
-  /// specify the backend you want to use
-  #[clap(short, long, default_value = "cranelift")]
-  backend: String,
+  /// specify the target triple (e.g. `aarch64-unknown-linux-gnu`)
+  #[clap(long)]
+  target: Option<String>,
+  /// specify the linker (`cc`, `clang`, `ld`, `lld`), `CC` otherwise
+  #[clap(long)]
+  linker: Option<String>,
+  /// write the executable to this path
+  #[clap(short, long, value_name = "PATH")]
+  output: Option<String>,
+  /// write the artifacts in this directory
+  #[clap(long, value_name = "DIR")]
+  out_dir: Option<String>,
+  /// attach the debug info of the program (dwarf)
+  #[clap(short = 'g', long)]
+  debug: bool,
+  /// build in release mode, with optimizations
+  #[clap(long)]
+  pub release: bool,
+  /// set a cranelift setting (e.g. `-C opt_level=speed`)
+  #[clap(short = 'C', value_name = "KEY=VALUE", value_parser = parse_key_value)]
+  codegen: Vec<(String, String)>,
 }
 
 impl Compile {
   pub async fn handle(&self) {
-    use crate::cmd::settings::Backend;
     use crate::common::{EXIT_FAILURE, EXIT_SUCCESS};
 
     use std::process;
 
-    let settings = Settings {
-      ast: self.ast,
-      _no_motion: self.no_motion,
-      input: self.input.to_string(),
-      ir: self.ir,
-      _backend: Backend::from(&self.backend),
-    };
+    let name = Path::new(&self.input)
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_else(|| self.input.to_string());
+
+    let settings =
+      self
+        .options
+        .to_settings(self.input.to_string(), name, self.backend);
 
     match compile(settings).await {
       Ok(_) => process::exit(EXIT_SUCCESS),
@@ -49,19 +97,89 @@ impl Compile {
   }
 }
 
-async fn compile(
+impl Options {
+  /// the settings of the compilation of a program
+  pub(crate) fn to_settings(
+    &self,
+    input: String,
+    name: String,
+    backend: Backend,
+  ) -> Settings {
+    let (output_name, out_dir) = match &self.output {
+      Some(output) => {
+        let path = Path::new(output);
+
+        let out_dir = match path.parent() {
+          Some(parent) if !parent.as_os_str().is_empty() => parent,
+          _ => Path::new("."),
+        };
+
+        (
+          path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string()),
+          Some(out_dir.display().to_string()),
+        )
+      }
+      None => (None, self.out_dir.to_owned()),
+    };
+
+    Settings {
+      ast: self.ast,
+      _no_motion: self.no_motion,
+      input,
+      name,
+      version: None,
+      ir: self.ir,
+      backend,
+      target: self.target.to_owned(),
+      linker: self.linker.to_owned(),
+      emit: self.emit.to_owned(),
+      debug: self.debug,
+      mode: self.mode(),
+      flags: self.codegen.to_owned(),
+      output_name,
+      out_dir,
+    }
+  }
+
+  pub(crate) const fn mode(&self) -> Mode {
+    if self.release {
+      Mode::Release
+    } else {
+      Mode::Dev
+    }
+  }
+}
+
+fn parse_backend(backend: &str) -> Result<Backend, String> {
+  Backend::try_from(backend)
+}
+
+fn parse_key_value(option: &str) -> Result<(String, String), String> {
+  match option.split_once('=') {
+    Some((key, value)) => Ok((key.to_string(), value.to_string())),
+    None => Err(format!("expected `key=value`, found `{option}`")),
+  }
+}
+
+pub(crate) async fn compile(
   settings: Settings,
-) -> Result<(), Box<(dyn Any + Send + 'static)>> {
+) -> Result<(), Box<dyn Any + Send + 'static>> {
   thread::spawn(move || compiling(settings)).join()
 }
 
 fn compiling(settings: Settings) {
   use zhoo_analyzer::analyzer;
   use zhoo_codegen_cranelift::cranelift;
+  use zhoo_mir::lower;
   use zhoo_parser::parser;
 
+  use crate::common::EXIT_FAILURE;
+
   use loaders::spin;
 
+  use std::process;
   use std::time::Duration;
 
   // -- todo #1 --
@@ -75,10 +193,6 @@ fn compiling(settings: Settings) {
   //
   // values between backticks should be dynamic
   //
-  // `program-name`: the name of the program from a configuration file
-  // `version`: the version of the program from a configuration file
-  // `mode`: [dev|release] --release
-  // `backend`: [cranelift|llvm]
   // `time`: the compilation time in seconds
 
   const INTERVAL: u64 = 500;
@@ -90,24 +204,58 @@ fn compiling(settings: Settings) {
   // used as a margin top
   println!();
 
-  spinner
-    .with_info(format!("{} `project-name` `version`", &*COMPILATION_START)); // todo #2
+  let project = match &settings.version {
+    Some(version) => format!("{} v{version}", settings.name),
+    None => settings.name.to_string(),
+  };
+
+  spinner.with_info(format!("{} {project}", &*COMPILATION_START));
 
   thread::sleep(Duration::from_millis(INTERVAL)); // todo #1
 
   // -- front --
 
   let program = parser::parse(settings.input);
-  let _ = analyzer::analyze(&program);
+
+  let resolutions = match analyzer::analyze(&program) {
+    Ok(resolutions) => resolutions,
+    Err(report) => {
+      spinner.stop();
+      program.reporter.raise(report)
+    }
+  };
+
+  // -- middle --
+
+  let mir = match lower::lower(&program, &resolutions) {
+    Ok(mir) => mir,
+    Err(report) => {
+      spinner.stop();
+      program.reporter.raise(report)
+    }
+  };
 
   // -- back --
 
-  let codegen = cranelift::generate(&program);
+  let codegen_settings = cranelift::Settings {
+    target: settings.target,
+    linker: settings.linker,
+    emit: settings.emit,
+    debug: settings.debug,
+    mode: settings.mode,
+    flags: settings.flags,
+    name: settings.output_name,
+    out_dir: settings.out_dir,
+  };
 
-  match codegen.build(settings.ir) {
+  match cranelift::generate(&program, mir, &codegen_settings)
+    .and_then(|codegen| codegen.build(settings.ir))
+  {
     Ok(done) => {
-      spinner
-        .with_info(format!("     {} `mode` | `backend`", &*COMPILATION_DONE)); // todo #2
+      spinner.with_info(format!(
+        "     {} {} | {}",
+        &*COMPILATION_DONE, codegen_settings.mode, settings.backend
+      ));
 
       thread::sleep(Duration::from_millis(INTERVAL)); // todo #1
 
@@ -116,8 +264,14 @@ fn compiling(settings: Settings) {
 
       thread::sleep(Duration::from_millis(INTERVAL)); // todo #1
       spinner.stop();
-      done();
-      println!("🤖 compile `program-name` successfully"); // todo #2
+
+      if let Err(report) = done() {
+        program.reporter.add_report(report);
+        eprintln!("🤖 i couldn't compile `{}`\n", settings.name);
+        program.reporter.abort_if_has_error();
+      }
+
+      println!("🤖 compile `{}` successfully", settings.name);
 
       if settings.ast {
         println!("\n{}", program);
@@ -129,7 +283,8 @@ fn compiling(settings: Settings) {
     Err(error) => {
       spinner.stop();
       eprintln!("{error}");
-      eprintln!("🤖 i couldn't compile `project-name`\n"); // todo #2
+      eprintln!("🤖 i couldn't compile `{}`\n", settings.name);
+      process::exit(EXIT_FAILURE);
     }
   }
 }
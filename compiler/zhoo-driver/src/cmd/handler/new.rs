@@ -0,0 +1,63 @@
+use crate::manifest::{self, Manifest};
+
+#[derive(clap::Parser)]
+pub struct New {
+  /// the name of the project
+  name: String,
+}
+
+impl New {
+  pub async fn handle(&self) {
+    use crate::common::{EXIT_FAILURE, EXIT_SUCCESS};
+
+    use std::process;
+
+    match new(&self.name) {
+      Ok(_) => {
+        println!("🤖 create `{}` project successfully", self.name);
+        process::exit(EXIT_SUCCESS)
+      }
+      Err(error) => {
+        eprintln!("{error}");
+        eprintln!("🤖 i couldn't create `{}` project\n", self.name);
+        process::exit(EXIT_FAILURE)
+      }
+    }
+  }
+}
+
+/// scaffolds a project with its manifest and its entry file
+fn new(name: &str) -> Result<(), String> {
+  use zhoo_helper::constant::{MANIFEST_FILE, PATH_TARGET_DIRECTORY};
+
+  use std::fs;
+  use std::path::Path;
+
+  manifest::check_name(name)?;
+
+  let path_project = Path::new(name);
+
+  if path_project.exists() {
+    return Err(format!("destination `{name}` already exists"));
+  }
+
+  let manifest = Manifest::new(name);
+  let path_entry = path_project.join(&manifest.package.entry);
+
+  let write = |path: &Path, contents: &str| {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|error| format!("{}: {error}", parent.display()))?;
+    }
+
+    fs::write(path, contents)
+      .map_err(|error| format!("{}: {error}", path.display()))
+  };
+
+  write(&path_project.join(MANIFEST_FILE), &manifest.to_toml())?;
+  write(&path_entry, "fun main() {\n  println(\"👋 🌍 👽\");\n}\n")?;
+  write(
+    &path_project.join(".gitignore"),
+    &format!("/{PATH_TARGET_DIRECTORY}\n"),
+  )
+}
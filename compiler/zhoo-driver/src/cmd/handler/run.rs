@@ -2,7 +2,18 @@ use std::any::Any;
 use std::thread;
 
 #[derive(clap::Parser)]
-pub struct Run;
+pub struct Run {
+  /// run the release build of the project
+  #[clap(long)]
+  release: bool,
+  /// interpret the program instead of running its executable
+  #[clap(long)]
+  interp: bool,
+  /// specify the path name of the program to interpret, the entry of the
+  /// project otherwise
+  #[clap(short, long, requires = "interp")]
+  input: Option<String>,
+}
 
 impl Run {
   pub async fn handle(&self) {
@@ -10,26 +21,107 @@ impl Run {
 
     use std::process;
 
-    match run().await {
+    if self.interp {
+      match interpret(self.input.to_owned()).await {
+        Ok(code) => process::exit(code),
+        Err(_) => process::exit(EXIT_FAILURE),
+      }
+    }
+
+    match run(self.release).await {
       Ok(_) => process::exit(EXIT_SUCCESS),
       Err(_) => process::exit(EXIT_FAILURE),
     }
   }
 }
 
-async fn run() -> Result<(), Box<(dyn Any + Send + 'static)>> {
-  thread::spawn(running).join()
+async fn run(release: bool) -> Result<(), Box<dyn Any + Send + 'static>> {
+  thread::spawn(move || running(release)).join()
+}
+
+async fn interpret(
+  input: Option<String>,
+) -> Result<i32, Box<dyn Any + Send + 'static>> {
+  use zhoo_interpreter::interpreter::STACK_SIZE;
+
+  // the calls of the interpreted program are nested in the stack of the
+  // interpreter
+  thread::Builder::new()
+    .stack_size(STACK_SIZE)
+    .spawn(move || interpreting(input))
+    .expect("spawn the interpreter")
+    .join()
 }
 
-fn running() {
-  use zhoo_helper::constant::{ENTRY_POINT, PATH_OUTPUT_DIRECTORY};
+fn interpreting(input: Option<String>) -> i32 {
+  use crate::common::EXIT_FAILURE;
+  use crate::manifest::Manifest;
+
+  use zhoo_analyzer::analyzer;
+  use zhoo_interpreter::interpreter;
+  use zhoo_parser::parser;
+
+  use std::io;
+  use std::process;
+
+  let input = match input {
+    Some(input) => input,
+    None => match Manifest::current() {
+      Ok((path_root, manifest)) => {
+        path_root.join(manifest.package.entry).display().to_string()
+      }
+      Err(error) => {
+        eprintln!("{error}");
+        eprintln!("🤖 i couldn't find a program to interpret\n");
+        process::exit(EXIT_FAILURE);
+      }
+    },
+  };
+
+  let program = parser::parse(input);
+  let _ = analyzer::analyze(&program);
+
+  match interpreter::interpret(&program, &mut io::stdout().lock()) {
+    Ok(code) => code,
+    Err(report) => {
+      program.reporter.add_report(report);
 
+      EXIT_FAILURE
+    }
+  }
+}
+
+fn running(release: bool) {
+  use crate::manifest::Manifest;
+
+  use zhoo_codegen_cranelift::cranelift::Mode;
+
+  use zhoo_helper::constant::{
+    ENTRY_POINT, PATH_OUTPUT_DIRECTORY, PATH_TARGET_DIRECTORY,
+  };
+
+  use std::path::Path;
   use std::process::Command;
   use std::str;
 
   println!("🤖 running the program");
 
-  let program = format!("./{PATH_OUTPUT_DIRECTORY}/{ENTRY_POINT}");
+  // the program of a project is the one built by `zhoo build`
+  let program = match Manifest::current() {
+    Ok((path_root, manifest)) => {
+      let mode = if release { Mode::Release } else { Mode::Dev };
+
+      Path::new(".")
+        .join(path_root)
+        .join(PATH_TARGET_DIRECTORY)
+        .join(mode.to_string())
+        .join(manifest.package.name)
+        .display()
+        .to_string()
+    }
+    Err(_) => format!("./{PATH_OUTPUT_DIRECTORY}/{ENTRY_POINT}"),
+  };
+
   let output = Command::new(program).output().unwrap();
   let output = str::from_utf8(&output.stdout).unwrap();
 
@@ -1,17 +1,32 @@
 pub mod compile;
 
-#[derive(Debug)]
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum Backend {
   Cranelift,
   Llvm,
 }
 
-impl From<&String> for Backend {
-  fn from(backend: &String) -> Self {
-    match backend.as_str() {
-      "llvm" => Self::Llvm,
-      "cranelift" => Self::Cranelift,
-      _ => panic!("wrong backend, expected: [cranelift]"),
+impl TryFrom<&str> for Backend {
+  type Error = String;
+
+  fn try_from(backend: &str) -> Result<Self, Self::Error> {
+    match backend {
+      "llvm" => Ok(Self::Llvm),
+      "cranelift" => Ok(Self::Cranelift),
+      _ => Err(format!(
+        "unknown backend `{backend}`, expected one of `cranelift`, `llvm`"
+      )),
+    }
+  }
+}
+
+impl fmt::Display for Backend {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Cranelift => write!(f, "cranelift"),
+      Self::Llvm => write!(f, "llvm"),
     }
   }
 }
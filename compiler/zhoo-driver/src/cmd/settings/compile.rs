@@ -1,10 +1,24 @@
 use crate::cmd::settings::Backend;
 
+use zhoo_codegen_cranelift::cranelift::{Emit, Mode};
+
 #[derive(Debug)]
 pub(crate) struct Settings {
   pub ast: bool,
   pub input: String,
+  /// the name of the program, displayed by the driver
+  pub name: String,
+  pub version: Option<String>,
   pub _no_motion: bool,
   pub ir: bool,
-  pub _backend: Backend,
+  pub backend: Backend,
+  pub target: Option<String>,
+  pub linker: Option<String>,
+  pub emit: Vec<Emit>,
+  pub debug: bool,
+  pub mode: Mode,
+  pub flags: Vec<(String, String)>,
+  /// the name of the artifacts
+  pub output_name: Option<String>,
+  pub out_dir: Option<String>,
 }
@@ -1,5 +1,9 @@
+#[cfg(test)]
+mod tests;
+
 mod cmd;
 mod common;
+mod manifest;
 
 pub use cmd::Cmd;
 
@@ -0,0 +1,172 @@
+use zhoo_helper::constant::{ENTRY_POINT, MANIFEST_FILE};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// the manifest of a project, read from `zhoo.toml`
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Manifest {
+  pub package: Package,
+  #[serde(default)]
+  pub profile: Profiles,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Package {
+  pub name: String,
+  pub version: String,
+  /// the source file of the program, relative to the manifest
+  #[serde(default = "default_entry")]
+  pub entry: PathBuf,
+  #[serde(default = "default_edition")]
+  pub edition: String,
+  #[serde(default = "default_backend")]
+  pub backend: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Profiles {
+  #[serde(default = "Profile::dev")]
+  pub dev: Profile,
+  #[serde(default)]
+  pub release: Profile,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Profile {
+  /// attach the debug info of the program
+  #[serde(default)]
+  pub debug: bool,
+  /// the cranelift settings, overridden by `-C key=value`
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub codegen: BTreeMap<String, toml::Value>,
+}
+
+impl Manifest {
+  pub fn new(name: &str) -> Self {
+    Self {
+      package: Package {
+        name: name.to_string(),
+        version: String::from("0.1.0"),
+        entry: default_entry(),
+        edition: default_edition(),
+        backend: default_backend(),
+      },
+      profile: Profiles::default(),
+    }
+  }
+
+  /// reads the manifest of the project located in a directory
+  pub fn read(path_directory: &Path) -> Result<Self, String> {
+    let path = path_directory.join(MANIFEST_FILE);
+
+    let manifest = fs::read_to_string(&path).map_err(|error| {
+      format!("could not read `{}`: {error}", path.display())
+    })?;
+
+    let manifest = toml::from_str::<Self>(&manifest)
+      .map_err(|error| format!("invalid `{}`: {error}", path.display()))?;
+
+    check_name(&manifest.package.name)
+      .map_err(|error| format!("invalid `{}`: {error}", path.display()))?;
+
+    Ok(manifest)
+  }
+
+  /// reads the manifest of the project enclosing the current directory
+  pub fn current() -> Result<(PathBuf, Self), String> {
+    let path_directory = env::current_dir().map_err(|error| {
+      format!("could not read the current directory: {error}")
+    })?;
+
+    Self::find(&path_directory)
+  }
+
+  /// reads the manifest of the project enclosing a directory, it is looked
+  /// for in the directory then in its parents. the directory of the project
+  /// is returned relative to the one given, empty when they are the same
+  pub fn find(path_directory: &Path) -> Result<(PathBuf, Self), String> {
+    let (depth, path_project) = path_directory
+      .ancestors()
+      .enumerate()
+      .find(|(_, path)| path.join(MANIFEST_FILE).is_file())
+      .ok_or_else(|| {
+        format!(
+          "could not find `{MANIFEST_FILE}` in `{}` or any parent directory",
+          path_directory.display()
+        )
+      })?;
+
+    let path_root = (0..depth).map(|_| Component::ParentDir).collect();
+
+    Ok((path_root, Self::read(path_project)?))
+  }
+
+  pub fn to_toml(&self) -> String {
+    toml::to_string(self).expect("serialize the manifest")
+  }
+}
+
+impl Default for Profiles {
+  fn default() -> Self {
+    Self {
+      dev: Profile::dev(),
+      release: Profile::default(),
+    }
+  }
+}
+
+impl Profile {
+  fn dev() -> Self {
+    Self {
+      debug: true,
+      ..Default::default()
+    }
+  }
+
+  /// the cranelift settings as `key=value` pairs
+  pub fn flags(&self) -> Vec<(String, String)> {
+    self
+      .codegen
+      .iter()
+      .map(|(key, value)| match value {
+        toml::Value::String(value) => (key.to_string(), value.to_string()),
+        value => (key.to_string(), value.to_string()),
+      })
+      .collect()
+  }
+}
+
+/// a package name is made of ascii letters, digits, `_` and `-`, it starts
+/// with a letter or `_`
+pub(crate) fn check_name(name: &str) -> Result<(), String> {
+  let is_start = |c: char| c.is_ascii_alphabetic() || c == '_';
+  let is_rest = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+
+  match name.chars().next() {
+    None => Err(String::from("the package name is empty")),
+    Some(first) if !is_start(first) => Err(format!(
+      "the package name `{name}` must start with a letter or `_`"
+    )),
+    _ if !name.chars().all(is_rest) => Err(format!(
+      "the package name `{name}` must only hold letters, digits, `_` or `-`"
+    )),
+    _ => Ok(()),
+  }
+}
+
+fn default_entry() -> PathBuf {
+  PathBuf::from(format!("src/{ENTRY_POINT}.zo"))
+}
+
+fn default_edition() -> String {
+  String::from("2022")
+}
+
+fn default_backend() -> String {
+  String::from("cranelift")
+}
@@ -0,0 +1,50 @@
+use super::cmd::settings::Backend;
+use super::manifest::{check_name, Manifest};
+
+use zhoo_helper::constant::MANIFEST_FILE;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_backend() {
+  assert_eq!(Backend::try_from("cranelift"), Ok(Backend::Cranelift));
+  assert_eq!(Backend::try_from("llvm"), Ok(Backend::Llvm));
+  assert!(Backend::try_from("gcc").is_err());
+}
+
+#[test]
+fn test_check_name() {
+  for name in ["hello", "hello_world", "hello-world", "_hello", "h3llo"] {
+    assert!(check_name(name).is_ok(), "the name `{name}`");
+  }
+
+  for name in ["", "1hello", "-hello", "hello world", "hello/world", ".."] {
+    assert!(check_name(name).is_err(), "the name `{name}`");
+  }
+}
+
+#[test]
+fn test_find_manifest() {
+  let path_project =
+    env::temp_dir().join(format!("zhoo-find-manifest-{}", std::process::id()));
+
+  let path_nested = path_project.join("src").join("nested");
+
+  fs::create_dir_all(&path_nested).unwrap();
+  fs::write(
+    path_project.join(MANIFEST_FILE),
+    Manifest::new("hello").to_toml(),
+  )
+  .unwrap();
+
+  let found = Manifest::find(&path_nested)
+    .map(|(path_root, manifest)| (path_root, manifest.package.name));
+
+  fs::remove_dir_all(&path_project).unwrap();
+
+  assert_eq!(found, Ok((PathBuf::from("../.."), String::from("hello"))));
+
+  assert!(Manifest::find(Path::new("/")).is_err());
+}
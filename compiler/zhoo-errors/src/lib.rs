@@ -1,9 +1,13 @@
 mod color;
+mod link;
 mod report;
+mod runtime;
 mod semantic;
 mod syntax;
 
+pub use link::LinkKind;
 pub use report::{Report, Reporter};
+pub use runtime::RuntimeKind;
 pub use semantic::SemanticKind;
 pub use syntax::SyntaxKind;
 
@@ -0,0 +1,73 @@
+//! this module is used for the link phase of the zhoo compiler
+
+use super::report::ReportMessage;
+
+#[derive(Debug)]
+pub enum LinkKind {
+  /// generated when the linker program can not be spawned.
+  LinkerNotFound(String, String),
+
+  /// generated when the linker exits with a failure, it holds the linker, the
+  /// command line and the linker's stderr.
+  LinkFailed(String, String, String),
+
+  /// generated when the embedded core library can not be written to its
+  /// cache path, it holds the path and the error.
+  LibraryCoreNotExtracted(String, String),
+
+  /// generated when the core library of a cross target can not be built, it
+  /// holds the target and the error of `rustc`.
+  LibraryCoreNotBuilt(String, String),
+
+  /// generated when a c runtime file of a cross target is not found by the c
+  /// compiler of the target, it holds the target, the c compiler and the file.
+  CrossRuntimeNotFound(String, String, String),
+}
+
+pub(crate) fn link_report(kind: &LinkKind) -> ReportMessage {
+  use super::color::Color;
+  use super::report::{ReportKind, REPORT_ERROR};
+
+  use ariadne::Fmt;
+
+  match kind {
+    LinkKind::LinkerNotFound(linker, error) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("linker {} not found", format_args!("`{linker}`").fg(Color::hint())).fg(Color::error())),
+      vec![],
+      vec![format!("🤖 {}", error.fg(Color::note()))],
+      vec![format!("👉 {}", "install a linker (cc, clang, ld or lld), then give it with `--linker` or the `CC` env".fg(Color::help()))],
+    ),
+    LinkKind::LinkFailed(linker, command, stderr) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("linking with {} failed", format_args!("`{linker}`").fg(Color::hint())).fg(Color::error())),
+      vec![],
+      vec![
+        format!("🤖 {}", command.fg(Color::note())),
+        stderr.trim_end().to_string(),
+      ],
+      vec![],
+    ),
+    LinkKind::CrossRuntimeNotFound(target, cc, file) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("the c runtime of {} not found", format_args!("`{target}`").fg(Color::hint())).fg(Color::error())),
+      vec![],
+      vec![format!("🤖 {}", format_args!("`ld` and `lld` link `{file}` of the target, it's given by `{cc}`").fg(Color::note()))],
+      vec![format!("👉 {}", "install the gnu toolchain of the target, or link with `clang` or the c compiler of the target".fg(Color::help()))],
+    ),
+    LinkKind::LibraryCoreNotExtracted(path, error) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("could not extract the core library to {}", format_args!("`{path}`").fg(Color::hint())).fg(Color::error())),
+      vec![],
+      vec![format!("🤖 {}", error.fg(Color::note()))],
+      vec![format!("👉 {}", "give a writable cache directory with the `ZHOO_CACHE_DIR` env".fg(Color::help()))],
+    ),
+    LinkKind::LibraryCoreNotBuilt(target, error) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("could not build the core library of {}", format_args!("`{target}`").fg(Color::hint())).fg(Color::error())),
+      vec![],
+      vec![error.trim_end().to_string()],
+      vec![format!("👉 {}", format_args!("install the standard library of the target with `rustup target add {target}`").fg(Color::help()))],
+    ),
+  }
+}
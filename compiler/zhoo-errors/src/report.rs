@@ -1,6 +1,8 @@
 //! this module is used to display report error messages
 
 use super::color::Color;
+use super::link::{link_report, LinkKind};
+use super::runtime::{runtime_report, RuntimeKind};
 use super::semantic::{semantic_report, SemanticKind};
 use super::syntax::{syntax_report, SyntaxKind};
 
@@ -36,6 +38,8 @@ pub enum Report {
   Io(io::Error),
   Syntax(SyntaxKind),
   Semantic(SemanticKind),
+  Link(LinkKind),
+  Runtime(RuntimeKind),
 }
 
 impl fmt::Display for Report {
@@ -50,6 +54,8 @@ impl Report {
       Self::Io(_) => 0, // this case will never be used because in io case we just panic
       Self::Syntax(_) => 1,
       Self::Semantic(_) => 2,
+      Self::Link(_) => 3,
+      Self::Runtime(_) => 4,
     }
   }
 }
@@ -83,6 +89,14 @@ impl Reporter {
     self.source_map.add(path.into())
   }
 
+  pub fn add_source_code<P: Into<PathBuf>>(
+    &mut self,
+    path: P,
+    code: &str,
+  ) -> u32 {
+    self.source_map.add_code(path.into(), code)
+  }
+
   pub fn code(&self, source_id: u32) -> &str {
     self.source_map.code(source_id)
   }
@@ -99,9 +113,12 @@ impl Reporter {
     let (kind, message, labels, notes, helps) = match report {
       Report::Syntax(ref kind) => syntax_report(kind),
       Report::Semantic(ref kind) => semantic_report(kind),
+      Report::Link(ref kind) => link_report(kind),
+      Report::Runtime(ref kind) => runtime_report(kind),
       Report::Io(error) => panic!("{error}"),
     };
 
+    let has_labels = !labels.is_empty();
     let span = labels.first().map(|label| label.0).unwrap_or(Span::ZERO);
     let source_id = self.source_id(span);
     let code = self.code(source_id);
@@ -122,12 +139,16 @@ impl Reporter {
       )
     }
 
-    for note in notes {
-      report = report.with_note(note);
-    }
+    // ariadne displays the notes and the helps below the source code, a
+    // report without labels displays them itself
+    if has_labels {
+      for note in notes.iter() {
+        report = report.with_note(note);
+      }
 
-    for help in helps {
-      report = report.with_help(help);
+      for help in helps.iter() {
+        report = report.with_help(help);
+      }
     }
 
     eprintln!();
@@ -140,6 +161,14 @@ impl Reporter {
       )
       .unwrap();
 
+    if !has_labels {
+      for note in notes.iter().chain(helps.iter()) {
+        eprintln!("{note}");
+      }
+
+      eprintln!();
+    }
+
     self.has_errors.set(true);
   }
 
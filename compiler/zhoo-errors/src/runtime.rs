@@ -0,0 +1,118 @@
+//! this module is used for the interpretation of a program by the zhoo
+//! interpreter
+
+use super::report::ReportMessage;
+
+use zhoo_span::span::Span;
+
+#[derive(Debug)]
+pub enum RuntimeKind {
+  /// generated when a builtin fails, it holds the builtin and the error.
+  BuiltinFailed(Span, String, String),
+
+  /// generated when an `int` is divided by zero.
+  DivisionByZero(Span),
+
+  /// generated when an index is outside of an array, it holds the index and
+  /// the length of the array.
+  IndexOutOfBounds(Span, i64, usize),
+
+  /// generated when an arithmetic operation traps, it holds the expression.
+  Overflow(Span, String),
+
+  /// generated when the calls exceed the depth limit, it holds the limit.
+  StackOverflow(Span, usize),
+
+  /// generated when a function has no body to interpret, it holds its name.
+  Uninterpretable(Span, String),
+
+  /// generated when a feature is only compiled, it holds the feature.
+  Unsupported(Span, String),
+}
+
+pub(crate) fn runtime_report(kind: &RuntimeKind) -> ReportMessage {
+  use super::color::Color;
+  use super::report::{ReportKind, REPORT_ERROR};
+
+  use ariadne::Fmt;
+
+  match kind {
+    RuntimeKind::BuiltinFailed(span, name, error) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("the builtin {} failed", format_args!("`{name}`").fg(Color::hint())).fg(Color::title())),
+      vec![(
+        *span,
+        error.fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![],
+    ),
+    RuntimeKind::DivisionByZero(span) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "division by zero".fg(Color::title())),
+      vec![(
+        *span,
+        "this division by zero is evaluated at runtime".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![],
+    ),
+    RuntimeKind::IndexOutOfBounds(span, index, len) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "index out of bounds".fg(Color::title())),
+      vec![(
+        *span,
+        format!("the index is {index} but the length is {len}").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![],
+    ),
+    RuntimeKind::Overflow(span, expr) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "arithmetic overflow".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{expr}` overflows at runtime").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 an `int` holds a value between {} and {}", i64::MIN.fg(Color::note()), i64::MAX.fg(Color::note()))],
+      vec![],
+    ),
+    RuntimeKind::StackOverflow(span, limit) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "stack overflow".fg(Color::title())),
+      vec![(
+        *span,
+        format!("this call exceeds the limit of {limit} nested calls").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![format!("👉 {}", "check the exit condition of the recursion".fg(Color::help()))],
+    ),
+    RuntimeKind::Uninterpretable(span, name) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", format_args!("the function {} can not be interpreted", format_args!("`{name}`").fg(Color::hint())).fg(Color::title())),
+      vec![(
+        *span,
+        "this external function has no body".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![format!("👉 {}", "compile the program to call external functions".fg(Color::help()))],
+    ),
+    RuntimeKind::Unsupported(span, what) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", format_args!("{what}").fg(Color::hint()), "are not supported by the interpreter yet".fg(Color::title())),
+      vec![(
+        *span,
+        "this expression can not be interpreted".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![format!("👉 {}", "compile the program with `zhoo compile`".fg(Color::help()))],
+    ),
+  }
+}
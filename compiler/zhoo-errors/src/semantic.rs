@@ -6,16 +6,32 @@ use zhoo_span::span::Span;
 
 #[derive(Debug)]
 pub enum SemanticKind {
+  AmbiguousOverload(Span, String, Vec<(Span, String)>),
   ArgumentsMismatch(Span, String, usize, usize, String),
+  DivisionByZero(Span),
+  EvaluationLimit(Span, String, String),
+  FieldNotFound(Span, String, String),
   FunctionNotFound(Span, String),
   IdentifierNotFound(Span, String),
+  InvalidCast(Span, String, String),
   InvalidIndex(Span, String),
+  InvalidInterpolation(Span, String),
+  InvalidSize(Span, String),
+  LiteralOutOfRange(Span, String, String),
   MainNotFound(Span, String),
   MainHasInputs(String, Span),
   NameClash(Span, String),
+  NoMatchingOverload(Span, String, String, Vec<(Span, String)>),
   NamingConvention(String, String, Span),
+  NotConstFun(Span, String),
+  NotPrintable(Span, String, String),
   OutOfLoop(Span, String),
+  Overflow(Span, String),
   TypeMismatch(Span, String, String),
+  Unsupported(Span, String),
+  UntypedLambda(Span),
+  ValCycle(Span, String),
+  ValNotConstant(Span, String),
 }
 
 pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
@@ -27,6 +43,21 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
   use ariadne::Fmt;
 
   match kind {
+    SemanticKind::AmbiguousOverload(span, name, candidates) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", "ambiguous overload of".fg(Color::title()), format_args!("`{name}`").fg(Color::hint())),
+      std::iter::once((
+        *span,
+        format!("`{name}` can be {} functions here", candidates.len()).fg(Color::error()).to_string(),
+        Color::error(),
+      ))
+      .chain(candidates.iter().map(|(span, signature)| {
+        (*span, format!("`{signature}` is a candidate").fg(Color::hint()).to_string(), Color::hint())
+      }))
+      .collect(),
+      vec![format!("🤖 the overloads of `{name}` are told apart by the types of their inputs")],
+      vec![format!("👉 {}", "cast the arguments to the inputs of one of them".fg(Color::help()))],
+    ),
     SemanticKind::ArgumentsMismatch(
       span,
       inputs,
@@ -57,6 +88,39 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
         format!("👉 {}", format_args!("try this: {should_be}").fg(Color::help())),
       ],
     ),
+    SemanticKind::DivisionByZero(span) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "division by zero".fg(Color::title())),
+      vec![(
+        *span,
+        "this division by zero is evaluated at compile time".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![],
+    ),
+    SemanticKind::EvaluationLimit(span, name, limit) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", "evaluation limit reached for".fg(Color::title()), format_args!("`{name}`").fg(Color::hint())),
+      vec![(
+        *span,
+        format!("the evaluation stops after {limit}").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 does this `const fun` ever end?")],
+      vec![],
+    ),
+    SemanticKind::FieldNotFound(span, field, ty) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", "no field".fg(Color::title()), format_args!("`{field}`").fg(Color::hint())),
+      vec![(
+        *span,
+        format!("`{ty}` has no field `{field}`").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 the fields of a tuple are numbered from 0: `pair.0`")],
+      vec![],
+    ),
     SemanticKind::FunctionNotFound(span, name) => (
       ReportKind::Error(REPORT_ERROR),
       format!("{}", format_args!("function {} not found", format_args!("`{name}`").fg(Color::hint())).fg(Color::error())),
@@ -79,6 +143,17 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
       vec![format!("🤖 are you sure you have defined it correctly because i'm stumped")],
       vec![],
     ),
+    SemanticKind::InvalidCast(span, from, to) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "invalid cast".fg(Color::title())),
+      vec![(
+        *span,
+        format!("a value of type `{from}` can not be converted to `{to}`").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 `as` converts a number to another number, an integer to a `bool` or a `char` and back, and a printable value to a `str`")],
+      vec![],
+    ),
     SemanticKind::InvalidIndex(span, ty) => (
       ReportKind::Error(REPORT_ERROR),
       format!("{}", "invalid index".fg(Color::title())),
@@ -93,6 +168,39 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
       vec![],
       vec![],
     ),
+    SemanticKind::InvalidInterpolation(span, ty) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "invalid interpolation".fg(Color::title())),
+      vec![(
+        *span,
+        format!("a value of type `{ty}` can not be written in a `str`").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 a `str` interpolates the numbers, the `bool`s, the `char`s, the `str`s and the arrays and the tuples of them")],
+      vec![],
+    ),
+    SemanticKind::InvalidSize(span, size) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "invalid size of an array".fg(Color::title())),
+      vec![(
+        *span,
+        format!("this size is `{size}`").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 the size of an array is a constant integer from 0")],
+      vec![],
+    ),
+    SemanticKind::LiteralOutOfRange(span, lit, ty) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "literal out of range".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{lit}` does not fit in the type `{ty}`").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![],
+    ),
     SemanticKind::MainNotFound(span, entry_point) => (
       ReportKind::Error(REPORT_ERROR),
       format!(
@@ -167,6 +275,43 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
       vec![],
       vec![],
     ),
+    SemanticKind::NoMatchingOverload(span, name, inputs, candidates) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", "no matching overload of".fg(Color::title()), format_args!("`{name}`").fg(Color::hint())),
+      std::iter::once((
+        *span,
+        format!("no function `{name}` takes ({inputs})").fg(Color::error()).to_string(),
+        Color::error(),
+      ))
+      .chain(candidates.iter().map(|(span, signature)| {
+        (*span, format!("`{signature}` is a candidate").fg(Color::hint()).to_string(), Color::hint())
+      }))
+      .collect(),
+      vec![format!("🤖 the overloads of `{name}` are told apart by the types of their inputs")],
+      vec![],
+    ),
+    SemanticKind::NotConstFun(span, name) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", format_args!("`{name}`").fg(Color::hint()), "is not a `const fun`".fg(Color::title())),
+      vec![(
+        *span,
+        "only a `const fun` can be called at compile time".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![format!("👉 {}", format_args!("try this: `const fun {name}`").fg(Color::help()))],
+    ),
+    SemanticKind::NotPrintable(span, name, ty) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "not printable".fg(Color::title())),
+      vec![(
+        *span,
+        format!("{} can not write a value of type `{ty}`", format_args!("`{name}`")).fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 {} writes the numbers, the `bool`s, the `char`s, the `str`s and the arrays and the tuples of them", format_args!("`{name}`"))],
+      vec![],
+    ),
     SemanticKind::OutOfLoop(span, behavior) => (
       ReportKind::Error(REPORT_ERROR),
       format!("{} {}", format_args!("`{}`", behavior.fg(Color::hint())), "outside of the loop".fg(Color::title())),
@@ -178,6 +323,17 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
       vec![],
       vec![],
     ),
+    SemanticKind::Overflow(span, expr) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "arithmetic overflow".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{expr}` overflows at compile time").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 an `int` holds a value between {} and {}", i64::MIN.fg(Color::note()), i64::MAX.fg(Color::note()))],
+      vec![],
+    ),
     SemanticKind::TypeMismatch(span, t1, t2) => (
       ReportKind::Error(REPORT_ERROR),
       format!("{}", "type mismatch".fg(Color::title())),
@@ -189,5 +345,49 @@ pub(crate) fn semantic_report(kind: &SemanticKind) -> ReportMessage {
       vec![],
       vec![],
     ),
+    SemanticKind::Unsupported(span, what) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", format_args!("{what}").fg(Color::hint()), "are not supported by the native backend yet".fg(Color::title())),
+      vec![(
+        *span,
+        "this expression can not be compiled".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![format!("👉 {}", "run the program with `zhoo run --interp`".fg(Color::help()))],
+    ),
+    SemanticKind::UntypedLambda(span) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "the inputs of this lambda have no type".fg(Color::title())),
+      vec![(
+        *span,
+        "i can not infer the types of these inputs".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 a lambda takes the types of the `Fn` it's given to")],
+      vec![format!("👉 {}", "give a type to its binding: `imu f: Fn(int): int = fn(x) -> x * 2`".fg(Color::help()))],
+    ),
+    SemanticKind::ValCycle(span, name) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", "cycle detected when evaluating".fg(Color::title()), format_args!("`{name}`").fg(Color::hint())),
+      vec![(
+        *span,
+        format!("the value of `{name}` depends on itself").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 one of these `val`s must have a value which does not use the others")],
+      vec![],
+    ),
+    SemanticKind::ValNotConstant(span, name) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{} {}", format_args!("`{name}`").fg(Color::hint()), "is not a constant".fg(Color::title())),
+      vec![(
+        *span,
+        "this expression can not be evaluated at compile time".fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 a top-level `val` is made of literals, operators, `val`s and calls of `const fun`s")],
+      vec![],
+    ),
   }
 }
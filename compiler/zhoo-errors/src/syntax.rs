@@ -23,6 +23,30 @@ pub enum SyntaxKind {
   /// generated by the parser when it encounters additional, unexpected tokens.
   ExtraToken(Span, String),
 
+  /// generated when a literal holds an unknown escape, it holds the escape.
+  InvalidEscape(Span, String),
+
+  /// generated when a `\u{...}` escape is not a unicode scalar value, it
+  /// holds the escape.
+  InvalidUnicodeEscape(Span, String),
+
+  /// generated when a char literal does not hold exactly one char, it holds
+  /// the literal.
+  InvalidCharLit(Span, String),
+
+  /// generated when an integer literal has no digits or does not fit in 64
+  /// bits, it holds the literal.
+  InvalidIntLit(Span, String),
+
+  /// generated when the `{` of an interpolation is never closed.
+  UnclosedInterpolation(Span),
+
+  /// generated when an interpolation holds no expression: `"{}"`.
+  EmptyInterpolation(Span),
+
+  /// generated when a `}` of a str literal closes no interpolation.
+  UnmatchedBrace(Span),
+
   /// Custom error type.
   User(String),
 }
@@ -76,6 +100,83 @@ pub(crate) fn syntax_report(kind: &SyntaxKind) -> ReportMessage {
       vec![],
       vec![]
     ),
+    SyntaxKind::InvalidEscape(span, escape) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "unknown character escape".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{escape}` is not an escape i know").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 the escapes are `\\n`, `\\r`, `\\t`, `\\0`, `\\\\`, `\\'`, `\\\"`, `\\x7f` and `\\u{{1f916}}`")],
+      vec![format!("👉 {}", "write `\\\\` for a backslash, or a raw string `$\"...\"$`".fg(Color::help()))],
+    ),
+    SyntaxKind::InvalidUnicodeEscape(span, escape) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "invalid unicode escape".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{escape}` is not a unicode scalar value").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 a unicode escape holds from 1 to 6 hex digits, up to `10ffff` without the surrogates `d800` to `dfff`")],
+      vec![],
+    ),
+    SyntaxKind::InvalidCharLit(span, lit) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "invalid char literal".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{lit}` does not hold exactly one char").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![],
+      vec![format!("👉 {}", "a text is written between double quotes: `\"...\"`".fg(Color::help()))],
+    ),
+    SyntaxKind::InvalidIntLit(span, lit) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "invalid integer literal".fg(Color::title())),
+      vec![(
+        *span,
+        format!("`{lit}` is not an integer of 64 bits").fg(Color::error()).to_string(),
+        Color::error(),
+      )],
+      vec![format!("🤖 an integer literal has digits and goes up to `18446744073709551615`, the largest `u64`")],
+      vec![],
+    ),
+    SyntaxKind::UnclosedInterpolation(span) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "unclosed interpolation".fg(Color::title())),
+      vec![(
+        *span,
+        format!("{}", "this `{` is never closed".fg(Color::error())),
+        Color::error(),
+      )],
+      vec![format!("🤖 in a str, a `{{` opens an interpolation and a `}}` closes it, a raw str `$\"{{}}\"$` writes its braces as they are")],
+      vec![format!("👉 {}", "write `{{` for a brace: `\"{{\"`".fg(Color::help()))],
+    ),
+    SyntaxKind::EmptyInterpolation(span) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "empty interpolation".fg(Color::title())),
+      vec![(
+        *span,
+        format!("{}", "i expected an expression between these braces".fg(Color::error())),
+        Color::error(),
+      )],
+      vec![format!("🤖 an interpolation writes the value of an expression: `\"{{x}}\"`")],
+      vec![format!("👉 {}", "write `{{}}` for a pair of braces".fg(Color::help()))],
+    ),
+    SyntaxKind::UnmatchedBrace(span) => (
+      ReportKind::Error(REPORT_ERROR),
+      format!("{}", "unmatched brace".fg(Color::title())),
+      vec![(
+        *span,
+        format!("{}", "this `}` closes no interpolation".fg(Color::error())),
+        Color::error(),
+      )],
+      vec![format!("🤖 in a str, a `{{` opens an interpolation and a `}}` closes it, a raw str `$\"{{}}\"$` writes its braces as they are")],
+      vec![format!("👉 {}", "write `}}` for a brace: `\"}}\"`".fg(Color::help()))],
+    ),
     // todo #1
     SyntaxKind::User(error) => (
       ReportKind::Error(REPORT_ERROR),
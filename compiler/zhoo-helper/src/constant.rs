@@ -4,14 +4,20 @@ pub const COMPILER_NAME: &str = "zhoo";
 /// the gcc compiler name
 pub const GCC_PROGRAM: &str = "gcc";
 
+/// the default c compiler, used to link a program
+pub const CC_PROGRAM: &str = "cc";
+
 /// the entry point of the program
 pub const ENTRY_POINT: &str = "main";
 
-/// the zhoo library directory path
-pub const PATH_LIBRARY: &str = "target/debug";
-
-/// the zhoo core library path
+/// the zhoo core library file name
 pub const PATH_LIBRARY_CORE: &str = "libzhoo_core.a";
 
 /// the compiler output directory
 pub const PATH_OUTPUT_DIRECTORY: &str = "program";
+
+/// the manifest file of a project
+pub const MANIFEST_FILE: &str = "zhoo.toml";
+
+/// the output directory of a project, one directory per build mode
+pub const PATH_TARGET_DIRECTORY: &str = "target";
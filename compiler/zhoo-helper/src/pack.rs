@@ -28,7 +28,7 @@ pub fn make_dir(path_directory: &str) {
     );
   }
 
-  match fs::create_dir(path_directory) {
+  match fs::create_dir_all(path_directory) {
     Ok(_) => {
       slow_println(&format!("│ [make] dir: `{path_directory}`"), *INTERVAL_DIR)
     }
@@ -36,12 +36,12 @@ pub fn make_dir(path_directory: &str) {
   }
 }
 
-/// create a file and display the file path
-pub fn make_file(path_file: &str, bytes_buf: &[u8]) {
+/// create a file and display the file kind and path
+pub fn make_file(kind: &str, path_file: &str, bytes_buf: &[u8]) {
   match File::create(path_file) {
     Ok(mut file) => match file.write_all(bytes_buf) {
       Ok(_) => {
-        slow_println(&format!("⋮ [make] obj: `{path_file}`"), *INTERVAL_OBJ)
+        slow_println(&format!("⋮ [make] {kind}: `{path_file}`"), *INTERVAL_OBJ)
       }
       Err(error) => panic!("error: {error}"),
     },
@@ -56,71 +56,32 @@ pub fn make_exe(path_input: &str, path_output: &str) {
     .output()
   {
     Ok(_) => {
-      slow_println(&format!("│ [make] exe: `{path_output}`"), *INTERVAL_EXE);
-      slow_println("╰\n", *INTERVAL_ARD);
+      slow_println(&format!("│ [make] exe: `{path_output}`"), *INTERVAL_EXE)
     }
     Err(error) => panic!("error: {error}"),
   }
 }
 
-/// create an executable with link (for linux and windows) and print the output path
-#[cfg(not(target_os = "macos"))]
-pub fn make_exe_with_link(
-  path_input: &str,
-  path_link: &str,
+/// create an executable with a link function and print the output path
+pub fn make_exe_with_link<E>(
+  link: impl FnOnce() -> Result<(), E>,
   path_output: &str,
-) {
-  // fixme: `ld: warning: PIE disabled. Absolute addressing (perhaps -mdynamic-no-pic) not allowed in code signed PIE`
-  match Command::new(GCC_PROGRAM)
-    .args([
-      "-v",
-      "-fno-pie",
-      "-pthread",
-      "-ldl",
-      "-Wl",
-      "no-as-needed",
-      path_input,
-      path_link,
-      "-o",
-      path_output,
-    ])
-    .output()
-  {
-    Ok(_) => {
-      slow_println(&format!("│ [make] exe: `{path_output}`"), *INTERVAL_EXE);
-      slow_println(&format!("╰\n"), *INTERVAL_ARD);
-    }
-    Err(error) => panic!("error: {error}"),
-  }
+) -> Result<(), E> {
+  link()?;
+
+  slow_println(&format!("│ [make] exe: `{path_output}`"), *INTERVAL_EXE);
+
+  Ok(())
 }
 
-/// create an executable with link (for macos) and print the output path
-#[cfg(target_os = "macos")]
-pub fn make_exe_with_link(
-  path_input: &str,
-  path_link: &str,
-  path_output: &str,
-) {
-  // fixme: `ld: warning: PIE disabled. Absolute addressing (perhaps -mdynamic-no-pic) not allowed in code signed PIE`
-  match Command::new(GCC_PROGRAM)
-    .args([
-      "-v",
-      "-fno-pie",
-      "-pthread",
-      "-ldl",
-      path_input,
-      path_link,
-      "-o",
-      path_output,
-    ])
-    .output()
-  {
-    Ok(_) => {
-      slow_println(&format!("│ [make] exe: `{path_output}`"), *INTERVAL_EXE);
-      slow_println("╰\n", *INTERVAL_ARD);
-    }
-    Err(error) => panic!("error: {error}"),
-  }
+/// skip the executable creation and print the reason
+pub fn skip_exe(reason: &str) {
+  slow_println(&format!("│ [skip] exe: {reason}"), *INTERVAL_EXE);
+}
+
+/// display the end of the created files
+pub fn make_end() {
+  slow_println("╰\n", *INTERVAL_ARD);
 }
 
 /// check the existence of a directory
@@ -0,0 +1,262 @@
+//! the high-level ir, the ast without its sugar
+//!
+//! `when` is an `if`, `while` and `until` are a `loop` which breaks on their
+//! condition, an assignment with an operator is an assignment of a binary
+//! operation and an expression between parentheses is the expression itself.
+//! the variables are resolved, every binding is a local of the program
+//!
+//! the body of a function, the body of a loop, the branches of an `if` and
+//! every other block open a scope. a binding is visible from its declaration
+//! to the end of its block and shadows the bindings of the same name, its own
+//! value still reads the binding it shadows. the checker, the mir and the
+//! generated code all read the bindings from the hir, so they agree on them
+
+use zhoo_ast::ast::{BinOp, LitKind, Ty, UnOp, UnOpKind};
+use zhoo_ast::ptr::Fsp;
+use zhoo_span::span::Span;
+
+/// a binding of the program: a variable, an input of a function or of a
+/// lambda
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LocalId(pub usize);
+
+#[derive(Debug)]
+pub struct Program {
+  pub items: Vec<Item>,
+  /// the bindings of every function of the program
+  pub locals: Vec<LocalDecl>,
+  pub span: Span,
+}
+
+impl Program {
+  pub fn local(&self, local: LocalId) -> &LocalDecl {
+    &self.locals[local.0]
+  }
+
+  /// visits every type written in the program: the types of the bindings, of
+  /// the items and of the casts
+  pub fn visit_tys_mut(&mut self, visit: &mut impl FnMut(&mut Ty)) {
+    for local in &mut self.locals {
+      if let Some(ty) = &mut local.ty {
+        visit(ty);
+      }
+    }
+
+    for item in &mut self.items {
+      item.visit_tys_mut(visit);
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Ident {
+  pub name: String,
+  pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct LocalDecl {
+  pub name: String,
+  /// the type given by the program, `None` when it's inferred
+  pub ty: Option<Fsp<Ty>>,
+  pub is_mutable: bool,
+  pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+  pub kind: ItemKind,
+  pub span: Span,
+}
+
+impl Item {
+  fn visit_tys_mut(&mut self, visit: &mut impl FnMut(&mut Ty)) {
+    match &mut self.kind {
+      ItemKind::Ext(ext) => {
+        ext.inputs.iter_mut().for_each(|ty| visit(ty));
+        visit(&mut ext.output);
+      }
+      ItemKind::Fun(fun) => {
+        visit(&mut fun.output);
+        fun.body.visit_tys_mut(visit);
+      }
+      ItemKind::Val(val) => {
+        if let Some(ty) = &mut val.ty {
+          visit(ty);
+        }
+
+        val.value.visit_tys_mut(visit);
+      }
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum ItemKind {
+  Ext(Ext),
+  Fun(Fun),
+  Val(Val),
+}
+
+/// a function declared by `ext`
+#[derive(Clone, Debug)]
+pub struct Ext {
+  pub name: Ident,
+  pub inputs: Vec<Fsp<Ty>>,
+  pub output: Fsp<Ty>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Fun {
+  pub name: Ident,
+  pub is_const: bool,
+  pub inputs: Vec<LocalId>,
+  pub output: Fsp<Ty>,
+  pub body: Block,
+}
+
+/// a top-level `val`
+#[derive(Clone, Debug)]
+pub struct Val {
+  pub name: Ident,
+  pub ty: Option<Fsp<Ty>>,
+  pub value: Expr,
+}
+
+/// a sequence of expressions, its bindings are only visible inside of it
+#[derive(Clone, Debug)]
+pub struct Block {
+  pub exprs: Vec<Expr>,
+  /// the bindings declared in the scope of the block
+  pub locals: Vec<LocalId>,
+  pub span: Span,
+}
+
+impl Block {
+  fn visit_tys_mut(&mut self, visit: &mut impl FnMut(&mut Ty)) {
+    for expr in &mut self.exprs {
+      expr.visit_tys_mut(visit);
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Expr {
+  pub kind: ExprKind,
+  pub span: Span,
+}
+
+impl Expr {
+  pub const fn new(kind: ExprKind, span: Span) -> Self {
+    Self { kind, span }
+  }
+
+  /// the literal of an unsuffixed number, a negated literal is a literal
+  pub fn number_lit(&self) -> Option<LitKind> {
+    match &self.kind {
+      ExprKind::Lit(lit @ (LitKind::Int(_, None) | LitKind::Real(_, None))) => {
+        Some(lit.to_owned())
+      }
+      ExprKind::UnOp(op, rhs) if matches!(op.node, UnOpKind::Neg) => {
+        match rhs.number_lit()? {
+          LitKind::Int(int, None) => Some(LitKind::Int(-int, None)),
+          LitKind::Real(real, None) => Some(LitKind::Real(-real, None)),
+          _ => None,
+        }
+      }
+      _ => None,
+    }
+  }
+
+  fn visit_tys_mut(&mut self, visit: &mut impl FnMut(&mut Ty)) {
+    match &mut self.kind {
+      ExprKind::Lit(_)
+      | ExprKind::Local(_)
+      | ExprKind::Global(_)
+      | ExprKind::Continue => {}
+      ExprKind::Cast(value, ty) => {
+        value.visit_tys_mut(visit);
+        visit(ty);
+      }
+      ExprKind::Interpolation(exprs)
+      | ExprKind::Array(exprs)
+      | ExprKind::Tuple(exprs) => {
+        exprs.iter_mut().for_each(|expr| expr.visit_tys_mut(visit))
+      }
+      ExprKind::Call(callee, inputs) => {
+        callee.visit_tys_mut(visit);
+        inputs
+          .iter_mut()
+          .for_each(|input| input.visit_tys_mut(visit));
+      }
+      ExprKind::UnOp(_, expr)
+      | ExprKind::Let(_, expr)
+      | ExprKind::Lambda(_, expr) => expr.visit_tys_mut(visit),
+      ExprKind::BinOp(lhs, _, rhs)
+      | ExprKind::Assign(lhs, rhs)
+      | ExprKind::Index(lhs, rhs)
+      | ExprKind::Field(lhs, rhs) => {
+        lhs.visit_tys_mut(visit);
+        rhs.visit_tys_mut(visit);
+      }
+      ExprKind::Block(block) | ExprKind::Loop(block, _) => {
+        block.visit_tys_mut(visit)
+      }
+      ExprKind::If(condition, consequence, maybe_alternative) => {
+        condition.visit_tys_mut(visit);
+        consequence.visit_tys_mut(visit);
+
+        if let Some(alternative) = maybe_alternative {
+          alternative.visit_tys_mut(visit);
+        }
+      }
+      ExprKind::Return(maybe_expr) | ExprKind::Break(maybe_expr) => {
+        if let Some(expr) = maybe_expr {
+          expr.visit_tys_mut(visit);
+        }
+      }
+      ExprKind::Item(item) => item.visit_tys_mut(visit),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum ExprKind {
+  Lit(LitKind),
+  /// the segments of an interpolated `str`, its texts are `str` literals
+  Interpolation(Vec<Expr>),
+  /// a read of a binding
+  Local(LocalId),
+  /// a name which is not a binding: a function, an extern, a builtin or a
+  /// top-level `val`
+  Global(Ident),
+  Call(Box<Expr>, Vec<Expr>),
+  UnOp(UnOp, Box<Expr>),
+  BinOp(Box<Expr>, BinOp, Box<Expr>),
+  /// the conversion of a value to another type by `as`
+  Cast(Box<Expr>, Fsp<Ty>),
+  /// the declaration of a binding and its value
+  Let(LocalId, Box<Expr>),
+  Assign(Box<Expr>, Box<Expr>),
+  Block(Block),
+  Loop(Block, LoopSource),
+  If(Box<Expr>, Box<Expr>, Option<Box<Expr>>),
+  Return(Option<Box<Expr>>),
+  Break(Option<Box<Expr>>),
+  Continue,
+  Lambda(Vec<LocalId>, Box<Expr>),
+  Array(Vec<Expr>),
+  Index(Box<Expr>, Box<Expr>),
+  Tuple(Vec<Expr>),
+  Field(Box<Expr>, Box<Expr>),
+  /// a function or an extern declared inside of a block
+  Item(Box<Item>),
+}
+
+/// the expression from which a `loop` is desugared
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoopSource {
+  Loop,
+  While,
+  Until,
+}
@@ -0,0 +1,377 @@
+//! the lowering of the ast into the hir, the sugar is removed and the
+//! variables are resolved to their binding
+
+use super::hir::{
+  Block, Expr, ExprKind, Ext, Fun, Ident, Item, ItemKind, LocalDecl, LocalId,
+  LoopSource, Program, Val,
+};
+
+use zhoo_ast::ast::{self, AsTy, Mutability, PatternKind, UnOpKind};
+use zhoo_ast::ptr::Fsp;
+use zhoo_span::span::{Span, Spanned};
+
+use fxhash::FxHashMap;
+
+/// lowers a program, a name which is not a binding is kept as a global and
+/// resolved by the passes which know the functions
+pub fn lower(program: &ast::Program) -> Program {
+  let mut lowerer = Lowerer::default();
+
+  let items = program
+    .stmts
+    .iter()
+    .filter_map(|stmt| lowerer.lower_item(stmt))
+    .collect();
+
+  Program {
+    items,
+    locals: lowerer.locals,
+    span: program.span,
+  }
+}
+
+#[derive(Default)]
+struct Scope {
+  names: FxHashMap<String, LocalId>,
+  locals: Vec<LocalId>,
+}
+
+#[derive(Default)]
+struct Lowerer {
+  locals: Vec<LocalDecl>,
+  /// the scopes of the function being lowered
+  scopes: Vec<Scope>,
+}
+
+impl Lowerer {
+  fn lower_item(&mut self, stmt: &ast::Stmt) -> Option<Item> {
+    let kind = match &stmt.kind {
+      ast::StmtKind::Ext(ext) => ItemKind::Ext(Ext {
+        name: lower_ident(&ext.prototype.name),
+        inputs: ext.prototype.as_inputs_tys(),
+        output: ext.prototype.as_ty(),
+      }),
+      ast::StmtKind::Fun(fun) => ItemKind::Fun(self.lower_fun(fun)),
+      ast::StmtKind::Val(decl) => ItemKind::Val(Val {
+        name: Ident {
+          name: decl.pattern.to_string(),
+          span: decl.pattern.span,
+        },
+        ty: decl.ty.to_owned(),
+        value: self.lower_expr(&decl.value),
+      }),
+      ast::StmtKind::TyAlias(_) | ast::StmtKind::Unit(_) => return None,
+    };
+
+    Some(Item {
+      kind,
+      span: stmt.span,
+    })
+  }
+
+  /// a function does not see the bindings of the function which contains it
+  fn lower_fun(&mut self, fun: &ast::Fun) -> Fun {
+    let scopes = std::mem::take(&mut self.scopes);
+
+    self.scopes.push(Scope::default());
+
+    let inputs = fun
+      .prototype
+      .inputs
+      .iter()
+      .map(|input| {
+        self.declare(&input.pattern, Some(input.ty.to_owned()), false)
+      })
+      .collect();
+
+    let body = self.lower_block(&fun.body);
+
+    self.scopes = scopes;
+
+    Fun {
+      name: lower_ident(&fun.prototype.name),
+      is_const: fun.is_const(),
+      inputs,
+      output: fun.prototype.as_ty(),
+      body,
+    }
+  }
+
+  fn lower_block(&mut self, block: &ast::Block) -> Block {
+    self.scopes.push(Scope::default());
+
+    let exprs = block
+      .exprs
+      .iter()
+      .map(|expr| self.lower_expr(expr))
+      .collect();
+
+    let scope = self.scopes.pop().expect("a scope");
+
+    Block {
+      exprs,
+      locals: scope.locals,
+      span: block.span,
+    }
+  }
+
+  fn lower_expr(&mut self, expr: &ast::Expr) -> Expr {
+    let kind = match &expr.kind {
+      ast::ExprKind::Lit(lit) => ExprKind::Lit(lit.kind.to_owned()),
+      ast::ExprKind::Interpolation(segments) => ExprKind::Interpolation(
+        segments
+          .iter()
+          .map(|segment| self.lower_expr(segment))
+          .collect(),
+      ),
+      ast::ExprKind::Identifier(identifier) => match self.lookup(identifier) {
+        Some(local) => ExprKind::Local(local),
+        None => ExprKind::Global(Ident {
+          name: identifier.to_string(),
+          span: expr.span,
+        }),
+      },
+      ast::ExprKind::UnOp(op, rhs) => {
+        ExprKind::UnOp(op.to_owned(), self.lower_boxed(rhs))
+      }
+      ast::ExprKind::BinOp(lhs, op, rhs) => ExprKind::BinOp(
+        self.lower_boxed(lhs),
+        op.to_owned(),
+        self.lower_boxed(rhs),
+      ),
+      ast::ExprKind::Cast(expr, ty) => {
+        ExprKind::Cast(self.lower_boxed(expr), ty.to_owned())
+      }
+      ast::ExprKind::Call(callee, inputs) => ExprKind::Call(
+        self.lower_boxed(callee),
+        inputs.iter().map(|input| self.lower_expr(input)).collect(),
+      ),
+      ast::ExprKind::Decl(decl) => self.lower_decl(decl),
+      ast::ExprKind::Assign(lhs, _, rhs) => {
+        ExprKind::Assign(self.lower_boxed(lhs), self.lower_boxed(rhs))
+      }
+      // `x += y` is `x = x + y`
+      ast::ExprKind::AssignOp(lhs, op, rhs) => {
+        let lhs = self.lower_boxed(lhs);
+        let rhs = self.lower_boxed(rhs);
+
+        ExprKind::Assign(
+          lhs.to_owned(),
+          Box::new(Expr::new(
+            ExprKind::BinOp(lhs, op.to_owned(), rhs),
+            expr.span,
+          )),
+        )
+      }
+      ast::ExprKind::Block(block) => ExprKind::Block(self.lower_block(block)),
+      ast::ExprKind::Loop(body) => {
+        ExprKind::Loop(self.lower_block(body), LoopSource::Loop)
+      }
+      ast::ExprKind::While(condition, body) => {
+        self.lower_conditional_loop(condition, body, LoopSource::While)
+      }
+      ast::ExprKind::Until(condition, body) => {
+        self.lower_conditional_loop(condition, body, LoopSource::Until)
+      }
+      ast::ExprKind::Return(maybe_expr) => {
+        ExprKind::Return(self.lower_maybe(maybe_expr))
+      }
+      ast::ExprKind::Break(maybe_expr) => {
+        ExprKind::Break(self.lower_maybe(maybe_expr))
+      }
+      ast::ExprKind::Continue => ExprKind::Continue,
+      ast::ExprKind::When(condition, consequence, alternative) => ExprKind::If(
+        self.lower_boxed(condition),
+        self.lower_boxed(consequence),
+        Some(self.lower_boxed(alternative)),
+      ),
+      ast::ExprKind::IfElse(condition, consequence, maybe_alternative) => {
+        ExprKind::If(
+          self.lower_boxed(condition),
+          self.lower_boxed(consequence),
+          self.lower_maybe(maybe_alternative),
+        )
+      }
+      ast::ExprKind::Lambda(inputs, body) => self.lower_lambda(inputs, body),
+      ast::ExprKind::Array(elements) => ExprKind::Array(
+        elements
+          .iter()
+          .map(|element| self.lower_expr(element))
+          .collect(),
+      ),
+      ast::ExprKind::ArrayAccess(indexed, index) => {
+        ExprKind::Index(self.lower_boxed(indexed), self.lower_boxed(index))
+      }
+      // a tuple of one element is an expression between parentheses
+      ast::ExprKind::Tuple(elements) if elements.len() == 1 => {
+        return self.lower_expr(&elements[0]);
+      }
+      ast::ExprKind::Tuple(elements) => ExprKind::Tuple(
+        elements
+          .iter()
+          .map(|element| self.lower_expr(element))
+          .collect(),
+      ),
+      ast::ExprKind::TupleAccess(tuple, index) => {
+        ExprKind::Field(self.lower_boxed(tuple), self.lower_boxed(index))
+      }
+      ast::ExprKind::Stmt(stmt) => match &stmt.kind {
+        // a `val` inside of a block is an immutable binding
+        ast::StmtKind::Val(decl) => self.lower_decl(decl),
+        _ => match self.lower_item(stmt) {
+          Some(item) => ExprKind::Item(Box::new(item)),
+          None => ExprKind::Block(Block {
+            exprs: vec![],
+            locals: vec![],
+            span: stmt.span,
+          }),
+        },
+      },
+    };
+
+    Expr::new(kind, expr.span)
+  }
+
+  fn lower_boxed(&mut self, expr: &ast::Expr) -> Box<Expr> {
+    Box::new(self.lower_expr(expr))
+  }
+
+  fn lower_maybe(
+    &mut self,
+    maybe_expr: &Option<Fsp<ast::Expr>>,
+  ) -> Option<Box<Expr>> {
+    maybe_expr.as_ref().map(|expr| self.lower_boxed(expr))
+  }
+
+  /// the value is lowered before the binding is declared, so a shadowing
+  /// binding can read the binding it shadows
+  fn lower_decl(&mut self, decl: &ast::Decl) -> ExprKind {
+    let value = self.lower_boxed(&decl.value);
+    let is_mutable = matches!(decl.mutability, Mutability::Yes(_));
+    let local = self.declare(&decl.pattern, decl.ty.to_owned(), is_mutable);
+
+    ExprKind::Let(local, value)
+  }
+
+  /// `while c { .. }` is `loop { if !c { break; } .. }` and `until c { .. }`
+  /// is `loop { if c { break; } .. }`
+  fn lower_conditional_loop(
+    &mut self,
+    condition: &ast::Expr,
+    body: &ast::Block,
+    source: LoopSource,
+  ) -> ExprKind {
+    let span = condition.span;
+    let condition = self.lower_boxed(condition);
+
+    let condition = match source {
+      LoopSource::While => Box::new(Expr::new(
+        ExprKind::UnOp(Spanned::new(UnOpKind::Not, span), condition),
+        span,
+      )),
+      _ => condition,
+    };
+
+    let exit = Expr::new(
+      ExprKind::Block(Block {
+        exprs: vec![Expr::new(ExprKind::Break(None), span)],
+        locals: vec![],
+        span,
+      }),
+      span,
+    );
+
+    let body = Block {
+      exprs: vec![
+        Expr::new(ExprKind::If(condition, Box::new(exit), None), span),
+        Expr::new(ExprKind::Block(self.lower_block(body)), body.span),
+      ],
+      locals: vec![],
+      span: body.span,
+    };
+
+    ExprKind::Loop(body, source)
+  }
+
+  /// a lambda sees the bindings of the function which contains it
+  fn lower_lambda(
+    &mut self,
+    inputs: &[Fsp<ast::Expr>],
+    body: &ast::Expr,
+  ) -> ExprKind {
+    self.scopes.push(Scope::default());
+
+    let inputs = inputs
+      .iter()
+      .map(|input| {
+        let local = self.new_local(input.to_string(), None, false, input.span);
+
+        self.bind(input.to_string(), local);
+
+        local
+      })
+      .collect();
+
+    let body = self.lower_boxed(body);
+
+    self.scopes.pop();
+
+    ExprKind::Lambda(inputs, body)
+  }
+
+  fn lookup(&self, name: &str) -> Option<LocalId> {
+    self
+      .scopes
+      .iter()
+      .rev()
+      .find_map(|scope| scope.names.get(name).copied())
+  }
+
+  /// declares a binding in the current scope, `_` is never bound
+  fn declare(
+    &mut self,
+    pattern: &ast::Pattern,
+    ty: Option<Fsp<ast::Ty>>,
+    is_mutable: bool,
+  ) -> LocalId {
+    let name = pattern.to_string();
+    let local = self.new_local(name.to_owned(), ty, is_mutable, pattern.span);
+
+    if !matches!(pattern.kind, PatternKind::Underscore) {
+      self.bind(name, local);
+    }
+
+    local
+  }
+
+  fn bind(&mut self, name: String, local: LocalId) {
+    let scope = self.scopes.last_mut().expect("a scope");
+
+    scope.names.insert(name, local);
+    scope.locals.push(local);
+  }
+
+  fn new_local(
+    &mut self,
+    name: String,
+    ty: Option<Fsp<ast::Ty>>,
+    is_mutable: bool,
+    span: Span,
+  ) -> LocalId {
+    self.locals.push(LocalDecl {
+      name,
+      ty,
+      is_mutable,
+      span,
+    });
+
+    LocalId(self.locals.len() - 1)
+  }
+}
+
+fn lower_ident(name: &ast::Expr) -> Ident {
+  Ident {
+    name: name.to_string(),
+    span: name.span,
+  }
+}
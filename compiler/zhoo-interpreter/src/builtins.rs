@@ -0,0 +1,163 @@
+//! the builtins of the interpreter, they behave like the functions of
+//! `library/core` and of the libc used by the compiled programs
+
+use super::eval::Interrupt;
+use super::value::Value;
+
+use zhoo_errors::{Report, RuntimeKind};
+use zhoo_span::span::Span;
+
+use std::fs;
+use std::io::Write;
+
+/// the alignment of the addresses given by `malloc`
+const MALLOC_ALIGN: i64 = 16;
+
+/// the memory given by `malloc`, only its addresses are simulated since a
+/// zhoo program can not dereference them
+pub(crate) struct Heap {
+  next: i64,
+}
+
+impl Default for Heap {
+  fn default() -> Self {
+    Self { next: MALLOC_ALIGN }
+  }
+}
+
+impl Heap {
+  fn malloc(&mut self, size: i64) -> i64 {
+    let address = self.next;
+    let size = size.max(1).saturating_add(MALLOC_ALIGN - 1);
+
+    self.next = self.next.saturating_add(size / MALLOC_ALIGN * MALLOC_ALIGN);
+
+    address
+  }
+}
+
+pub(crate) fn call<'a>(
+  out: &mut dyn Write,
+  heap: &mut Heap,
+  span: Span,
+  name: &str,
+  inputs: &[Value<'a>],
+) -> Result<Value<'a>, Interrupt<'a>> {
+  let failed = |error: String| -> Interrupt<'a> {
+    Report::Runtime(RuntimeKind::BuiltinFailed(span, name.to_string(), error))
+      .into()
+  };
+
+  let written = match (name, inputs) {
+    // `print` and `println` write any printable value, like their entry
+    // points of the compiled programs
+    ("print", [value]) => write!(out, "{value}"),
+    ("println", [value]) => writeln!(out, "{value}"),
+    ("printi", [Value::Int(int, _)]) => write!(out, "{int}"),
+    ("printiln", [Value::Int(int, _)]) => writeln!(out, "{int}"),
+    ("printr", [Value::Real(real, _)]) => write!(out, "{real}"),
+    ("printrln", [Value::Real(real, _)]) => writeln!(out, "{real}"),
+    ("printb", [Value::Bool(boolean)]) => write!(out, "{boolean}"),
+    ("printbln", [Value::Bool(boolean)]) => writeln!(out, "{boolean}"),
+    ("printc", [Value::Char(char)]) => write!(out, "{char}"),
+    ("printcln", [Value::Char(char)]) => writeln!(out, "{char}"),
+    ("str_len", [Value::Str(string)]) => {
+      return Ok(Value::int(string.chars().count() as i64))
+    }
+    ("str_concat", [Value::Str(lhs), Value::Str(rhs)]) => {
+      return Ok(Value::str(&format!("{lhs}{rhs}")))
+    }
+    ("str_cmp", [Value::Str(lhs), Value::Str(rhs)]) => {
+      return Ok(Value::int(lhs.cmp(rhs) as i64))
+    }
+    ("str_at", [Value::Str(string), Value::Int(index, _)]) => {
+      return str_at(span, string, *index)
+    }
+    (
+      "str_slice",
+      [Value::Str(string), Value::Int(start, _), Value::Int(end, _)],
+    ) => return str_slice(span, string, *start, *end),
+    ("fmt_int" | "fmt_uint", [Value::Int(..)])
+    | ("fmt_real" | "fmt_f32", [Value::Real(..)])
+    | ("fmt_bool", [Value::Bool(_)])
+    | ("fmt_char", [Value::Char(_)]) => {
+      return Ok(Value::str(&inputs[0].to_string()))
+    }
+    ("exit", [Value::Int(code, _)]) => {
+      out.flush().map_err(|error| failed(error.to_string()))?;
+
+      return Err(Interrupt::Exit(*code as i32));
+    }
+    ("create", [Value::Str(path), Value::Str(source)]) => {
+      fs::write(&**path, source.as_bytes())
+        .map_err(|error| failed(format!("{path}: {error}")))?;
+
+      return Ok(Value::Void);
+    }
+    ("open", [Value::Str(path)]) => {
+      return match fs::read_to_string(&**path) {
+        Ok(contents) => Ok(Value::str(&contents)),
+        Err(error) => Err(failed(format!("{path}: {error}"))),
+      };
+    }
+    ("malloc", [Value::Int(size, _)]) => {
+      return Ok(Value::int(heap.malloc(*size)))
+    }
+    ("free", [Value::Int(..)]) => return Ok(Value::Void),
+    _ => {
+      let inputs = inputs
+        .iter()
+        .map(|input| input.kind())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+      return Err(failed(format!("unexpected inputs ({inputs})")));
+    }
+  };
+
+  written.map_err(|error| failed(error.to_string()))?;
+
+  Ok(Value::Void)
+}
+
+/// the char at an index of a `str`, as a `str` of one char
+pub(crate) fn str_at<'a>(
+  span: Span,
+  string: &str,
+  index: i64,
+) -> Result<Value<'a>, Interrupt<'a>> {
+  str_slice(span, string, index, index.saturating_add(1))
+}
+
+/// the chars of a `str` from `start` to `end`, `end` is excluded
+pub(crate) fn str_slice<'a>(
+  span: Span,
+  string: &str,
+  start: i64,
+  end: i64,
+) -> Result<Value<'a>, Interrupt<'a>> {
+  let len = string.chars().count();
+
+  let out_of_bounds = match (usize::try_from(start), usize::try_from(end)) {
+    (Ok(start), _) if start > len => Some(start as i64),
+    (Err(_), _) => Some(start),
+    (Ok(start), Ok(end)) if start <= end && end <= len => None,
+    _ => Some(end),
+  };
+
+  if let Some(index) = out_of_bounds {
+    return Err(
+      Report::Runtime(RuntimeKind::IndexOutOfBounds(span, index, len)).into(),
+    );
+  }
+
+  let (start, end) = (start as usize, end as usize);
+
+  Ok(Value::str(
+    &string
+      .chars()
+      .skip(start)
+      .take(end - start)
+      .collect::<String>(),
+  ))
+}
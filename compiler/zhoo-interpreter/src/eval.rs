@@ -0,0 +1,722 @@
+//! a tree-walking interpreter, it executes a checked program without
+//! generating native code
+
+use super::builtins::{self, Heap};
+use super::value::{Closure, Value};
+
+use zhoo_analyzer::analyzer;
+use zhoo_analyzer::builtins::{builtins, Proto};
+use zhoo_analyzer::constant::{self, Constant};
+use zhoo_analyzer::flow::{self, Evaluate, Flow};
+use zhoo_analyzer::num::Num;
+use zhoo_analyzer::ops::{self, OpError};
+use zhoo_analyzer::resolver::{DefId, DefKind, Resolutions};
+use zhoo_ast::ast::{
+  Arg, AsTy, BinOp, BinOpKind, Block, Decl, Expr, ExprKind, Fun, PatternKind,
+  Program, StmtKind, Ty, TyKind, UnOp, UnOpKind,
+};
+
+use zhoo_ast::ptr::Fsp;
+use zhoo_errors::{Report, Result, RuntimeKind, SemanticKind};
+use zhoo_helper::constant::ENTRY_POINT;
+use zhoo_span::span::Span;
+
+use fxhash::FxHashMap;
+
+use std::io::Write;
+use std::rc::Rc;
+
+/// the maximum depth of the calls
+pub const CALLS_LIMIT: usize = 10_000;
+
+/// the stack size of the thread running the interpreter, enough to reach
+/// `CALLS_LIMIT` nested calls
+pub const STACK_SIZE: usize = 1 << 30;
+
+pub(crate) type Interrupt<'a> = flow::Interrupt<Value<'a>>;
+
+type Eval<'a> = Flow<Value<'a>>;
+
+/// the local variables of a call by their definition, a shadowing binding
+/// has its own definition
+type Frame<'a> = FxHashMap<DefId, Value<'a>>;
+
+/// interprets a checked program, the output of the program is written to
+/// `out` and its exit code is returned
+pub fn interpret(program: &Program, out: &mut dyn Write) -> Result<i32> {
+  let mut interpreter = Interpreter::new(program, out)?;
+  let code = interpreter.run(program.span);
+
+  interpreter.out.flush().map_err(Report::Io)?;
+
+  code
+}
+
+struct Interpreter<'a, 'o> {
+  /// the `val`s, the functions and the externs of the top-level, the
+  /// overloads of a function are told apart by their definition
+  globals: FxHashMap<DefId, Value<'a>>,
+  /// the types of the inputs of the builtins, the ones declared by the
+  /// analyzer
+  builtins: FxHashMap<String, Proto>,
+  resolutions: Resolutions,
+  frames: Vec<Frame<'a>>,
+  heap: Heap,
+  out: &'o mut dyn Write,
+}
+
+impl<'a, 'o> Interpreter<'a, 'o> {
+  fn new(program: &'a Program, out: &'o mut dyn Write) -> Result<Self> {
+    let resolutions = analyzer::resolve(program)?;
+    let mut vals = constant::eval_vals(program, &resolutions)?
+      .into_iter()
+      .collect::<FxHashMap<_, _>>();
+
+    let mut globals = FxHashMap::default();
+
+    for stmt in &program.stmts {
+      let (span, value) = match &stmt.kind {
+        StmtKind::Val(decl) => match vals.remove(&decl.pattern.to_string()) {
+          Some(constant) => (decl.pattern.span, Value::from(constant)),
+          None => continue,
+        },
+        StmtKind::Fun(fun) => (fun.prototype.name.span, Value::Fun(fun)),
+        StmtKind::Ext(ext) => (ext.prototype.name.span, Value::Ext(ext)),
+        _ => continue,
+      };
+
+      if let Some(def) = resolutions.resolution(span) {
+        globals.insert(def, value);
+      }
+    }
+
+    let builtins = builtins()
+      .into_iter()
+      .map(|builtin| (builtin.name, builtin.proto))
+      .collect();
+
+    Ok(Self {
+      globals,
+      builtins,
+      resolutions,
+      frames: vec![],
+      heap: Heap::default(),
+      out,
+    })
+  }
+
+  fn run(&mut self, span: Span) -> Result<i32> {
+    let main = self
+      .resolutions
+      .defs()
+      .find(|(_, def)| def.kind == DefKind::Fun && def.symbol == ENTRY_POINT)
+      .and_then(|(def, _)| self.globals.get(&def).cloned())
+      .ok_or_else(|| {
+        Report::Semantic(SemanticKind::MainNotFound(span, ENTRY_POINT.into()))
+      })?;
+
+    match self.call(span, main, vec![]) {
+      Ok(Value::Int(code, _)) => Ok(code as i32),
+      Ok(_) => Ok(0),
+      Err(Interrupt::Exit(code)) => Ok(code),
+      Err(Interrupt::Report(report)) => Err(report),
+      Err(_) => Ok(0),
+    }
+  }
+
+  /// a value is written like `print` writes it
+  fn eval_expr_interpolation(&mut self, segments: &'a [Fsp<Expr>]) -> Eval<'a> {
+    let mut string = String::new();
+
+    for segment in segments {
+      string += &self.eval_expr(segment)?.to_string();
+    }
+
+    Ok(Value::str(&string))
+  }
+
+  fn eval_expr_un_op(&mut self, op: &UnOp, rhs: &'a Expr) -> Eval<'a> {
+    let rhs = self.eval_expr(rhs)?;
+
+    match rhs
+      .constant()
+      .and_then(|rhs| ops::eval_un_op(&op.node, rhs))
+    {
+      Some(constant) => Ok(Value::from(constant)),
+      None if matches!(op.node, UnOpKind::Neg) => {
+        Err(mismatch(op.span, "int", &rhs))
+      }
+      None => Err(mismatch(op.span, "bool", &rhs)),
+    }
+  }
+
+  fn eval_expr_bin_op(
+    &mut self,
+    span: Span,
+    lhs: &'a Expr,
+    op: &BinOp,
+    rhs: &'a Expr,
+  ) -> Eval<'a> {
+    let lhs_value = self.eval_expr(lhs)?;
+
+    if let Value::Bool(boolean) = lhs_value {
+      if ops::short_circuits(&op.node, &Constant::Bool(boolean)) {
+        return Ok(lhs_value);
+      }
+    }
+
+    let rhs_value = self.eval_expr(rhs)?;
+
+    eval_bin_op(span, lhs, lhs_value, op, rhs, rhs_value)
+  }
+
+  fn eval_expr_call(
+    &mut self,
+    span: Span,
+    callee: &'a Expr,
+    inputs: &'a [Fsp<Expr>],
+  ) -> Eval<'a> {
+    let callee = self.eval_expr(callee)?;
+    let inputs = self.eval_exprs(inputs)?;
+
+    self.call(span, callee, inputs)
+  }
+
+  fn eval_expr_lambda(
+    &mut self,
+    inputs: &'a [Fsp<Expr>],
+    body: &'a Expr,
+  ) -> Value<'a> {
+    let captures = self.frames.last().cloned().unwrap_or_default();
+
+    Value::Closure(Rc::new(Closure {
+      inputs,
+      body,
+      captures,
+    }))
+  }
+
+  fn call(
+    &mut self,
+    span: Span,
+    callee: Value<'a>,
+    inputs: Vec<Value<'a>>,
+  ) -> Eval<'a> {
+    let (scope, body) = match callee {
+      Value::Fun(fun) => {
+        let output = fun.prototype.as_ty();
+        let frame = self.bind(fun, inputs);
+        let value = self.enter(span, frame, |interpreter| {
+          interpreter.eval_block(&fun.body)
+        })?;
+
+        // a function without output returns nothing even if its last
+        // expression has a value
+        return match output.kind {
+          TyKind::Void => Ok(Value::Void),
+          _ => Ok(coerce(value, &output)),
+        };
+      }
+      Value::Ext(ext) => {
+        let name = ext.prototype.name.to_string();
+
+        if self.builtins.contains_key(&name) {
+          return self.call(span, Value::Builtin(Rc::from(name)), inputs);
+        }
+
+        match &ext.body {
+          Some(body) => {
+            let frame = self.inputs_frame(&ext.prototype.inputs, inputs);
+
+            (frame, &**body)
+          }
+          None => {
+            return Err(
+              Report::Runtime(RuntimeKind::Uninterpretable(span, name)).into(),
+            )
+          }
+        }
+      }
+      Value::Builtin(name) => {
+        // an input is converted to the type of the builtin like the compiled
+        // program converts it
+        let inputs = match self.builtins.get(&*name) {
+          Some(Proto(inputs_tys, _)) => inputs
+            .into_iter()
+            .zip(inputs_tys)
+            .map(|(value, ty)| coerce(value, ty))
+            .collect(),
+          None => inputs,
+        };
+
+        return builtins::call(self.out, &mut self.heap, span, &name, &inputs);
+      }
+      Value::Closure(closure) => {
+        let mut frame = closure.captures.to_owned();
+
+        for (input, value) in closure.inputs.iter().zip(inputs) {
+          if let Some(def) = self.resolutions.resolution(input.span) {
+            frame.insert(def, value);
+          }
+        }
+
+        return self.enter(span, frame, |interpreter| {
+          interpreter.eval_expr(closure.body)
+        });
+      }
+      value => return Err(mismatch(span, "fn", &value)),
+    };
+
+    self.enter(span, scope, |interpreter| interpreter.eval_block(body))
+  }
+
+  /// binds the inputs of a function, the function is visible by itself to
+  /// allow a nested function to be recursive. the nested functions visible
+  /// from the call stay visible, so nested functions can call each other
+  fn bind(&self, fun: &'a Fun, inputs: Vec<Value<'a>>) -> Frame<'a> {
+    let mut frame = Frame::default();
+
+    for (def, value) in self.frames.last().into_iter().flatten() {
+      if matches!(value, Value::Fun(_) | Value::Ext(_)) {
+        frame.insert(*def, value.to_owned());
+      }
+    }
+
+    if let Some(def) = self.resolutions.resolution(fun.prototype.name.span) {
+      frame.insert(def, Value::Fun(fun));
+    }
+
+    frame.extend(self.inputs_frame(&fun.prototype.inputs, inputs));
+    frame
+  }
+
+  /// the inputs of a call, converted to the types of the inputs
+  fn inputs_frame(
+    &self,
+    args: &'a [Fsp<Arg>],
+    inputs: Vec<Value<'a>>,
+  ) -> Frame<'a> {
+    args
+      .iter()
+      .zip(inputs)
+      .filter_map(|(arg, value)| {
+        let def = self.resolutions.resolution(arg.pattern.span)?;
+
+        Some((def, coerce(value, &arg.ty)))
+      })
+      .collect()
+  }
+
+  /// evaluates the body of a call in a new frame
+  fn enter(
+    &mut self,
+    span: Span,
+    frame: Frame<'a>,
+    body: impl FnOnce(&mut Self) -> Eval<'a>,
+  ) -> Eval<'a> {
+    if self.frames.len() >= CALLS_LIMIT {
+      return Err(
+        Report::Runtime(RuntimeKind::StackOverflow(span, CALLS_LIMIT)).into(),
+      );
+    }
+
+    self.frames.push(frame);
+
+    let value = flow::returned(body(self));
+
+    self.frames.pop();
+
+    value
+  }
+
+  fn eval_decl(&mut self, decl: &'a Decl) -> Eval<'a> {
+    let value = match &decl.ty {
+      Some(ty) => coerce(self.eval_expr(&decl.value)?, ty),
+      None => self.eval_expr(&decl.value)?,
+    };
+
+    match &decl.pattern.kind {
+      PatternKind::Underscore => {}
+      _ => self.declare(decl.pattern.span, value),
+    }
+
+    Ok(Value::Void)
+  }
+
+  /// declares the definition of a name in the current frame
+  fn declare(&mut self, span: Span, value: Value<'a>) {
+    let Some(def) = self.resolutions.resolution(span) else {
+      return;
+    };
+
+    match self.frames.last_mut() {
+      Some(frame) => frame.insert(def, value),
+      None => self.globals.insert(def, value),
+    };
+  }
+
+  /// the value of the definition named at a span, the type checker selected
+  /// the overload of a call
+  fn lookup(&self, span: Span, name: &str) -> Result<Value<'a>> {
+    let not_found = || {
+      Report::Semantic(SemanticKind::IdentifierNotFound(span, name.to_string()))
+    };
+
+    let def = self.resolutions.resolution(span).ok_or_else(not_found)?;
+
+    let value = self
+      .frames
+      .last()
+      .and_then(|frame| frame.get(&def))
+      .or_else(|| self.globals.get(&def));
+
+    match value {
+      Some(value) => Ok(value.to_owned()),
+      None if self.resolutions.def(def).kind == DefKind::Builtin => {
+        Ok(Value::Builtin(Rc::from(name)))
+      }
+      None => Err(not_found()),
+    }
+  }
+
+  /// assigns a variable, an element of an array or of a tuple
+  fn assign(&mut self, lhs: &'a Expr, value: Value<'a>) -> Eval<'a> {
+    let mut path = vec![];
+    let mut root = lhs;
+
+    while let ExprKind::ArrayAccess(indexed, index)
+    | ExprKind::TupleAccess(indexed, index) = &root.kind
+    {
+      path.push((self.eval_int(index)?, root.span));
+      root = indexed;
+    }
+
+    let def = self.resolutions.resolution(root.span);
+    let frame = self.frames.last_mut();
+
+    let mut place =
+      match def.zip(frame).and_then(|(def, frame)| frame.get_mut(&def)) {
+        Some(place) => place,
+        None => {
+          return Err(
+            Report::Semantic(SemanticKind::IdentifierNotFound(
+              root.span,
+              root.to_string(),
+            ))
+            .into(),
+          )
+        }
+      };
+
+    for (index, span) in path.into_iter().rev() {
+      place = match place {
+        Value::Array(elements) | Value::Tuple(elements) => {
+          let x = element(span, index, elements.len())?;
+
+          &mut elements[x]
+        }
+        value => return Err(mismatch(span, "array", value)),
+      };
+    }
+
+    // a number is converted to the type of the variable
+    *place = match place.num() {
+      Some(num) => value.convert(num),
+      None => value,
+    };
+
+    Ok(Value::Void)
+  }
+
+  fn eval_exprs(
+    &mut self,
+    exprs: &'a [Fsp<Expr>],
+  ) -> std::result::Result<Vec<Value<'a>>, Interrupt<'a>> {
+    exprs.iter().map(|expr| self.eval_expr(expr)).collect()
+  }
+
+  /// a `str` is indexed by a char or sliced by a range of chars
+  fn eval_str_index(
+    &mut self,
+    span: Span,
+    string: &str,
+    index: &'a Expr,
+  ) -> Eval<'a> {
+    match &index.kind {
+      ExprKind::BinOp(start, op, end)
+        if matches!(op.node, BinOpKind::Range) =>
+      {
+        let start = self.eval_int(start)?;
+        let end = self.eval_int(end)?;
+
+        builtins::str_slice(span, string, start, end)
+      }
+      _ => {
+        let index = self.eval_int(index)?;
+
+        builtins::str_at(span, string, index)
+      }
+    }
+  }
+
+  fn eval_int(
+    &mut self,
+    expr: &'a Expr,
+  ) -> std::result::Result<i64, Interrupt<'a>> {
+    match self.eval_expr(expr)? {
+      Value::Int(int, _) => Ok(int),
+      value => Err(mismatch(expr.span, "int", &value)),
+    }
+  }
+}
+
+impl<'a, 'o> Evaluate<'a> for Interpreter<'a, 'o> {
+  type Value = Value<'a>;
+
+  fn void() -> Value<'a> {
+    Value::Void
+  }
+
+  fn eval_expr(&mut self, expr: &'a Expr) -> Eval<'a> {
+    match &expr.kind {
+      ExprKind::Lit(lit) => Ok(Value::from(Constant::lit(&lit.kind))),
+      ExprKind::Interpolation(segments) => {
+        self.eval_expr_interpolation(segments)
+      }
+      ExprKind::Identifier(identifier) => {
+        Ok(self.lookup(expr.span, identifier)?)
+      }
+      ExprKind::UnOp(op, rhs) => self.eval_expr_un_op(op, rhs),
+      ExprKind::BinOp(lhs, op, rhs) => {
+        self.eval_expr_bin_op(expr.span, lhs, op, rhs)
+      }
+      ExprKind::Cast(value, ty) => {
+        let value = self.eval_expr(value)?;
+
+        eval_cast(expr.span, value, ty)
+      }
+      ExprKind::Call(callee, inputs) => {
+        self.eval_expr_call(expr.span, callee, inputs)
+      }
+      ExprKind::Decl(decl) => self.eval_decl(decl),
+      ExprKind::Assign(lhs, _, rhs) => {
+        let value = self.eval_expr(rhs)?;
+
+        self.assign(lhs, value)
+      }
+      ExprKind::AssignOp(lhs, op, rhs) => {
+        let lhs_value = self.eval_expr(lhs)?;
+        let rhs_value = self.eval_expr(rhs)?;
+        let value = eval_bin_op(expr.span, lhs, lhs_value, op, rhs, rhs_value)?;
+
+        self.assign(lhs, value)
+      }
+      ExprKind::Block(block) => self.eval_block(block),
+      ExprKind::Loop(body) => self.eval_loop(None, false, body),
+      ExprKind::While(condition, body) => {
+        self.eval_loop(Some(condition), true, body)
+      }
+      ExprKind::Until(condition, body) => {
+        self.eval_loop(Some(condition), false, body)
+      }
+      ExprKind::Return(value) => {
+        let value = self.eval_maybe(value)?;
+
+        Err(Interrupt::Return(value))
+      }
+      ExprKind::Break(value) => {
+        let value = self.eval_maybe(value)?;
+
+        Err(Interrupt::Break(value))
+      }
+      ExprKind::Continue => Err(Interrupt::Continue),
+      ExprKind::When(condition, consequence, alternative) => {
+        self.eval_branch(condition, consequence, Some(alternative))
+      }
+      ExprKind::IfElse(condition, consequence, maybe_alternative) => {
+        self.eval_branch(condition, consequence, maybe_alternative.as_deref())
+      }
+      ExprKind::Lambda(inputs, body) => Ok(self.eval_expr_lambda(inputs, body)),
+      ExprKind::Array(elements) => Ok(Value::Array(self.eval_exprs(elements)?)),
+      ExprKind::ArrayAccess(indexed, index) => {
+        match self.eval_expr(indexed)? {
+          Value::Array(mut elements) => {
+            let index = self.eval_int(index)?;
+            let x = element(expr.span, index, elements.len())?;
+
+            Ok(elements.swap_remove(x))
+          }
+          Value::Str(string) => self.eval_str_index(expr.span, &string, index),
+          value => Err(mismatch(expr.span, "array", &value)),
+        }
+      }
+      // a tuple of one element is an expression between parentheses
+      ExprKind::Tuple(elements) => match elements.as_slice() {
+        [element] => self.eval_expr(element),
+        elements => Ok(Value::Tuple(self.eval_exprs(elements)?)),
+      },
+      ExprKind::TupleAccess(tuple, index) => {
+        let tuple = self.eval_expr(tuple)?;
+        let index = self.eval_int(index)?;
+
+        match tuple {
+          Value::Tuple(mut elements) => {
+            let x = element(expr.span, index, elements.len())?;
+
+            Ok(elements.swap_remove(x))
+          }
+          value => Err(mismatch(expr.span, "tuple", &value)),
+        }
+      }
+      ExprKind::Stmt(stmt) => match &stmt.kind {
+        StmtKind::Val(decl) => self.eval_decl(decl),
+        StmtKind::Fun(_)
+        | StmtKind::Ext(_)
+        | StmtKind::TyAlias(_)
+        | StmtKind::Unit(_) => Ok(Value::Void),
+      },
+    }
+  }
+
+  fn eval_bool(&mut self, expr: &'a Expr) -> Flow<Value<'a>, bool> {
+    match self.eval_expr(expr)? {
+      Value::Bool(boolean) => Ok(boolean),
+      value => Err(mismatch(expr.span, "bool", &value)),
+    }
+  }
+
+  /// the nested functions of a block are declared before its expressions
+  fn eval_block(&mut self, block: &'a Block) -> Eval<'a> {
+    for expr in &block.exprs {
+      if let ExprKind::Stmt(stmt) = &expr.kind {
+        match &stmt.kind {
+          StmtKind::Fun(fun) => {
+            self.declare(fun.prototype.name.span, Value::Fun(fun))
+          }
+          StmtKind::Ext(ext) => {
+            self.declare(ext.prototype.name.span, Value::Ext(ext))
+          }
+          _ => {}
+        }
+      }
+    }
+
+    let mut value = Value::Void;
+
+    for expr in &block.exprs {
+      value = self.eval_expr(expr)?;
+    }
+
+    Ok(value)
+  }
+}
+
+/// the position of an element, an out of bounds index is reported
+fn element<'a>(
+  span: Span,
+  index: i64,
+  len: usize,
+) -> std::result::Result<usize, Interrupt<'a>> {
+  match usize::try_from(index) {
+    Ok(x) if x < len => Ok(x),
+    _ => Err(
+      Report::Runtime(RuntimeKind::IndexOutOfBounds(span, index, len)).into(),
+    ),
+  }
+}
+
+/// converts a value to the type it's given to, the typechecker only lets a
+/// literal which fits in the type and a widening through
+fn coerce<'a>(value: Value<'a>, ty: &Ty) -> Value<'a> {
+  match (&ty.kind, value) {
+    (TyKind::Array(ty, _), Value::Array(elements)) => Value::Array(
+      elements
+        .into_iter()
+        .map(|element| coerce(element, ty))
+        .collect(),
+    ),
+    (TyKind::Tuple(tys), Value::Tuple(elements)) => Value::Tuple(
+      elements
+        .into_iter()
+        .zip(tys)
+        .map(|(element, ty)| coerce(element, ty))
+        .collect(),
+    ),
+    (kind, value) => match Num::from_ty(kind) {
+      Some(num) => value.convert(num),
+      None => value,
+    },
+  }
+}
+
+fn mismatch<'a>(span: Span, expected: &str, found: &Value) -> Interrupt<'a> {
+  Report::Semantic(SemanticKind::TypeMismatch(
+    span,
+    expected.to_string(),
+    found.kind().to_string(),
+  ))
+  .into()
+}
+
+/// converts a value to the type of `as` like a constant is converted, an
+/// array or a tuple is converted to a `str` like it's written
+fn eval_cast<'a>(span: Span, value: Value<'a>, ty: &Ty) -> Eval<'a> {
+  if let (TyKind::Str, Value::Array(_) | Value::Tuple(_)) = (&ty.kind, &value) {
+    return Ok(Value::str(&value.to_string()));
+  }
+
+  match value
+    .constant()
+    .and_then(|value| ops::eval_cast(value, &ty.kind))
+  {
+    Some(constant) => Ok(Value::from(constant)),
+    None => Err(mismatch(span, &ty.to_string(), &value)),
+  }
+}
+
+/// the scalars are computed like the constants after their types are unified,
+/// a range is an array of the integers from its start to its end
+fn eval_bin_op<'a>(
+  span: Span,
+  lhs: &Expr,
+  lhs_value: Value<'a>,
+  op: &BinOp,
+  rhs: &Expr,
+  rhs_value: Value<'a>,
+) -> Eval<'a> {
+  let (Some(lhs_constant), Some(rhs_constant)) =
+    (lhs_value.constant(), rhs_value.constant())
+  else {
+    return match op.node {
+      BinOpKind::Eq if lhs_value.kind() == rhs_value.kind() => {
+        Ok(Value::Bool(lhs_value == rhs_value))
+      }
+      BinOpKind::Ne if lhs_value.kind() == rhs_value.kind() => {
+        Ok(Value::Bool(lhs_value != rhs_value))
+      }
+      _ => Err(mismatch(op.span, lhs_value.kind(), &rhs_value)),
+    };
+  };
+
+  let (lhs_constant, rhs_constant) =
+    ops::unify(lhs, lhs_constant, rhs, rhs_constant);
+
+  if let (BinOpKind::Range, Constant::Int(start, num), Constant::Int(end, _)) =
+    (&op.node, &lhs_constant, &rhs_constant)
+  {
+    return Ok(Value::Array(
+      (*start..*end).map(|int| Value::Int(int, *num)).collect(),
+    ));
+  }
+
+  match ops::eval_bin_op(&op.node, lhs_constant, rhs_constant) {
+    Ok(Some(constant)) => Ok(Value::from(constant)),
+    Ok(None) => Err(mismatch(op.span, lhs_value.kind(), &rhs_value)),
+    Err(OpError::DivisionByZero) => {
+      Err(Report::Runtime(RuntimeKind::DivisionByZero(span)).into())
+    }
+    Err(OpError::Overflow(expr)) => {
+      Err(Report::Runtime(RuntimeKind::Overflow(span, expr)).into())
+    }
+  }
+}
@@ -0,0 +1,10 @@
+#[cfg(test)]
+mod tests;
+
+mod builtins;
+mod eval;
+mod value;
+
+pub mod interpreter {
+  pub use super::eval::{interpret, CALLS_LIMIT, STACK_SIZE};
+}
@@ -0,0 +1,51 @@
+use super::eval::interpret;
+
+use zhoo_parser::parser::parse_source;
+
+const NAMES: &str = "
+val N: int = 2;
+
+fun area(side: int): int { side * side }
+fun area(width: int, height: int): int { width * height }
+
+fun main(): int {
+  imu x := 1;
+
+  if x > 0 {
+    imu x := x + N;
+    println(x);
+  }
+
+  fun twice(n: int): int { n * 2 }
+
+  imu add: Fn(int): int = fn(y) -> x + y;
+
+  println(x);
+  println(area(3) + area(2, 5));
+  println(add(twice(2)));
+  println(fmt_uint(18446744073709551615));
+  println(fmt_f32(0.1f32));
+
+  0
+}
+";
+
+fn run(name: &str, source: &str) -> (i32, String) {
+  let program = parse_source(name, source);
+  let mut out = vec![];
+
+  let code = match interpret(&program, &mut out) {
+    Ok(code) => code,
+    Err(_) => panic!("the program should be interpreted"),
+  };
+
+  (code, String::from_utf8(out).expect("an utf-8 output"))
+}
+
+#[test]
+fn test_interpret_names() {
+  let (code, out) = run("names", NAMES);
+
+  assert_eq!(code, 0);
+  assert_eq!(out, "3\n1\n19\n5\n18446744073709551615\n0.1\n");
+}
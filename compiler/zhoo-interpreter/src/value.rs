@@ -0,0 +1,170 @@
+//! the values handled by the interpreter at runtime
+
+use zhoo_analyzer::constant::Constant;
+use zhoo_analyzer::num::Num;
+use zhoo_analyzer::resolver::DefId;
+use zhoo_ast::ast::{Expr, Ext, Fun};
+use zhoo_ast::ptr::Fsp;
+
+use fxhash::FxHashMap;
+
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Clone, Debug)]
+pub(crate) enum Value<'a> {
+  Void,
+  Bool(bool),
+  /// an integer is wrapped to the bits of its type
+  Int(i64, Num),
+  /// an `f32` is rounded to its precision
+  Real(f64, Num),
+  Char(char),
+  Str(Rc<str>),
+  Array(Vec<Value<'a>>),
+  Tuple(Vec<Value<'a>>),
+  Fun(&'a Fun),
+  Ext(&'a Ext),
+  Builtin(Rc<str>),
+  Closure(Rc<Closure<'a>>),
+}
+
+/// a lambda and the variables it captures by copy when it is created
+#[derive(Debug)]
+pub(crate) struct Closure<'a> {
+  pub inputs: &'a [Fsp<Expr>],
+  pub body: &'a Expr,
+  pub captures: FxHashMap<DefId, Value<'a>>,
+}
+
+impl<'a> Value<'a> {
+  pub fn str(string: &str) -> Self {
+    Self::Str(Rc::from(string))
+  }
+
+  pub fn int(int: i64) -> Self {
+    Self::Int(int, Num::Int)
+  }
+
+  /// the type of a number
+  pub fn num(&self) -> Option<Num> {
+    match self {
+      Self::Int(_, num) | Self::Real(_, num) => Some(*num),
+      _ => None,
+    }
+  }
+
+  /// converts a number to a type it's given to like a constant is converted,
+  /// the other values are left as they are
+  pub fn convert(self, num: Num) -> Self {
+    match self {
+      Self::Int(int, from) => Self::from(Constant::Int(int, from).convert(num)),
+      Self::Real(real, from) => {
+        Self::from(Constant::Real(real, from).convert(num))
+      }
+      value => value,
+    }
+  }
+
+  /// the constant of a scalar value, the operators of the scalars are the
+  /// ones of the constants
+  pub fn constant(&self) -> Option<Constant> {
+    match self {
+      Self::Bool(boolean) => Some(Constant::Bool(*boolean)),
+      Self::Int(int, num) => Some(Constant::Int(*int, *num)),
+      Self::Real(real, num) => Some(Constant::Real(*real, *num)),
+      Self::Char(char) => Some(Constant::Char(*char)),
+      Self::Str(string) => Some(Constant::Str(string.to_string())),
+      _ => None,
+    }
+  }
+
+  /// the name of the type of the value, used by the error messages
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Self::Void => "void",
+      Self::Bool(_) => "bool",
+      Self::Int(_, num) | Self::Real(_, num) => num.name(),
+      Self::Char(_) => "char",
+      Self::Str(_) => "str",
+      Self::Array(_) => "array",
+      Self::Tuple(_) => "tuple",
+      Self::Fun(_) | Self::Ext(_) | Self::Builtin(_) | Self::Closure(_) => "fn",
+    }
+  }
+}
+
+impl<'a> From<Constant> for Value<'a> {
+  fn from(constant: Constant) -> Self {
+    match constant {
+      Constant::Bool(boolean) => Self::Bool(boolean),
+      Constant::Int(int, num) => Self::Int(int, num),
+      Constant::Real(real, num) => Self::Real(real, num),
+      Constant::Char(char) => Self::Char(char),
+      Constant::Str(string) => Self::str(&string),
+    }
+  }
+}
+
+impl<'a> PartialEq for Value<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::Void, Self::Void) => true,
+      (Self::Bool(lhs), Self::Bool(rhs)) => lhs == rhs,
+      (Self::Int(lhs, _), Self::Int(rhs, _)) => lhs == rhs,
+      (Self::Real(lhs, _), Self::Real(rhs, _)) => lhs == rhs,
+      (Self::Char(lhs), Self::Char(rhs)) => lhs == rhs,
+      (Self::Str(lhs), Self::Str(rhs)) => lhs == rhs,
+      (Self::Array(lhs), Self::Array(rhs)) => lhs == rhs,
+      (Self::Tuple(lhs), Self::Tuple(rhs)) => lhs == rhs,
+      (Self::Fun(lhs), Self::Fun(rhs)) => std::ptr::eq(*lhs, *rhs),
+      (Self::Ext(lhs), Self::Ext(rhs)) => std::ptr::eq(*lhs, *rhs),
+      (Self::Builtin(lhs), Self::Builtin(rhs)) => lhs == rhs,
+      (Self::Closure(lhs), Self::Closure(rhs)) => Rc::ptr_eq(lhs, rhs),
+      _ => false,
+    }
+  }
+}
+
+impl<'a> fmt::Display for Value<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Void => write!(f, "()"),
+      Self::Bool(boolean) => write!(f, "{boolean}"),
+      Self::Int(int, Num::U64) => write!(f, "{}", *int as u64),
+      Self::Int(int, _) => write!(f, "{int}"),
+      // an `f32` is written with its own precision, like `fmt_f32`
+      Self::Real(real, Num::F32) => write!(f, "{}", *real as f32),
+      Self::Real(real, _) => write!(f, "{real}"),
+      Self::Char(char) => write!(f, "{char}"),
+      Self::Str(string) => write!(f, "{string}"),
+      Self::Array(elements) => write!(f, "[{}]", Sep(elements)),
+      Self::Tuple(elements) => write!(f, "({})", Sep(elements)),
+      Self::Fun(fun) => write!(f, "fun {}", fun.prototype.name),
+      Self::Ext(ext) => write!(f, "ext {}", ext.prototype.name),
+      Self::Builtin(name) => write!(f, "fun {name}"),
+      Self::Closure(_) => write!(f, "fn"),
+    }
+  }
+}
+
+struct Sep<'b, 'a>(&'b [Value<'a>]);
+
+impl<'b, 'a> fmt::Display for Sep<'b, 'a> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (x, value) in self.0.iter().enumerate() {
+      if x > 0 {
+        write!(f, ", ")?;
+      }
+
+      // an element is written like its literal
+      match value {
+        Value::Char(char) => write!(f, "'{char}'")?,
+        Value::Str(string) => write!(f, "\"{string}\"")?,
+        value => write!(f, "{value}")?,
+      }
+    }
+
+    Ok(())
+  }
+}
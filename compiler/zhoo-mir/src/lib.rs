@@ -0,0 +1,9 @@
+#[cfg(test)]
+mod tests;
+
+mod pp;
+mod transform;
+
+pub mod lower;
+pub mod mir;
+pub mod optimizer;
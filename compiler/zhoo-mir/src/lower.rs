@@ -0,0 +1,1265 @@
+//! the lowering of a checked program into the mir, the program is lowered
+//! into the hir first, then its branches and its loops become basic blocks
+//! linked by their terminators
+
+use super::mir::{
+  BasicBlock, BinOp, BlockId, Body, Const, Extern, Local, LocalDecl, Operand,
+  Program, Rvalue, Stmt, StmtKind, Terminator, TerminatorKind, Ty, UnOp,
+};
+
+use super::transform::simplify;
+
+use zhoo_analyzer::builtins::{builtins, overload, Overload, Proto};
+use zhoo_analyzer::constant::{self, Constant};
+use zhoo_analyzer::resolver::{DefId, DefKind, Resolutions};
+use zhoo_ast::ast::{self, BinOpKind, LitKind, UnOpKind};
+use zhoo_ast::ptr::Fsp;
+use zhoo_errors::{Report, Result, RuntimeKind, SemanticKind};
+use zhoo_hir::hir::{self, Block, Expr, ExprKind, Ident, LocalId};
+use zhoo_hir::lower as hir_lower;
+use zhoo_span::span::Span;
+
+use fxhash::FxHashMap;
+
+/// the types of the inputs and of the output of a function
+type Signature = (Vec<Ty>, Ty);
+
+/// lowers every function of a checked program with the resolutions its
+/// analysis gives
+pub fn lower(
+  program: &ast::Program,
+  resolutions: &Resolutions,
+) -> Result<Program> {
+  let vals = constant::eval_vals(program, resolutions)?;
+  let hir = hir_lower::lower(program);
+  let mut signatures = FxHashMap::default();
+  let mut externs = vec![];
+  let mut bodies = vec![];
+
+  let builtins = builtins()
+    .into_iter()
+    .map(|builtin| (builtin.name, builtin.proto))
+    .collect::<FxHashMap<_, _>>();
+
+  for (def, decl) in resolutions.defs() {
+    if let Some(Proto(inputs, output)) = builtins.get(&decl.name) {
+      if decl.kind == DefKind::Builtin {
+        signatures.insert(def, lower_signature(inputs, output)?);
+      }
+    }
+  }
+
+  for item in &hir.items {
+    match &item.kind {
+      hir::ItemKind::Ext(ext) => {
+        let (inputs, output) = lower_signature(&ext.inputs, &ext.output)?;
+
+        externs.push(Extern {
+          name: ext.name.name.to_string(),
+          inputs: inputs.to_owned(),
+          output,
+        });
+
+        signatures.insert(item_def(resolutions, &ext.name), (inputs, output));
+      }
+      hir::ItemKind::Fun(fun) => {
+        declare_fun(&hir, resolutions, &mut signatures, fun)?;
+      }
+      hir::ItemKind::Val(_) => {}
+    }
+  }
+
+  // a `val` is stored with the type of its constant
+  let mut vals_tys = vals
+    .iter()
+    .map(|(name, constant)| {
+      let ty = constant_ty(constant);
+
+      (name.to_string(), (ty, ty))
+    })
+    .collect::<FxHashMap<_, _>>();
+
+  for item in &hir.items {
+    if let hir::ItemKind::Val(hir::Val {
+      name, ty: Some(ty), ..
+    }) = &item.kind
+    {
+      if let Some((_, val_ty)) = vals_tys.get_mut(&name.name) {
+        *val_ty = lower_ty(ty)?;
+      }
+    }
+  }
+
+  for item in &hir.items {
+    if let hir::ItemKind::Fun(fun) = &item.kind {
+      bodies.extend(
+        Builder::new(&hir, resolutions, &mut signatures, &vals_tys)
+          .lower_fun(fun, item.span)?,
+      );
+    }
+  }
+
+  Ok(Program {
+    externs,
+    bodies,
+    vals,
+  })
+}
+
+fn item_def(resolutions: &Resolutions, name: &Ident) -> DefId {
+  resolutions.resolution(name.span).expect("a resolved item")
+}
+
+fn declare_fun(
+  hir: &hir::Program,
+  resolutions: &Resolutions,
+  signatures: &mut FxHashMap<DefId, Signature>,
+  fun: &hir::Fun,
+) -> Result<()> {
+  let inputs = fun
+    .inputs
+    .iter()
+    .map(|input| hir.local(*input).ty.to_owned().expect("a type"))
+    .collect::<Vec<_>>();
+
+  signatures.insert(
+    item_def(resolutions, &fun.name),
+    lower_signature(&inputs, &fun.output)?,
+  );
+
+  Ok(())
+}
+
+fn lower_signature(
+  inputs: &[Fsp<ast::Ty>],
+  output: &ast::Ty,
+) -> Result<Signature> {
+  let inputs = inputs
+    .iter()
+    .map(|input| lower_ty(input))
+    .collect::<Result<Vec<_>>>()?;
+
+  Ok((inputs, lower_ty(output)?))
+}
+
+/// the mir type of a type, the mir only has scalar types
+pub fn lower_ty(ty: &ast::Ty) -> Result<Ty> {
+  match &ty.kind {
+    ast::TyKind::Void => Ok(Ty::Void),
+    ast::TyKind::Bool => Ok(Ty::Bool),
+    ast::TyKind::Int => Ok(Ty::Int),
+    ast::TyKind::Real => Ok(Ty::Real),
+    ast::TyKind::Str => Ok(Ty::Str),
+    ast::TyKind::Char => Ok(Ty::Char),
+    ast::TyKind::S8 => Ok(Ty::S8),
+    ast::TyKind::S16 => Ok(Ty::S16),
+    ast::TyKind::S32 => Ok(Ty::S32),
+    ast::TyKind::U8 => Ok(Ty::U8),
+    ast::TyKind::U16 => Ok(Ty::U16),
+    ast::TyKind::U32 => Ok(Ty::U32),
+    ast::TyKind::U64 => Ok(Ty::U64),
+    ast::TyKind::F32 => Ok(Ty::F32),
+    ast::TyKind::Infer => Err(unsupported(ty.span, "inferred types")),
+    ast::TyKind::Fn(..) => Err(unsupported(ty.span, "function types")),
+    ast::TyKind::Array(..) => Err(unsupported(ty.span, "arrays")),
+    ast::TyKind::Tuple(..) => Err(unsupported(ty.span, "tuples")),
+  }
+}
+
+/// the type of a number literal, an unsuffixed number has the default type
+fn lower_suffix(
+  suffix: &Option<ast::TyKind>,
+  default: Ty,
+  span: Span,
+) -> Result<Ty> {
+  match suffix {
+    Some(kind) => lower_ty(&ast::Ty::new(kind.to_owned(), span)),
+    None => Ok(default),
+  }
+}
+
+fn constant_ty(constant: &Constant) -> Ty {
+  match constant {
+    Constant::Bool(_) => Ty::Bool,
+    Constant::Int(..) => Ty::Int,
+    Constant::Real(..) => Ty::Real,
+    Constant::Char(_) => Ty::Char,
+    Constant::Str(_) => Ty::Str,
+  }
+}
+
+/// an unsuffixed number only takes the type of the other operand when it is
+/// a number of the same kind
+fn takes_ty(expr: &Expr, ty: Ty) -> bool {
+  match expr.number_lit() {
+    Some(LitKind::Int(..)) => ty.is_integer(),
+    Some(LitKind::Real(..)) => ty.is_float(),
+    _ => false,
+  }
+}
+
+fn unsupported(span: Span, what: &str) -> Report {
+  Report::Semantic(SemanticKind::Unsupported(span, what.to_string()))
+}
+
+/// an array or a tuple is kept in the locals of its elements, an element is
+/// read by its position
+#[derive(Clone)]
+enum Aggregate {
+  Scalar(Operand),
+  Array(Vec<Aggregate>),
+  Tuple(Vec<Aggregate>),
+}
+
+impl Aggregate {
+  /// the scalars of the aggregate in the order of its elements
+  fn scalars(self) -> Vec<Operand> {
+    match self {
+      Self::Scalar(value) => vec![value],
+      Self::Array(elements) | Self::Tuple(elements) => {
+        elements.into_iter().flat_map(Self::scalars).collect()
+      }
+    }
+  }
+}
+
+/// the blocks of a loop reached by `continue` and `break`
+struct Loop {
+  header: BlockId,
+  exit: BlockId,
+  /// the value given to `break`
+  value: Option<Local>,
+}
+
+struct Builder<'a> {
+  hir: &'a hir::Program,
+  resolutions: &'a Resolutions,
+  signatures: &'a mut FxHashMap<DefId, Signature>,
+  /// the type of the constant and the type of every `val`
+  vals: &'a FxHashMap<String, (Ty, Ty)>,
+  /// the output of the function
+  output: Ty,
+  locals: Vec<LocalDecl>,
+  blocks: Vec<BasicBlock>,
+  current: BlockId,
+  /// the mir local of every binding of the function
+  bindings: FxHashMap<DefId, Local>,
+  /// the elements of every binding of an array or a tuple
+  aggregates: FxHashMap<DefId, Aggregate>,
+  loops: Vec<Loop>,
+  /// the bodies of the functions declared inside of the function
+  nested: Vec<Body>,
+}
+
+impl<'a> Builder<'a> {
+  fn new(
+    hir: &'a hir::Program,
+    resolutions: &'a Resolutions,
+    signatures: &'a mut FxHashMap<DefId, Signature>,
+    vals: &'a FxHashMap<String, (Ty, Ty)>,
+  ) -> Self {
+    Self {
+      hir,
+      resolutions,
+      signatures,
+      vals,
+      output: Ty::Void,
+      locals: vec![],
+      blocks: vec![],
+      current: BlockId::ENTRY,
+      bindings: FxHashMap::default(),
+      aggregates: FxHashMap::default(),
+      loops: vec![],
+      nested: vec![],
+    }
+  }
+
+  /// lowers a function, its body comes before the bodies of its nested
+  /// functions
+  fn lower_fun(mut self, fun: &hir::Fun, span: Span) -> Result<Vec<Body>> {
+    let def = self.resolutions.def(item_def(self.resolutions, &fun.name));
+    let name = def.symbol.to_string();
+    let is_local = name != def.name;
+    let output = lower_ty(&fun.output)?;
+
+    self.output = output;
+
+    for input in &fun.inputs {
+      let ty = lower_ty(self.hir.local(*input).ty.as_ref().expect("a type"))?;
+
+      self.declare(*input, ty);
+    }
+
+    let entry = self.new_block();
+
+    self.switch_to(entry);
+
+    let value = self.lower_block(&fun.body)?;
+
+    // a function without output returns nothing even if its last expression
+    // has a value
+    let value = match output {
+      Ty::Void => Operand::Const(Const::Void),
+      _ => self.coerce(value, output, fun.body.span),
+    };
+
+    self.terminate(TerminatorKind::Return(value), fun.body.span);
+
+    let mut body = Body {
+      name,
+      inputs: fun.inputs.len(),
+      output,
+      locals: self.locals,
+      blocks: self.blocks,
+      is_local,
+      span,
+    };
+
+    simplify::remove_unreachable_blocks(&mut body);
+
+    Ok(std::iter::once(body).chain(self.nested).collect())
+  }
+
+  fn lower_expr(&mut self, expr: &Expr) -> Result<Operand> {
+    match &expr.kind {
+      ExprKind::Lit(lit) => Ok(Operand::Const(match lit {
+        LitKind::Bool(boolean) => Const::Bool(*boolean),
+        LitKind::Int(int, suffix) => {
+          let ty = LitKind::int_ty(*int, suffix);
+          let ty = lower_ty(&ast::Ty::new(ty, expr.span))?;
+
+          Const::Int(ty.wrap(*int as i64), ty)
+        }
+        LitKind::Real(real, suffix) => {
+          Const::Real(*real, lower_suffix(suffix, Ty::Real, expr.span)?)
+        }
+        LitKind::Char(char) => Const::Char(*char),
+        LitKind::Str(string) => Const::Str(string.to_string()),
+      })),
+      ExprKind::Interpolation(segments) => {
+        self.lower_expr_interpolation(segments, expr.span)
+      }
+      ExprKind::Local(local) => Ok(Operand::Copy(self.binding(*local))),
+      ExprKind::Global(ident) => self.lower_expr_global(ident),
+      ExprKind::UnOp(op, rhs) => {
+        let rhs = self.lower_expr(rhs)?;
+        let ty = self.operand_ty(&rhs);
+
+        let op = match op.node {
+          UnOpKind::Neg => UnOp::Neg,
+          UnOpKind::Not => UnOp::Not,
+        };
+
+        Ok(self.temporary(ty, Rvalue::UnOp(op, rhs), expr.span))
+      }
+      ExprKind::BinOp(lhs, op, rhs) => {
+        self.lower_expr_bin_op(expr.span, lhs, op, rhs)
+      }
+      // a value is converted to a `str` like it's written
+      ExprKind::Cast(value, ty) if ty.kind == ast::TyKind::Str => {
+        self.render(value)
+      }
+      ExprKind::Cast(value, ty) => {
+        let value = self.lower_expr(value)?;
+
+        Ok(self.coerce(value, lower_ty(ty)?, expr.span))
+      }
+      ExprKind::Call(callee, inputs) => {
+        self.lower_expr_call(expr.span, callee, inputs)
+      }
+      ExprKind::Let(local, value) => self.lower_let(expr.span, *local, value),
+      ExprKind::Assign(lhs, rhs) => {
+        let local = match &lhs.kind {
+          ExprKind::Local(local) => *local,
+          _ => return Err(unsupported(lhs.span, "assignments of elements")),
+        };
+
+        if let Some(aggregate) = self.aggregate(local) {
+          return self.lower_assign_aggregate(expr.span, aggregate, rhs);
+        }
+
+        let local = self.binding(local);
+
+        let rhs = self.lower_expr(rhs)?;
+        let rhs = self.coerce(rhs, self.locals[local.0].ty, expr.span);
+
+        self.assign(local, Rvalue::Use(rhs), expr.span);
+
+        Ok(Operand::Const(Const::Void))
+      }
+      ExprKind::Block(block) => self.lower_block(block),
+      ExprKind::Loop(body, _) => self.lower_loop(body),
+      ExprKind::Return(maybe_expr) => {
+        let value = self.lower_maybe(maybe_expr)?;
+        let value = match self.output {
+          Ty::Void => value,
+          output => self.coerce(value, output, expr.span),
+        };
+
+        self.terminate(TerminatorKind::Return(value), expr.span);
+
+        Ok(Operand::Const(Const::Void))
+      }
+      ExprKind::Break(maybe_expr) => self.lower_break(expr.span, maybe_expr),
+      ExprKind::Continue => {
+        let header = match self.loops.last() {
+          Some(header) => header.header,
+          None => {
+            return Err(unsupported(expr.span, "`continue` out of loops"))
+          }
+        };
+
+        self.terminate(TerminatorKind::Goto(header), expr.span);
+
+        Ok(Operand::Const(Const::Void))
+      }
+      ExprKind::If(condition, consequence, maybe_alternative) => self
+        .lower_conditional(
+          expr.span,
+          condition,
+          consequence,
+          maybe_alternative.as_deref(),
+        ),
+      ExprKind::Lambda(..) => Err(unsupported(expr.span, "lambdas")),
+      ExprKind::Index(..)
+      | ExprKind::Array(_)
+      | ExprKind::Tuple(_)
+      | ExprKind::Field(..) => match self.lower_aggregate(expr)? {
+        Aggregate::Scalar(value) => Ok(value),
+        Aggregate::Array(_) => Err(unsupported(expr.span, "arrays as values")),
+        Aggregate::Tuple(_) => Err(unsupported(expr.span, "tuples as values")),
+      },
+      ExprKind::Item(item) => match &item.kind {
+        hir::ItemKind::Fun(fun) => {
+          let bodies = Builder::new(
+            self.hir,
+            self.resolutions,
+            self.signatures,
+            self.vals,
+          )
+          .lower_fun(fun, item.span)?;
+
+          self.nested.extend(bodies);
+
+          Ok(Operand::Const(Const::Void))
+        }
+        _ => Err(unsupported(expr.span, "nested externs")),
+      },
+    }
+  }
+
+  /// a global read as a value is a top-level `val`
+  fn lower_expr_global(&mut self, ident: &Ident) -> Result<Operand> {
+    let def = self.resolutions.def(item_def(self.resolutions, ident));
+
+    match (def.kind, self.vals.get(&def.name).copied()) {
+      (DefKind::Val, Some((constant_ty, ty))) => {
+        let value = self.temporary(
+          constant_ty,
+          Rvalue::Val(def.name.to_string()),
+          ident.span,
+        );
+
+        Ok(self.coerce(value, ty, ident.span))
+      }
+      _ => Err(unsupported(ident.span, "function values")),
+    }
+  }
+
+  fn lower_expr_bin_op(
+    &mut self,
+    span: Span,
+    lhs: &Expr,
+    op: &ast::BinOp,
+    rhs: &Expr,
+  ) -> Result<Operand> {
+    if matches!(op.node, BinOpKind::And | BinOpKind::Or) {
+      return self.lower_logical(span, lhs, op, rhs);
+    }
+
+    if matches!(op.node, BinOpKind::Concat) {
+      let inputs = vec![self.lower_expr(lhs)?, self.lower_expr(rhs)?];
+
+      return Ok(self.temporary(
+        Ty::Str,
+        Rvalue::Call(String::from("str_concat"), inputs),
+        span,
+      ));
+    }
+
+    let bin_op = lower_bin_op(op)?;
+    let lhs_value = self.lower_expr(lhs)?;
+    let rhs_value = self.lower_expr(rhs)?;
+
+    // the same choice of type as the typechecker, an unsuffixed number takes
+    // the type of the other operand, an integer mixed with a real is converted
+    // to the real and the smaller operand is widened
+    let operands_ty =
+      match (self.operand_ty(&lhs_value), self.operand_ty(&rhs_value)) {
+        (lhs_ty, rhs_ty) if lhs_ty == rhs_ty => lhs_ty,
+        (lhs_ty, _) if takes_ty(rhs, lhs_ty) => lhs_ty,
+        (_, rhs_ty) if takes_ty(lhs, rhs_ty) => rhs_ty,
+        (lhs_ty, rhs_ty) if lhs_ty.is_float() != rhs_ty.is_float() => {
+          match lhs_ty.is_float() {
+            true => lhs_ty,
+            false => rhs_ty,
+          }
+        }
+        (lhs_ty, rhs_ty) if lhs_ty.bits() < rhs_ty.bits() => rhs_ty,
+        (lhs_ty, _) => lhs_ty,
+      };
+
+    // the `str`s are compared by `str_cmp` of the core library, it gives
+    // `-1`, `0` or `1`
+    if operands_ty == Ty::Str {
+      let ordering = self.temporary(
+        Ty::Int,
+        Rvalue::Call(String::from("str_cmp"), vec![lhs_value, rhs_value]),
+        span,
+      );
+
+      let zero = Operand::Const(Const::Int(0, Ty::Int));
+
+      return Ok(self.temporary(
+        Ty::Bool,
+        Rvalue::BinOp(bin_op, ordering, zero),
+        span,
+      ));
+    }
+
+    if operands_ty.is_float() && !bin_op.is_arithmetic() {
+      return Err(unsupported(op.span, "remainders of `real`s"));
+    }
+
+    let lhs = self.coerce(lhs_value, operands_ty, lhs.span);
+    let rhs = self.coerce(rhs_value, operands_ty, rhs.span);
+
+    let ty = if bin_op.is_comparison() {
+      Ty::Bool
+    } else {
+      operands_ty
+    };
+
+    Ok(self.temporary(ty, Rvalue::BinOp(bin_op, lhs, rhs), span))
+  }
+
+  /// an interpolated `str` is the concatenation of its segments, a segment
+  /// which is not a `str` is formatted by the core library
+  fn lower_expr_interpolation(
+    &mut self,
+    segments: &[Expr],
+    span: Span,
+  ) -> Result<Operand> {
+    let mut string = Operand::Const(Const::Str(String::new()));
+
+    for (x, segment) in segments.iter().enumerate() {
+      let value = self.render(segment)?;
+
+      string = match x {
+        0 => value,
+        _ => self.concat(string, value, span),
+      };
+    }
+
+    Ok(string)
+  }
+
+  /// the `str` of an expression, an array or a tuple is written element by
+  /// element: `[1, 2]`, `(1, 'a', "b")`
+  fn render(&mut self, expr: &Expr) -> Result<Operand> {
+    let aggregate = self.lower_aggregate(expr)?;
+
+    Ok(self.render_aggregate(aggregate, expr.span))
+  }
+
+  fn render_aggregate(&mut self, aggregate: Aggregate, span: Span) -> Operand {
+    let (open, elements, close) = match aggregate {
+      Aggregate::Array(elements) => ("[", elements, "]"),
+      Aggregate::Tuple(elements) => ("(", elements, ")"),
+      Aggregate::Scalar(value) => return self.format(value, span),
+    };
+
+    let mut string = Operand::Const(Const::Str(String::from(open)));
+
+    for (x, element) in elements.into_iter().enumerate() {
+      if x > 0 {
+        let separator = Operand::Const(Const::Str(String::from(", ")));
+
+        string = self.concat(string, separator, span);
+      }
+
+      let value = self.render_element(element, span);
+
+      string = self.concat(string, value, span);
+    }
+
+    let close = Operand::Const(Const::Str(String::from(close)));
+
+    self.concat(string, close, span)
+  }
+
+  /// an element of an array or of a tuple is written like its literal, a
+  /// `char` and a `str` are quoted
+  fn render_element(&mut self, element: Aggregate, span: Span) -> Operand {
+    let value = match element {
+      Aggregate::Scalar(value) => value,
+      aggregate => return self.render_aggregate(aggregate, span),
+    };
+
+    let quote = match self.operand_ty(&value) {
+      Ty::Char => "'",
+      Ty::Str => "\"",
+      _ => return self.format(value, span),
+    };
+
+    let value = self.format(value, span);
+    let open = Operand::Const(Const::Str(String::from(quote)));
+    let string = self.concat(open, value, span);
+    let close = Operand::Const(Const::Str(String::from(quote)));
+
+    self.concat(string, close, span)
+  }
+
+  fn concat(&mut self, lhs: Operand, rhs: Operand, span: Span) -> Operand {
+    self.temporary(
+      Ty::Str,
+      Rvalue::Call(String::from("str_concat"), vec![lhs, rhs]),
+      span,
+    )
+  }
+
+  /// the `str` of a value, an integer is written as an `int` unless it's a
+  /// `u64`
+  fn format(&mut self, value: Operand, span: Span) -> Operand {
+    let (name, ty) = match self.operand_ty(&value) {
+      Ty::Str => return value,
+      Ty::Bool => ("fmt_bool", Ty::Bool),
+      Ty::Char => ("fmt_char", Ty::Char),
+      Ty::Real => ("fmt_real", Ty::Real),
+      Ty::F32 => ("fmt_f32", Ty::F32),
+      Ty::U64 => ("fmt_uint", Ty::U64),
+      _ => ("fmt_int", Ty::Int),
+    };
+
+    let value = self.coerce(value, ty, span);
+
+    self.temporary(Ty::Str, Rvalue::Call(String::from(name), vec![value]), span)
+  }
+
+  /// a `str` is indexed by `str_at` and sliced by `str_slice` of the core
+  /// library
+  fn lower_expr_index(
+    &mut self,
+    span: Span,
+    indexed: Operand,
+    index: &Expr,
+  ) -> Result<Operand> {
+    if self.operand_ty(&indexed) != Ty::Str {
+      return Err(unsupported(span, "arrays"));
+    }
+
+    let (name, mut inputs) = match &index.kind {
+      ExprKind::BinOp(start, op, end)
+        if matches!(op.node, BinOpKind::Range) =>
+      {
+        let start = self.lower_expr(start)?;
+        let end = self.lower_expr(end)?;
+
+        ("str_slice", vec![start, end])
+      }
+      _ => ("str_at", vec![self.lower_expr(index)?]),
+    };
+
+    inputs.insert(0, indexed);
+
+    Ok(self.temporary(Ty::Str, Rvalue::Call(String::from(name), inputs), span))
+  }
+
+  /// the rhs of `&&` and `||` is only evaluated when it gives the result
+  fn lower_logical(
+    &mut self,
+    span: Span,
+    lhs: &Expr,
+    op: &ast::BinOp,
+    rhs: &Expr,
+  ) -> Result<Operand> {
+    let lhs = self.lower_expr(lhs)?;
+    let result = self.new_local(Ty::Bool, None, span);
+    let rhs_block = self.new_block();
+    let merge_block = self.new_block();
+
+    self.assign(result, Rvalue::Use(lhs.to_owned()), span);
+
+    let (consequence, alternative) = match op.node {
+      BinOpKind::And => (rhs_block, merge_block),
+      _ => (merge_block, rhs_block),
+    };
+
+    self.terminate(TerminatorKind::Branch(lhs, consequence, alternative), span);
+    self.switch_to(rhs_block);
+
+    let rhs = self.lower_expr(rhs)?;
+
+    self.assign(result, Rvalue::Use(rhs), span);
+    self.terminate(TerminatorKind::Goto(merge_block), span);
+    self.switch_to(merge_block);
+
+    Ok(Operand::Copy(result))
+  }
+
+  fn lower_expr_call(
+    &mut self,
+    span: Span,
+    callee: &Expr,
+    inputs: &[Expr],
+  ) -> Result<Operand> {
+    let def = match &callee.kind {
+      ExprKind::Global(ident) => item_def(self.resolutions, ident),
+      _ => return Err(unsupported(callee.span, "calls of function values")),
+    };
+
+    let name = self.resolutions.def(def).symbol.to_string();
+
+    let (inputs_tys, output) = match self.signatures.get(&def) {
+      Some(signature) => signature.to_owned(),
+      None => return Err(unsupported(callee.span, "calls of function values")),
+    };
+
+    if self.resolutions.def(def).kind == DefKind::Builtin {
+      if let Some(overload) = overload(&name) {
+        return self.lower_expr_call_overload(span, &overload, &inputs[0]);
+      }
+    }
+
+    let inputs = inputs
+      .iter()
+      .zip(inputs_tys)
+      .map(|(input, ty)| {
+        let value = self.lower_expr(input)?;
+
+        Ok(self.coerce(value, ty, input.span))
+      })
+      .collect::<Result<Vec<_>>>()?;
+
+    let value = self.temporary(output, Rvalue::Call(name, inputs), span);
+
+    match output {
+      Ty::Void => Ok(Operand::Const(Const::Void)),
+      _ => Ok(value),
+    }
+  }
+
+  /// the entry point of an overloaded builtin is the one of the type of its
+  /// input, a value without entry point is written from its `str`
+  fn lower_expr_call_overload(
+    &mut self,
+    span: Span,
+    overload: &Overload,
+    input: &Expr,
+  ) -> Result<Operand> {
+    let value = match self.lower_aggregate(input)? {
+      Aggregate::Scalar(value) => value,
+      aggregate => self.render_aggregate(aggregate, input.span),
+    };
+
+    let ty = self.operand_ty(&value);
+
+    let entry = overload
+      .entries
+      .iter()
+      .find(|(entry_ty, _)| lower_ty(entry_ty).ok() == Some(ty))
+      .map(|(_, entry)| *entry);
+
+    let (entry, value) = match entry {
+      Some(entry) => (entry, value),
+      None => (
+        overload
+          .entry(&ast::TyKind::Str)
+          .expect("a `str` entry point"),
+        self.format(value, input.span),
+      ),
+    };
+
+    self.temporary(
+      Ty::Void,
+      Rvalue::Call(entry.to_string(), vec![value]),
+      span,
+    );
+
+    Ok(Operand::Const(Const::Void))
+  }
+
+  fn lower_let(
+    &mut self,
+    span: Span,
+    local: LocalId,
+    value: &Expr,
+  ) -> Result<Operand> {
+    let value = match self.lower_aggregate(value)? {
+      Aggregate::Scalar(value) => value,
+      aggregate => {
+        let decl = self.hir.local(local);
+        let aggregate =
+          self.store(aggregate, decl.ty.as_deref(), &decl.name, decl.span)?;
+
+        self
+          .aggregates
+          .insert(self.resolutions.local(local), aggregate);
+
+        return Ok(Operand::Const(Const::Void));
+      }
+    };
+
+    let ty = match &self.hir.local(local).ty {
+      Some(ty) => lower_ty(ty)?,
+      None => self.operand_ty(&value),
+    };
+
+    let value = self.coerce(value, ty, span);
+    let local = self.declare(local, ty);
+
+    self.assign(local, Rvalue::Use(value), span);
+
+    Ok(Operand::Const(Const::Void))
+  }
+
+  /// the elements of an array or of a tuple, an element is read from the
+  /// locals of its array or of its tuple by its position. any other
+  /// expression is a scalar
+  fn lower_aggregate(&mut self, expr: &Expr) -> Result<Aggregate> {
+    match &expr.kind {
+      ExprKind::Array(elements) => Ok(Aggregate::Array(
+        elements
+          .iter()
+          .map(|element| self.lower_aggregate(element))
+          .collect::<Result<Vec<_>>>()?,
+      )),
+      ExprKind::Tuple(elements) => Ok(Aggregate::Tuple(
+        elements
+          .iter()
+          .map(|element| self.lower_aggregate(element))
+          .collect::<Result<Vec<_>>>()?,
+      )),
+      ExprKind::Local(local) => match self.aggregate(*local) {
+        Some(aggregate) => Ok(aggregate),
+        None => Ok(Aggregate::Scalar(Operand::Copy(self.binding(*local)))),
+      },
+      ExprKind::Field(tuple, index) => {
+        let elements = match self.lower_aggregate(tuple)? {
+          Aggregate::Tuple(elements) => elements,
+          _ => return Err(unsupported(expr.span, "fields of values")),
+        };
+
+        let index = match self.lower_expr(index)? {
+          Operand::Const(Const::Int(int, _)) => int,
+          _ => return Err(unsupported(index.span, "fields of values")),
+        };
+
+        self.element(elements, index, expr.span)
+      }
+      ExprKind::Index(indexed, index) => match self.lower_aggregate(indexed)? {
+        Aggregate::Array(elements) => match self.lower_expr(index)? {
+          Operand::Const(Const::Int(int, _)) => {
+            self.element(elements, int, expr.span)
+          }
+          _ => Err(unsupported(index.span, "arrays indexed by a variable")),
+        },
+        Aggregate::Scalar(indexed) => Ok(Aggregate::Scalar(
+          self.lower_expr_index(expr.span, indexed, index)?,
+        )),
+        Aggregate::Tuple(_) => Err(unsupported(expr.span, "indexes of tuples")),
+      },
+      _ => Ok(Aggregate::Scalar(self.lower_expr(expr)?)),
+    }
+  }
+
+  /// the element at a position, an index out of the elements is reported
+  /// like the interpreter reports it
+  fn element(
+    &mut self,
+    elements: Vec<Aggregate>,
+    index: i64,
+    span: Span,
+  ) -> Result<Aggregate> {
+    let len = elements.len();
+
+    usize::try_from(index)
+      .ok()
+      .and_then(|index| elements.into_iter().nth(index))
+      .ok_or(Report::Runtime(RuntimeKind::IndexOutOfBounds(
+        span, index, len,
+      )))
+  }
+
+  /// gives a local to every element of an array or of a tuple, an element is
+  /// converted to its type when the binding has one
+  fn store(
+    &mut self,
+    aggregate: Aggregate,
+    ty: Option<&ast::Ty>,
+    name: &str,
+    span: Span,
+  ) -> Result<Aggregate> {
+    let (elements, is_array) = match aggregate {
+      Aggregate::Scalar(value) => {
+        let ty = match ty {
+          Some(ty) => lower_ty(ty)?,
+          None => self.operand_ty(&value),
+        };
+
+        let value = self.coerce(value, ty, span);
+        let local = self.new_local(ty, Some(name.to_string()), span);
+
+        self.assign(local, Rvalue::Use(value), span);
+
+        return Ok(Aggregate::Scalar(Operand::Copy(local)));
+      }
+      Aggregate::Array(elements) => (elements, true),
+      Aggregate::Tuple(elements) => (elements, false),
+    };
+
+    let tys = match ty.map(|ty| &ty.kind) {
+      Some(ast::TyKind::Array(ty, _)) => vec![Some(&**ty); elements.len()],
+      Some(ast::TyKind::Tuple(tys)) => {
+        tys.iter().map(|ty| Some(&**ty)).collect()
+      }
+      _ => vec![None; elements.len()],
+    };
+
+    let elements = elements
+      .into_iter()
+      .zip(tys)
+      .enumerate()
+      .map(|(x, (element, ty))| {
+        let name = match is_array {
+          true => format!("{name}[{x}]"),
+          false => format!("{name}.{x}"),
+        };
+
+        self.store(element, ty, &name, span)
+      })
+      .collect::<Result<Vec<_>>>()?;
+
+    match is_array {
+      true => Ok(Aggregate::Array(elements)),
+      false => Ok(Aggregate::Tuple(elements)),
+    }
+  }
+
+  /// assigns every element of an array or of a tuple, the elements of the
+  /// rhs are read before the first one is assigned
+  fn lower_assign_aggregate(
+    &mut self,
+    span: Span,
+    aggregate: Aggregate,
+    rhs: &Expr,
+  ) -> Result<Operand> {
+    let values = self
+      .lower_aggregate(rhs)?
+      .scalars()
+      .into_iter()
+      .map(|value| match value {
+        Operand::Copy(_) => {
+          let ty = self.operand_ty(&value);
+
+          self.temporary(ty, Rvalue::Use(value), span)
+        }
+        value => value,
+      })
+      .collect::<Vec<_>>();
+
+    for (local, value) in aggregate.scalars().into_iter().zip(values) {
+      let Operand::Copy(local) = local else {
+        continue;
+      };
+
+      let value = self.coerce(value, self.locals[local.0].ty, span);
+
+      self.assign(local, Rvalue::Use(value), span);
+    }
+
+    Ok(Operand::Const(Const::Void))
+  }
+
+  /// the nested functions of a block can be called from the whole block
+  fn lower_block(&mut self, block: &Block) -> Result<Operand> {
+    let mut value = Operand::Const(Const::Void);
+
+    for expr in &block.exprs {
+      if let ExprKind::Item(item) = &expr.kind {
+        if let hir::ItemKind::Fun(fun) = &item.kind {
+          declare_fun(self.hir, self.resolutions, self.signatures, fun)?;
+        }
+      }
+    }
+
+    for expr in &block.exprs {
+      value = self.lower_expr(expr)?;
+    }
+
+    Ok(value)
+  }
+
+  /// lowers a loop, `while` and `until` are a loop which starts by a branch
+  /// to its exit
+  fn lower_loop(&mut self, body: &Block) -> Result<Operand> {
+    let header = self.new_block();
+    let exit = self.new_block();
+
+    self.terminate(TerminatorKind::Goto(header), body.span);
+    self.switch_to(header);
+
+    self.loops.push(Loop {
+      header,
+      exit,
+      value: None,
+    });
+
+    let lowered = self.lower_block(body);
+    let lp = self.loops.pop().expect("a loop");
+
+    lowered?;
+
+    self.terminate(TerminatorKind::Goto(header), body.span);
+    self.switch_to(exit);
+
+    match lp.value {
+      Some(value) => Ok(Operand::Copy(value)),
+      None => Ok(Operand::Const(Const::Void)),
+    }
+  }
+
+  fn lower_break(
+    &mut self,
+    span: Span,
+    maybe_expr: &Option<Box<Expr>>,
+  ) -> Result<Operand> {
+    let value = self.lower_maybe(maybe_expr)?;
+    let ty = self.operand_ty(&value);
+
+    if self.loops.is_empty() {
+      return Err(unsupported(span, "`break` out of loops"));
+    }
+
+    if ty != Ty::Void {
+      let local = match self.loops.last().and_then(|lp| lp.value) {
+        Some(local) => local,
+        None => {
+          let local = self.new_local(ty, None, span);
+
+          self.loops.last_mut().expect("a loop").value = Some(local);
+
+          local
+        }
+      };
+
+      self.assign(local, Rvalue::Use(value), span);
+    }
+
+    let exit = self.loops.last().expect("a loop").exit;
+
+    self.terminate(TerminatorKind::Goto(exit), span);
+
+    Ok(Operand::Const(Const::Void))
+  }
+
+  /// lowers `if`, it has a value when both of its branches have a value of
+  /// the same type. a negated condition swaps the branches
+  fn lower_conditional(
+    &mut self,
+    span: Span,
+    condition: &Expr,
+    consequence: &Expr,
+    maybe_alternative: Option<&Expr>,
+  ) -> Result<Operand> {
+    let consequence_block = self.new_block();
+    let alternative_block = self.new_block();
+    let merge_block = self.new_block();
+
+    let (condition, targets) = match &condition.kind {
+      ExprKind::UnOp(op, rhs) if matches!(op.node, UnOpKind::Not) => (
+        self.lower_expr(rhs)?,
+        (alternative_block, consequence_block),
+      ),
+      _ => (
+        self.lower_expr(condition)?,
+        (consequence_block, alternative_block),
+      ),
+    };
+
+    self.terminate(
+      TerminatorKind::Branch(condition, targets.0, targets.1),
+      span,
+    );
+
+    self.switch_to(consequence_block);
+
+    let consequence = self.lower_expr(consequence)?;
+    let ty = self.operand_ty(&consequence);
+
+    let result = match (ty, maybe_alternative) {
+      (Ty::Void, _) | (_, None) => None,
+      (ty, Some(_)) => {
+        let result = self.new_local(ty, None, span);
+
+        self.assign(result, Rvalue::Use(consequence), span);
+
+        Some(result)
+      }
+    };
+
+    self.terminate(TerminatorKind::Goto(merge_block), span);
+    self.switch_to(alternative_block);
+
+    let alternative = match maybe_alternative {
+      Some(alternative) => self.lower_expr(alternative)?,
+      None => Operand::Const(Const::Void),
+    };
+
+    let result = match result {
+      Some(result) if self.operand_ty(&alternative) == ty => {
+        self.assign(result, Rvalue::Use(alternative), span);
+
+        Some(result)
+      }
+      _ => None,
+    };
+
+    self.terminate(TerminatorKind::Goto(merge_block), span);
+    self.switch_to(merge_block);
+
+    match result {
+      Some(result) => Ok(Operand::Copy(result)),
+      None => Ok(Operand::Const(Const::Void)),
+    }
+  }
+
+  fn lower_maybe(&mut self, maybe_expr: &Option<Box<Expr>>) -> Result<Operand> {
+    match maybe_expr {
+      Some(expr) => self.lower_expr(expr),
+      None => Ok(Operand::Const(Const::Void)),
+    }
+  }
+
+  /// gives a mir local to a binding
+  fn declare(&mut self, binding: LocalId, ty: Ty) -> Local {
+    let decl = self.hir.local(binding);
+    let local = self.new_local(ty, Some(decl.name.to_owned()), decl.span);
+
+    self.bindings.insert(self.resolutions.local(binding), local);
+
+    local
+  }
+
+  fn binding(&self, binding: LocalId) -> Local {
+    self.bindings[&self.resolutions.local(binding)]
+  }
+
+  fn aggregate(&self, binding: LocalId) -> Option<Aggregate> {
+    self
+      .aggregates
+      .get(&self.resolutions.local(binding))
+      .cloned()
+  }
+
+  fn new_local(&mut self, ty: Ty, name: Option<String>, span: Span) -> Local {
+    self.locals.push(LocalDecl { ty, name, span });
+
+    Local(self.locals.len() - 1)
+  }
+
+  /// assigns an rvalue to a new temporary
+  fn temporary(&mut self, ty: Ty, rvalue: Rvalue, span: Span) -> Operand {
+    let local = self.new_local(ty, None, span);
+
+    self.assign(local, rvalue, span);
+
+    Operand::Copy(local)
+  }
+
+  /// converts a value to the type it's given to, the typechecker only lets a
+  /// literal which fits in the type and a widening through
+  fn coerce(&mut self, operand: Operand, ty: Ty, span: Span) -> Operand {
+    if self.operand_ty(&operand) == ty {
+      return operand;
+    }
+
+    match operand {
+      Operand::Const(Const::Int(int, _)) if ty.is_integer() => {
+        Operand::Const(Const::Int(ty.wrap(int), ty))
+      }
+      Operand::Const(Const::Int(int, from)) if ty.is_float() => {
+        Operand::Const(Const::Real(ty.round(from.to_real(int)), ty))
+      }
+      Operand::Const(Const::Real(real, _)) if ty.is_float() => {
+        Operand::Const(Const::Real(ty.round(real), ty))
+      }
+      operand => self.temporary(ty, Rvalue::Cast(operand, ty), span),
+    }
+  }
+
+  fn operand_ty(&self, operand: &Operand) -> Ty {
+    match operand {
+      Operand::Copy(local) => self.locals[local.0].ty,
+      Operand::Const(constant) => constant.ty(),
+    }
+  }
+
+  fn assign(&mut self, local: Local, rvalue: Rvalue, span: Span) {
+    self.blocks[self.current.0].stmts.push(Stmt {
+      kind: StmtKind::Assign(local, rvalue),
+      span,
+    });
+  }
+
+  fn new_block(&mut self) -> BlockId {
+    self.blocks.push(BasicBlock {
+      stmts: vec![],
+      terminator: Terminator {
+        kind: TerminatorKind::Unreachable,
+        span: Span::ZERO,
+      },
+    });
+
+    BlockId(self.blocks.len() - 1)
+  }
+
+  fn switch_to(&mut self, block: BlockId) {
+    self.current = block;
+  }
+
+  /// terminates the current block, the code which follows a terminator is
+  /// lowered into a block without predecessors
+  fn terminate(&mut self, kind: TerminatorKind, span: Span) {
+    self.blocks[self.current.0].terminator = Terminator { kind, span };
+
+    let next = self.new_block();
+
+    self.switch_to(next);
+  }
+}
+
+fn lower_bin_op(op: &ast::BinOp) -> Result<BinOp> {
+  let op = match op.node {
+    BinOpKind::Add => BinOp::Add,
+    BinOpKind::Sub => BinOp::Sub,
+    BinOpKind::Mul => BinOp::Mul,
+    BinOpKind::Div => BinOp::Div,
+    BinOpKind::Rem => BinOp::Rem,
+    BinOpKind::Shl => BinOp::Shl,
+    BinOpKind::Shr => BinOp::Shr,
+    BinOpKind::BitAnd => BinOp::BitAnd,
+    BinOpKind::BitOr => BinOp::BitOr,
+    BinOpKind::BitXor => BinOp::BitXor,
+    BinOpKind::Lt => BinOp::Lt,
+    BinOpKind::Gt => BinOp::Gt,
+    BinOpKind::Le => BinOp::Le,
+    BinOpKind::Ge => BinOp::Ge,
+    BinOpKind::Eq => BinOp::Eq,
+    BinOpKind::Ne => BinOp::Ne,
+    BinOpKind::Range => return Err(unsupported(op.span, "ranges")),
+    BinOpKind::And | BinOpKind::Or | BinOpKind::Concat => unreachable!(),
+  };
+
+  Ok(op)
+}
@@ -0,0 +1,397 @@
+//! the mid-level ir, a control flow graph of basic blocks per function
+//!
+//! a local is a variable of a function, assigned by statements and read by
+//! operands. the control flow only leaves a basic block by its terminator,
+//! so every backend gets the same lowering of the branches and the loops
+
+use zhoo_analyzer::constant::Constant;
+use zhoo_span::span::Span;
+
+/// a local of a body, the inputs are the first locals
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Local(pub usize);
+
+/// a basic block of a body, the entry is the first block
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+impl BlockId {
+  pub const ENTRY: Self = Self(0);
+}
+
+/// the types of the values handled by the mir, `int` is a signed integer of
+/// 64 bits, `real` a float of 64 bits and `char` a unicode scalar value of 32
+/// bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ty {
+  Void,
+  Bool,
+  Int,
+  Real,
+  Str,
+  Char,
+  S8,
+  S16,
+  S32,
+  U8,
+  U16,
+  U32,
+  U64,
+  F32,
+}
+
+impl Ty {
+  pub fn is_integer(&self) -> bool {
+    self.is_signed() || self.is_unsigned()
+  }
+
+  pub fn is_signed(&self) -> bool {
+    matches!(self, Self::Int | Self::S8 | Self::S16 | Self::S32)
+  }
+
+  pub fn is_unsigned(&self) -> bool {
+    matches!(self, Self::U8 | Self::U16 | Self::U32 | Self::U64)
+  }
+
+  pub fn is_float(&self) -> bool {
+    matches!(self, Self::Real | Self::F32)
+  }
+
+  /// the size of a number in bits
+  pub fn bits(&self) -> u32 {
+    match self {
+      Self::S8 | Self::U8 => 8,
+      Self::S16 | Self::U16 => 16,
+      Self::S32 | Self::U32 | Self::F32 | Self::Char => 32,
+      _ => 64,
+    }
+  }
+
+  /// the nearest real of an integer of the type
+  pub fn to_real(&self, int: i64) -> f64 {
+    match self.is_unsigned() {
+      true => int as u64 as f64,
+      false => int as f64,
+    }
+  }
+
+  /// rounds a real to the precision of the type
+  pub fn round(&self, real: f64) -> f64 {
+    match self {
+      Self::F32 => real as f32 as f64,
+      _ => real,
+    }
+  }
+
+  /// truncates an integer to the bits of the type, the bits above are the
+  /// sign of a signed integer and zeros for an unsigned integer
+  pub fn wrap(&self, int: i64) -> i64 {
+    let shift = 64 - self.bits();
+
+    match self.is_unsigned() {
+      true => ((int as u64) << shift >> shift) as i64,
+      false => int << shift >> shift,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct Program {
+  pub externs: Vec<Extern>,
+  pub bodies: Vec<Body>,
+  /// the top-level `val`s in the order of their evaluation
+  pub vals: Vec<(String, Constant)>,
+}
+
+impl Program {
+  pub fn body(&self, name: &str) -> Option<&Body> {
+    self.bodies.iter().find(|body| body.name == name)
+  }
+}
+
+/// a function declared by `ext`, defined outside of the program
+#[derive(Debug)]
+pub struct Extern {
+  pub name: String,
+  pub inputs: Vec<Ty>,
+  pub output: Ty,
+}
+
+#[derive(Clone, Debug)]
+pub struct Body {
+  pub name: String,
+  /// the number of inputs, the inputs are the locals `0..inputs`
+  pub inputs: usize,
+  pub output: Ty,
+  pub locals: Vec<LocalDecl>,
+  pub blocks: Vec<BasicBlock>,
+  /// a nested function is only called from the object which defines it
+  pub is_local: bool,
+  pub span: Span,
+}
+
+impl Body {
+  pub fn local_ty(&self, local: Local) -> Ty {
+    self.locals[local.0].ty
+  }
+
+  pub fn operand_ty(&self, operand: &Operand) -> Ty {
+    match operand {
+      Operand::Copy(local) => self.local_ty(*local),
+      Operand::Const(constant) => constant.ty(),
+    }
+  }
+
+  pub fn block(&self, block: BlockId) -> &BasicBlock {
+    &self.blocks[block.0]
+  }
+
+  pub fn block_mut(&mut self, block: BlockId) -> &mut BasicBlock {
+    &mut self.blocks[block.0]
+  }
+
+  pub fn block_ids(&self) -> impl Iterator<Item = BlockId> {
+    (0..self.blocks.len()).map(BlockId)
+  }
+
+  /// the number of statements, used to weigh a body
+  pub fn size(&self) -> usize {
+    self.blocks.iter().map(|block| block.stmts.len() + 1).sum()
+  }
+
+  /// the number of assignments of every local, an input is assigned once by
+  /// the call
+  pub fn assignments(&self) -> Vec<usize> {
+    let mut assignments = vec![0; self.locals.len()];
+
+    for count in assignments.iter_mut().take(self.inputs) {
+      *count += 1;
+    }
+
+    for block in &self.blocks {
+      for stmt in &block.stmts {
+        let StmtKind::Assign(local, _) = &stmt.kind;
+
+        assignments[local.0] += 1;
+      }
+    }
+
+    assignments
+  }
+
+  /// visits the operands read by the statements and by the terminators
+  pub fn visit_operands_mut(&mut self, mut visit: impl FnMut(&mut Operand)) {
+    for block in &mut self.blocks {
+      for stmt in &mut block.stmts {
+        let StmtKind::Assign(_, rvalue) = &mut stmt.kind;
+
+        for operand in rvalue.operands_mut() {
+          visit(operand);
+        }
+      }
+
+      if let Some(operand) = block.terminator.kind.operand_mut() {
+        visit(operand);
+      }
+    }
+  }
+
+  /// the predecessors of every block
+  pub fn predecessors(&self) -> Vec<Vec<BlockId>> {
+    let mut predecessors = vec![vec![]; self.blocks.len()];
+
+    for block in self.block_ids() {
+      for successor in self.block(block).successors() {
+        predecessors[successor.0].push(block);
+      }
+    }
+
+    predecessors
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct LocalDecl {
+  pub ty: Ty,
+  /// the name of a variable, a temporary has no name
+  pub name: Option<String>,
+  pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct BasicBlock {
+  pub stmts: Vec<Stmt>,
+  pub terminator: Terminator,
+}
+
+impl BasicBlock {
+  pub fn successors(&self) -> Vec<BlockId> {
+    match &self.terminator.kind {
+      TerminatorKind::Goto(target) => vec![*target],
+      TerminatorKind::Branch(_, consequence, alternative) => {
+        vec![*consequence, *alternative]
+      }
+      TerminatorKind::Return(_) | TerminatorKind::Unreachable => vec![],
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Stmt {
+  pub kind: StmtKind,
+  pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum StmtKind {
+  Assign(Local, Rvalue),
+}
+
+#[derive(Clone, Debug)]
+pub struct Terminator {
+  pub kind: TerminatorKind,
+  pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum TerminatorKind {
+  Goto(BlockId),
+  /// goes to the first block when the condition is `true`
+  Branch(Operand, BlockId, BlockId),
+  Return(Operand),
+  /// the end of a block which is never reached
+  Unreachable,
+}
+
+impl TerminatorKind {
+  pub fn operand_mut(&mut self) -> Option<&mut Operand> {
+    match self {
+      Self::Branch(operand, ..) | Self::Return(operand) => Some(operand),
+      Self::Goto(_) | Self::Unreachable => None,
+    }
+  }
+
+  /// visits the blocks reached by the terminator
+  pub fn targets_mut(&mut self) -> Vec<&mut BlockId> {
+    match self {
+      Self::Goto(target) => vec![target],
+      Self::Branch(_, consequence, alternative) => {
+        vec![consequence, alternative]
+      }
+      Self::Return(_) | Self::Unreachable => vec![],
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum Rvalue {
+  Use(Operand),
+  UnOp(UnOp, Operand),
+  BinOp(BinOp, Operand, Operand),
+  /// a call of a function of the program, an extern or a builtin
+  Call(String, Vec<Operand>),
+  /// the load of a top-level `val`
+  Val(String),
+  /// the conversion of a value by `as` or of a number to a wider type
+  Cast(Operand, Ty),
+}
+
+impl Rvalue {
+  /// a call has side effects, the other rvalues can be removed when their
+  /// value is not used
+  pub fn is_pure(&self) -> bool {
+    !matches!(self, Self::Call(..))
+  }
+
+  pub fn operands(&self) -> Vec<&Operand> {
+    match self {
+      Self::Use(operand) | Self::UnOp(_, operand) | Self::Cast(operand, _) => {
+        vec![operand]
+      }
+      Self::BinOp(_, lhs, rhs) => vec![lhs, rhs],
+      Self::Call(_, inputs) => inputs.iter().collect(),
+      Self::Val(_) => vec![],
+    }
+  }
+
+  pub fn operands_mut(&mut self) -> Vec<&mut Operand> {
+    match self {
+      Self::Use(operand) | Self::UnOp(_, operand) | Self::Cast(operand, _) => {
+        vec![operand]
+      }
+      Self::BinOp(_, lhs, rhs) => vec![lhs, rhs],
+      Self::Call(_, inputs) => inputs.iter_mut().collect(),
+      Self::Val(_) => vec![],
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+  Copy(Local),
+  Const(Const),
+}
+
+/// a number is given with its type, an integer is wrapped to its type
+#[derive(Clone, Debug, PartialEq)]
+pub enum Const {
+  Void,
+  Bool(bool),
+  Int(i64, Ty),
+  Real(f64, Ty),
+  Char(char),
+  Str(String),
+}
+
+impl Const {
+  pub fn ty(&self) -> Ty {
+    match self {
+      Self::Void => Ty::Void,
+      Self::Bool(_) => Ty::Bool,
+      Self::Int(_, ty) | Self::Real(_, ty) => *ty,
+      Self::Char(_) => Ty::Char,
+      Self::Str(_) => Ty::Str,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnOp {
+  Neg,
+  Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Rem,
+  Shl,
+  Shr,
+  BitAnd,
+  BitOr,
+  BitXor,
+  Lt,
+  Gt,
+  Le,
+  Ge,
+  Eq,
+  Ne,
+}
+
+impl BinOp {
+  /// the operations defined on the `real`s
+  pub fn is_arithmetic(&self) -> bool {
+    matches!(self, Self::Add | Self::Sub | Self::Mul | Self::Div)
+      || self.is_comparison()
+  }
+
+  pub fn is_comparison(&self) -> bool {
+    matches!(
+      self,
+      Self::Lt | Self::Gt | Self::Le | Self::Ge | Self::Eq | Self::Ne
+    )
+  }
+}
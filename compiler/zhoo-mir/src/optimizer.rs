@@ -0,0 +1,25 @@
+//! the optimizations of the mir, run before the code generation in release
+//! mode
+
+use super::mir::Program;
+use super::transform::{constfold, copyprop, dce, inliner, simplify};
+
+/// inlines the small functions then simplifies every body until nothing
+/// changes
+pub fn optimize(program: &mut Program) {
+  inliner::inline(program);
+
+  for body in &mut program.bodies {
+    loop {
+      let mut changed = constfold::fold(body);
+
+      changed |= copyprop::propagate(body);
+      changed |= simplify::simplify(body);
+      changed |= dce::eliminate(body);
+
+      if !changed {
+        break;
+      }
+    }
+  }
+}
@@ -0,0 +1,203 @@
+use super::mir::{
+  BasicBlock, BinOp, BlockId, Body, Const, Extern, Local, Operand, Program,
+  Rvalue, Stmt, StmtKind, Terminator, TerminatorKind, Ty, UnOp,
+};
+
+use std::fmt;
+
+struct Sep<'a, T: 'a>(&'a [T], &'a str);
+
+impl<'a, T: fmt::Display> fmt::Display for Sep<'a, T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (x, node) in self.0.iter().enumerate() {
+      if x > 0 {
+        write!(f, "{}", self.1)?;
+      }
+
+      write!(f, "{node}")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for Local {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "_{}", self.0)
+  }
+}
+
+impl fmt::Display for BlockId {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "bb{}", self.0)
+  }
+}
+
+impl fmt::Display for Ty {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Void => write!(f, "void"),
+      Self::Bool => write!(f, "bool"),
+      Self::Int => write!(f, "int"),
+      Self::Real => write!(f, "real"),
+      Self::Str => write!(f, "str"),
+      Self::Char => write!(f, "char"),
+      Self::S8 => write!(f, "s8"),
+      Self::S16 => write!(f, "s16"),
+      Self::S32 => write!(f, "s32"),
+      Self::U8 => write!(f, "u8"),
+      Self::U16 => write!(f, "u16"),
+      Self::U32 => write!(f, "u32"),
+      Self::U64 => write!(f, "u64"),
+      Self::F32 => write!(f, "f32"),
+    }
+  }
+}
+
+impl fmt::Display for Program {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (name, constant) in &self.vals {
+      writeln!(f, "val {name} = {constant:?};")?;
+    }
+
+    for ext in &self.externs {
+      writeln!(f, "{ext}")?;
+    }
+
+    for body in &self.bodies {
+      writeln!(f, "\n{body}")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for Extern {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "ext {}({}): {};",
+      self.name,
+      Sep(&self.inputs, ", "),
+      self.output
+    )
+  }
+}
+
+impl fmt::Display for Body {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let inputs = (0..self.inputs)
+      .map(|x| format!("{}: {}", Local(x), self.locals[x].ty))
+      .collect::<Vec<_>>();
+
+    writeln!(
+      f,
+      "fun {}({}): {} {{",
+      self.name,
+      Sep(&inputs, ", "),
+      self.output
+    )?;
+
+    for (x, local) in self.locals.iter().enumerate().skip(self.inputs) {
+      write!(f, "  let {}: {};", Local(x), local.ty)?;
+
+      match &local.name {
+        Some(name) => writeln!(f, " // {name}")?,
+        None => writeln!(f)?,
+      }
+    }
+
+    for (x, block) in self.blocks.iter().enumerate() {
+      write!(f, "\n  {}: {{\n{block}  }}\n", BlockId(x))?;
+    }
+
+    write!(f, "}}")
+  }
+}
+
+impl fmt::Display for BasicBlock {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for stmt in &self.stmts {
+      writeln!(f, "    {stmt};")?;
+    }
+
+    writeln!(f, "    {};", self.terminator)
+  }
+}
+
+impl fmt::Display for Stmt {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match &self.kind {
+      StmtKind::Assign(local, rvalue) => write!(f, "{local} = {rvalue}"),
+    }
+  }
+}
+
+impl fmt::Display for Terminator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match &self.kind {
+      TerminatorKind::Goto(target) => write!(f, "goto {target}"),
+      TerminatorKind::Branch(condition, consequence, alternative) => write!(
+        f,
+        "branch {condition} -> [true: {consequence}, false: {alternative}]"
+      ),
+      TerminatorKind::Return(value) => write!(f, "return {value}"),
+      TerminatorKind::Unreachable => write!(f, "unreachable"),
+    }
+  }
+}
+
+impl fmt::Display for Rvalue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Use(operand) => write!(f, "{operand}"),
+      Self::UnOp(op, operand) => write!(f, "{op}({operand})"),
+      Self::BinOp(op, lhs, rhs) => write!(f, "{op}({lhs}, {rhs})"),
+      Self::Call(name, inputs) => write!(f, "{name}({})", Sep(inputs, ", ")),
+      Self::Val(name) => write!(f, "val {name}"),
+      Self::Cast(operand, ty) => write!(f, "{operand} as {ty}"),
+    }
+  }
+}
+
+impl fmt::Display for Operand {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Copy(local) => write!(f, "{local}"),
+      Self::Const(constant) => write!(f, "const {constant}"),
+    }
+  }
+}
+
+impl fmt::Display for Const {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Void => write!(f, "()"),
+      Self::Bool(boolean) => write!(f, "{boolean}"),
+      Self::Int(int, Ty::Int) => write!(f, "{int}"),
+      Self::Int(int, ty) if ty.is_unsigned() => {
+        write!(f, "{}{ty}", *int as u64)
+      }
+      Self::Int(int, ty) => write!(f, "{int}{ty}"),
+      Self::Real(real, Ty::Real) => write!(f, "{real:?}"),
+      Self::Real(real, ty) => write!(f, "{real:?}{ty}"),
+      Self::Char(char) => write!(f, "{char:?}"),
+      Self::Str(string) => write!(f, "{string:?}"),
+    }
+  }
+}
+
+impl fmt::Display for UnOp {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Neg => write!(f, "Neg"),
+      Self::Not => write!(f, "Not"),
+    }
+  }
+}
+
+impl fmt::Display for BinOp {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{self:?}")
+  }
+}
@@ -0,0 +1,392 @@
+use super::lower::lower;
+use super::mir::{
+  BasicBlock, BinOp, BlockId, Body, Const, Local, LocalDecl, Operand, Program,
+  Rvalue, Stmt, StmtKind, Terminator, TerminatorKind, Ty,
+};
+use super::optimizer::optimize;
+use super::transform::constfold::{fold, fold_bin_op, fold_cast, fold_un_op};
+use super::transform::dce::eliminate;
+use super::transform::simplify::{remove_unreachable_blocks, simplify};
+
+use zhoo_analyzer::analyzer;
+use zhoo_analyzer::constant::Constant;
+use zhoo_parser::parser::parse_source;
+use zhoo_span::span::Span;
+
+const WHILE: &str = "
+fun main(): int {
+  mut x: int = 0;
+  while x < 3 -> x += 1;
+  x
+}
+";
+
+const UNTIL: &str = "
+fun main(): int {
+  mut i := 0;
+  mut n := 0;
+
+  until i == 3 {
+    i += 1;
+    n += 1;
+  }
+
+  until n >= 0 -> n -= 100;
+
+  n
+}
+";
+
+const IF_ELSE: &str = "
+fun max(a: int, b: int): int {
+  if a > b { a } else { b }
+}
+
+fun main(): int {
+  max(7, 2) - max(2, 3)
+}
+";
+
+const AGGREGATES: &str = "
+fun main(): int {
+  mut pair := (1, [2, 3]);
+  pair = ((pair.1)[1], [(pair.1)[0], pair.0]);
+  pair.0 * 100 + (pair.1)[0] * 10 + (pair.1)[1]
+}
+";
+
+/// parses then lowers a source code
+fn lower_source(name: &str, source: &str) -> Program {
+  let program = parse_source(name, source);
+  let resolutions = analyzer::resolve(&program).unwrap();
+
+  match lower(&program, &resolutions) {
+    Ok(program) => program,
+    Err(_) => panic!("the sample `{name}` should be lowered"),
+  }
+}
+
+/// runs a body of a program, the operations are folded by the rules of the
+/// constant folding
+fn run(program: &Program, name: &str, args: Vec<Const>) -> Const {
+  let body = program.body(name).unwrap();
+  let mut locals = vec![Const::Void; body.locals.len()];
+  let mut block = BlockId::ENTRY;
+
+  for (local, arg) in locals.iter_mut().zip(args) {
+    *local = arg;
+  }
+
+  loop {
+    let basic_block = body.block(block);
+
+    for stmt in &basic_block.stmts {
+      let StmtKind::Assign(local, rvalue) = &stmt.kind;
+      let ty = body.local_ty(*local);
+
+      locals[local.0] = run_rvalue(program, &locals, rvalue, ty);
+    }
+
+    block = match &basic_block.terminator.kind {
+      TerminatorKind::Goto(target) => *target,
+      TerminatorKind::Branch(condition, consequence, alternative) => {
+        match run_operand(&locals, condition) {
+          Const::Bool(true) => *consequence,
+          Const::Bool(false) => *alternative,
+          condition => panic!("the condition `{condition:?}` is not a bool"),
+        }
+      }
+      TerminatorKind::Return(operand) => return run_operand(&locals, operand),
+      TerminatorKind::Unreachable => panic!("an unreachable block is reached"),
+    };
+  }
+}
+
+fn run_rvalue(
+  program: &Program,
+  locals: &[Const],
+  rvalue: &Rvalue,
+  ty: Ty,
+) -> Const {
+  match rvalue {
+    Rvalue::Use(operand) => run_operand(locals, operand),
+    Rvalue::UnOp(op, operand) => {
+      fold_un_op(*op, &run_operand(locals, operand)).unwrap()
+    }
+    Rvalue::BinOp(op, lhs, rhs) => {
+      fold_bin_op(*op, &run_operand(locals, lhs), &run_operand(locals, rhs))
+        .unwrap()
+    }
+    Rvalue::Cast(operand, ty) => {
+      fold_cast(&run_operand(locals, operand), *ty).unwrap()
+    }
+    Rvalue::Call(callee, args) => {
+      let args = args.iter().map(|arg| run_operand(locals, arg)).collect();
+
+      run(program, callee, args)
+    }
+    Rvalue::Val(name) => {
+      match program.vals.iter().find(|(val, _)| val == name) {
+        Some((_, Constant::Bool(boolean))) => Const::Bool(*boolean),
+        Some((_, Constant::Int(int, _))) => Const::Int(ty.wrap(*int), ty),
+        Some((_, Constant::Real(real, _))) => Const::Real(*real, ty),
+        Some((_, Constant::Char(ch))) => Const::Char(*ch),
+        Some((_, Constant::Str(string))) => Const::Str(string.clone()),
+        None => panic!("the val `{name}` is not defined"),
+      }
+    }
+  }
+}
+
+fn run_operand(locals: &[Const], operand: &Operand) -> Const {
+  match operand {
+    Operand::Copy(local) => locals[local.0].clone(),
+    Operand::Const(constant) => constant.clone(),
+  }
+}
+
+fn int(int: i64) -> Operand {
+  Operand::Const(Const::Int(int, Ty::Int))
+}
+
+fn assign(local: usize, rvalue: Rvalue) -> Stmt {
+  Stmt {
+    kind: StmtKind::Assign(Local(local), rvalue),
+    span: Span::ZERO,
+  }
+}
+
+fn block(stmts: Vec<Stmt>, kind: TerminatorKind) -> BasicBlock {
+  BasicBlock {
+    stmts,
+    terminator: Terminator {
+      kind,
+      span: Span::ZERO,
+    },
+  }
+}
+
+/// a body without inputs named `main`
+fn body(locals: Vec<Ty>, blocks: Vec<BasicBlock>) -> Body {
+  Body {
+    name: String::from("main"),
+    inputs: 0,
+    output: Ty::Int,
+    locals: locals
+      .into_iter()
+      .map(|ty| LocalDecl {
+        ty,
+        name: None,
+        span: Span::ZERO,
+      })
+      .collect(),
+    blocks,
+    is_local: false,
+    span: Span::ZERO,
+  }
+}
+
+fn program(bodies: Vec<Body>) -> Program {
+  Program {
+    externs: vec![],
+    bodies,
+    vals: vec![],
+  }
+}
+
+#[test]
+fn test_lower_while() {
+  let program = lower_source("while", WHILE);
+
+  assert_eq!(run(&program, "main", vec![]), Const::Int(3, Ty::Int));
+}
+
+#[test]
+fn test_lower_until() {
+  let program = lower_source("until", UNTIL);
+
+  assert_eq!(run(&program, "main", vec![]), Const::Int(3, Ty::Int));
+}
+
+#[test]
+fn test_lower_if_else() {
+  let program = lower_source("if-else", IF_ELSE);
+
+  assert_eq!(run(&program, "main", vec![]), Const::Int(4, Ty::Int));
+}
+
+#[test]
+fn test_lower_aggregates() {
+  let program = lower_source("aggregates", AGGREGATES);
+
+  assert_eq!(run(&program, "main", vec![]), Const::Int(321, Ty::Int));
+}
+
+#[test]
+fn test_optimize_preserves_results() {
+  let samples = [
+    ("optimize-while", WHILE, 3),
+    ("optimize-until", UNTIL, 3),
+    ("optimize-if-else", IF_ELSE, 4),
+    ("optimize-aggregates", AGGREGATES, 321),
+  ];
+
+  for (name, source, expected) in samples {
+    let mut program = lower_source(name, source);
+
+    optimize(&mut program);
+
+    assert_eq!(
+      run(&program, "main", vec![]),
+      Const::Int(expected, Ty::Int),
+      "the sample `{name}` changed once optimized",
+    );
+  }
+}
+
+#[test]
+fn test_fold_bin_op() {
+  let mut body = body(
+    vec![Ty::Int],
+    vec![block(
+      vec![assign(0, Rvalue::BinOp(BinOp::Add, int(2), int(3)))],
+      TerminatorKind::Return(Operand::Copy(Local(0))),
+    )],
+  );
+
+  assert!(fold(&mut body));
+
+  let StmtKind::Assign(_, rvalue) = &body.blocks[0].stmts[0].kind;
+
+  assert!(matches!(
+    rvalue,
+    Rvalue::Use(Operand::Const(Const::Int(5, Ty::Int)))
+  ));
+}
+
+#[test]
+fn test_fold_wraps_sized_integers() {
+  let lhs = Operand::Const(Const::Int(250, Ty::U8));
+  let rhs = Operand::Const(Const::Int(10, Ty::U8));
+  let mut body = body(
+    vec![Ty::U8],
+    vec![block(
+      vec![assign(0, Rvalue::BinOp(BinOp::Add, lhs, rhs))],
+      TerminatorKind::Return(Operand::Copy(Local(0))),
+    )],
+  );
+
+  assert!(fold(&mut body));
+
+  let StmtKind::Assign(_, rvalue) = &body.blocks[0].stmts[0].kind;
+
+  assert!(matches!(
+    rvalue,
+    Rvalue::Use(Operand::Const(Const::Int(4, Ty::U8)))
+  ));
+
+  assert_eq!(
+    fold_cast(&Const::Int(-1, Ty::S8), Ty::U16),
+    Some(Const::Int(65535, Ty::U16))
+  );
+}
+
+#[test]
+fn test_fold_int_to_char() {
+  assert_eq!(
+    fold_cast(&Const::Int(0x1f916, Ty::Int), Ty::Char),
+    Some(Const::Char('🤖'))
+  );
+
+  for int in [-1, 0xd800, 0x110000] {
+    assert_eq!(
+      fold_cast(&Const::Int(int, Ty::Int), Ty::Char),
+      Some(Const::Char(char::REPLACEMENT_CHARACTER))
+    );
+  }
+}
+
+#[test]
+fn test_eliminate_dead_assignments() {
+  let callee =
+    body(vec![], vec![block(vec![], TerminatorKind::Return(int(1)))]);
+
+  let mut body = body(
+    vec![Ty::Int, Ty::Int],
+    vec![block(
+      vec![
+        assign(0, Rvalue::BinOp(BinOp::Mul, int(6), int(7))),
+        assign(1, Rvalue::Call(String::from("callee"), vec![])),
+      ],
+      TerminatorKind::Return(int(0)),
+    )],
+  );
+
+  assert!(eliminate(&mut body));
+  assert_eq!(body.blocks[0].stmts.len(), 1);
+  assert_eq!(body.locals.len(), 1);
+
+  let StmtKind::Assign(local, rvalue) = &body.blocks[0].stmts[0].kind;
+
+  assert_eq!(*local, Local(0));
+  assert!(matches!(rvalue, Rvalue::Call(..)));
+
+  let program = program(vec![
+    body,
+    Body {
+      name: String::from("callee"),
+      ..callee
+    },
+  ]);
+
+  assert_eq!(run(&program, "main", vec![]), Const::Int(0, Ty::Int));
+}
+
+#[test]
+fn test_remove_unreachable_blocks() {
+  let mut body = body(
+    vec![],
+    vec![
+      block(vec![], TerminatorKind::Goto(BlockId(2))),
+      block(vec![], TerminatorKind::Unreachable),
+      block(vec![], TerminatorKind::Return(int(1))),
+    ],
+  );
+
+  assert!(remove_unreachable_blocks(&mut body));
+  assert_eq!(body.blocks.len(), 2);
+  assert!(matches!(
+    body.blocks[0].terminator.kind,
+    TerminatorKind::Goto(BlockId(1))
+  ));
+
+  let program = program(vec![body]);
+
+  assert_eq!(run(&program, "main", vec![]), Const::Int(1, Ty::Int));
+}
+
+#[test]
+fn test_simplify_constant_branch() {
+  let mut body = body(
+    vec![],
+    vec![
+      block(
+        vec![],
+        TerminatorKind::Branch(
+          Operand::Const(Const::Bool(false)),
+          BlockId(1),
+          BlockId(2),
+        ),
+      ),
+      block(vec![], TerminatorKind::Return(int(1))),
+      block(vec![], TerminatorKind::Return(int(2))),
+    ],
+  );
+
+  assert!(simplify(&mut body));
+  assert_eq!(body.blocks.len(), 1);
+  assert!(matches!(
+    body.blocks[0].terminator.kind,
+    TerminatorKind::Return(Operand::Const(Const::Int(2, Ty::Int)))
+  ));
+}
@@ -0,0 +1,5 @@
+pub(crate) mod constfold;
+pub(crate) mod copyprop;
+pub(crate) mod dce;
+pub(crate) mod inliner;
+pub(crate) mod simplify;
@@ -0,0 +1,206 @@
+//! the constant folding, the locals assigned once by a constant are replaced
+//! by it and the operations on constants are evaluated
+//!
+//! the integers wrap to their type like at runtime, a division by zero is
+//! left to the runtime
+
+use crate::mir::{BinOp, Body, Const, Operand, Rvalue, StmtKind, Ty, UnOp};
+
+use zhoo_analyzer::ops::int_to_char;
+
+pub(crate) fn fold(body: &mut Body) -> bool {
+  let mut changed = substitute(body);
+
+  for block in &mut body.blocks {
+    for stmt in &mut block.stmts {
+      let StmtKind::Assign(_, rvalue) = &mut stmt.kind;
+
+      let constant = match rvalue {
+        Rvalue::UnOp(op, Operand::Const(operand)) => fold_un_op(*op, operand),
+        Rvalue::BinOp(op, Operand::Const(lhs), Operand::Const(rhs)) => {
+          fold_bin_op(*op, lhs, rhs)
+        }
+        Rvalue::Cast(Operand::Const(operand), ty) => fold_cast(operand, *ty),
+        _ => None,
+      };
+
+      if let Some(constant) = constant {
+        *rvalue = Rvalue::Use(Operand::Const(constant));
+        changed = true;
+      }
+    }
+  }
+
+  changed
+}
+
+/// replaces the reads of the locals assigned once by a constant
+fn substitute(body: &mut Body) -> bool {
+  let assignments = body.assignments();
+  let mut constants = vec![None; body.locals.len()];
+
+  for block in &body.blocks {
+    for stmt in &block.stmts {
+      let StmtKind::Assign(local, rvalue) = &stmt.kind;
+
+      if let Rvalue::Use(Operand::Const(constant)) = rvalue {
+        if local.0 >= body.inputs && assignments[local.0] == 1 {
+          constants[local.0] = Some(constant.to_owned());
+        }
+      }
+    }
+  }
+
+  let mut changed = false;
+
+  body.visit_operands_mut(|operand| {
+    if let Operand::Copy(local) = operand {
+      if let Some(constant) = &constants[local.0] {
+        *operand = Operand::Const(constant.to_owned());
+        changed = true;
+      }
+    }
+  });
+
+  changed
+}
+
+pub(crate) fn fold_un_op(op: UnOp, operand: &Const) -> Option<Const> {
+  match (op, operand) {
+    (UnOp::Neg, Const::Int(int, ty)) => {
+      Some(Const::Int(ty.wrap(int.wrapping_neg()), *ty))
+    }
+    (UnOp::Neg, Const::Real(real, ty)) => Some(Const::Real(-real, *ty)),
+    (UnOp::Not, Const::Bool(boolean)) => Some(Const::Bool(!boolean)),
+    (UnOp::Not, Const::Int(int, ty)) => Some(Const::Int(ty.wrap(!int), *ty)),
+    _ => None,
+  }
+}
+
+pub(crate) fn fold_bin_op(op: BinOp, lhs: &Const, rhs: &Const) -> Option<Const> {
+  match (lhs, rhs) {
+    (Const::Int(lhs, ty), Const::Int(rhs, _)) => fold_int(op, *lhs, *rhs, *ty),
+    (Const::Real(lhs, ty), Const::Real(rhs, _)) => {
+      fold_real(op, *lhs, *rhs, *ty)
+    }
+    (Const::Bool(lhs), Const::Bool(rhs)) => match op {
+      BinOp::Eq => Some(Const::Bool(lhs == rhs)),
+      BinOp::Ne => Some(Const::Bool(lhs != rhs)),
+      BinOp::BitAnd => Some(Const::Bool(lhs & rhs)),
+      BinOp::BitOr => Some(Const::Bool(lhs | rhs)),
+      BinOp::BitXor => Some(Const::Bool(lhs ^ rhs)),
+      _ => None,
+    },
+    // the `char`s are compared by their scalar values
+    (Const::Char(lhs), Const::Char(rhs)) if op.is_comparison() => {
+      fold_uint(op, *lhs as u64, *rhs as u64, Ty::U32)
+    }
+    _ => None,
+  }
+}
+
+/// the unsigned integers are divided, shifted and compared as unsigned, the
+/// shifts take their amount modulo the bits of the type
+fn fold_int(op: BinOp, lhs: i64, rhs: i64, ty: Ty) -> Option<Const> {
+  if ty.is_unsigned() {
+    return fold_uint(op, lhs as u64, rhs as u64, ty);
+  }
+
+  let int = match op {
+    BinOp::Add => lhs.wrapping_add(rhs),
+    BinOp::Sub => lhs.wrapping_sub(rhs),
+    BinOp::Mul => lhs.wrapping_mul(rhs),
+    BinOp::Div => lhs.checked_div(rhs)?,
+    BinOp::Rem => lhs.checked_rem(rhs)?,
+    BinOp::Shl => lhs.wrapping_shl(rhs as u32 % ty.bits()),
+    BinOp::Shr => lhs.wrapping_shr(rhs as u32 % ty.bits()),
+    BinOp::BitAnd => lhs & rhs,
+    BinOp::BitOr => lhs | rhs,
+    BinOp::BitXor => lhs ^ rhs,
+    BinOp::Lt => return Some(Const::Bool(lhs < rhs)),
+    BinOp::Gt => return Some(Const::Bool(lhs > rhs)),
+    BinOp::Le => return Some(Const::Bool(lhs <= rhs)),
+    BinOp::Ge => return Some(Const::Bool(lhs >= rhs)),
+    BinOp::Eq => return Some(Const::Bool(lhs == rhs)),
+    BinOp::Ne => return Some(Const::Bool(lhs != rhs)),
+  };
+
+  Some(Const::Int(ty.wrap(int), ty))
+}
+
+fn fold_uint(op: BinOp, lhs: u64, rhs: u64, ty: Ty) -> Option<Const> {
+  let int = match op {
+    BinOp::Add => lhs.wrapping_add(rhs),
+    BinOp::Sub => lhs.wrapping_sub(rhs),
+    BinOp::Mul => lhs.wrapping_mul(rhs),
+    BinOp::Div => lhs.checked_div(rhs)?,
+    BinOp::Rem => lhs.checked_rem(rhs)?,
+    BinOp::Shl => lhs.wrapping_shl(rhs as u32 % ty.bits()),
+    BinOp::Shr => lhs.wrapping_shr(rhs as u32 % ty.bits()),
+    BinOp::BitAnd => lhs & rhs,
+    BinOp::BitOr => lhs | rhs,
+    BinOp::BitXor => lhs ^ rhs,
+    BinOp::Lt => return Some(Const::Bool(lhs < rhs)),
+    BinOp::Gt => return Some(Const::Bool(lhs > rhs)),
+    BinOp::Le => return Some(Const::Bool(lhs <= rhs)),
+    BinOp::Ge => return Some(Const::Bool(lhs >= rhs)),
+    BinOp::Eq => return Some(Const::Bool(lhs == rhs)),
+    BinOp::Ne => return Some(Const::Bool(lhs != rhs)),
+  };
+
+  Some(Const::Int(ty.wrap(int as i64), ty))
+}
+
+/// an `f32` is rounded to its precision
+fn fold_real(op: BinOp, lhs: f64, rhs: f64, ty: Ty) -> Option<Const> {
+  let real = match op {
+    BinOp::Add => lhs + rhs,
+    BinOp::Sub => lhs - rhs,
+    BinOp::Mul => lhs * rhs,
+    BinOp::Div => lhs / rhs,
+    BinOp::Rem => lhs % rhs,
+    BinOp::Lt => return Some(Const::Bool(lhs < rhs)),
+    BinOp::Gt => return Some(Const::Bool(lhs > rhs)),
+    BinOp::Le => return Some(Const::Bool(lhs <= rhs)),
+    BinOp::Ge => return Some(Const::Bool(lhs >= rhs)),
+    BinOp::Eq => return Some(Const::Bool(lhs == rhs)),
+    BinOp::Ne => return Some(Const::Bool(lhs != rhs)),
+    _ => return None,
+  };
+
+  Some(Const::Real(ty.round(real), ty))
+}
+
+/// the conversions of `as`, an integer is wrapped to its new type, a real is
+/// truncated toward zero, saturated to 64 bits and wrapped, and a `char` is
+/// its scalar value
+pub(crate) fn fold_cast(operand: &Const, ty: Ty) -> Option<Const> {
+  match operand {
+    Const::Int(int, _) if ty.is_integer() => {
+      Some(Const::Int(ty.wrap(*int), ty))
+    }
+    Const::Int(int, from) if ty.is_float() => {
+      Some(Const::Real(ty.round(from.to_real(*int)), ty))
+    }
+    Const::Int(int, _) if ty == Ty::Bool => Some(Const::Bool(*int != 0)),
+    Const::Real(real, _) if ty.is_float() => {
+      Some(Const::Real(ty.round(*real), ty))
+    }
+    Const::Real(real, _) if ty.is_unsigned() => {
+      Some(Const::Int(ty.wrap(*real as u64 as i64), ty))
+    }
+    Const::Real(real, _) if ty.is_signed() => {
+      Some(Const::Int(ty.wrap(*real as i64), ty))
+    }
+    Const::Bool(boolean) if ty.is_integer() => {
+      Some(Const::Int(*boolean as i64, ty))
+    }
+    Const::Char(char) if ty.is_integer() => {
+      Some(Const::Int(ty.wrap(*char as i64), ty))
+    }
+    Const::Int(int, _) if ty == Ty::Char => {
+      Some(Const::Char(int_to_char(*int)))
+    }
+    _ => None,
+  }
+}
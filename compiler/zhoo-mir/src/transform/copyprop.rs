@@ -0,0 +1,52 @@
+//! the copy propagation, a local assigned once by the copy of another local
+//! assigned at most once is replaced by this other local
+
+use crate::mir::{Body, Local, Operand, Rvalue, StmtKind};
+
+pub(crate) fn propagate(body: &mut Body) -> bool {
+  let assignments = body.assignments();
+  let mut copies = vec![None; body.locals.len()];
+
+  for block in &body.blocks {
+    for stmt in &block.stmts {
+      let StmtKind::Assign(local, rvalue) = &stmt.kind;
+
+      if let Rvalue::Use(Operand::Copy(source)) = rvalue {
+        if local != source
+          && local.0 >= body.inputs
+          && assignments[local.0] == 1
+          && assignments[source.0] <= 1
+        {
+          copies[local.0] = Some(*source);
+        }
+      }
+    }
+  }
+
+  let resolve = |mut local: Local| {
+    // the copies of copies are followed, bounded by the number of locals
+    for _ in 0..copies.len() {
+      match copies[local.0] {
+        Some(source) => local = source,
+        None => break,
+      }
+    }
+
+    local
+  };
+
+  let mut changed = false;
+
+  body.visit_operands_mut(|operand| {
+    if let Operand::Copy(local) = operand {
+      let source = resolve(*local);
+
+      if source != *local {
+        *local = source;
+        changed = true;
+      }
+    }
+  });
+
+  changed
+}
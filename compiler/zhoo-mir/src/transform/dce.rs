@@ -0,0 +1,95 @@
+//! the dead code elimination, the pure assignments of locals which are never
+//! read are removed, then the unused locals
+
+use crate::mir::{Body, Local, Operand, StmtKind};
+
+pub(crate) fn eliminate(body: &mut Body) -> bool {
+  let mut changed = false;
+
+  loop {
+    let reads = reads(body);
+    let mut removed = false;
+
+    for block in &mut body.blocks {
+      block.stmts.retain(|stmt| {
+        let StmtKind::Assign(local, rvalue) = &stmt.kind;
+        let dead = !reads[local.0] && rvalue.is_pure();
+
+        removed |= dead;
+        !dead
+      });
+    }
+
+    if !removed {
+      break;
+    }
+
+    changed = true;
+  }
+
+  changed | compact_locals(body)
+}
+
+fn reads(body: &mut Body) -> Vec<bool> {
+  let mut reads = vec![false; body.locals.len()];
+
+  body.visit_operands_mut(|operand| {
+    if let Operand::Copy(local) = operand {
+      reads[local.0] = true;
+    }
+  });
+
+  reads
+}
+
+/// removes the locals which are neither read nor assigned, the inputs are
+/// always kept
+fn compact_locals(body: &mut Body) -> bool {
+  let mut used = reads(body);
+
+  for used in used.iter_mut().take(body.inputs) {
+    *used = true;
+  }
+
+  for block in &body.blocks {
+    for stmt in &block.stmts {
+      let StmtKind::Assign(local, _) = &stmt.kind;
+
+      used[local.0] = true;
+    }
+  }
+
+  if used.iter().all(|used| *used) {
+    return false;
+  }
+
+  let mut ids = vec![None; body.locals.len()];
+  let mut locals = vec![];
+
+  for (x, local) in std::mem::take(&mut body.locals).into_iter().enumerate() {
+    if used[x] {
+      ids[x] = Some(Local(locals.len()));
+      locals.push(local);
+    }
+  }
+
+  body.locals = locals;
+
+  let remap = |local: &mut Local| *local = ids[local.0].expect("a used local");
+
+  for block in &mut body.blocks {
+    for stmt in &mut block.stmts {
+      let StmtKind::Assign(local, _) = &mut stmt.kind;
+
+      remap(local);
+    }
+  }
+
+  body.visit_operands_mut(|operand| {
+    if let Operand::Copy(local) = operand {
+      remap(local);
+    }
+  });
+
+  true
+}
@@ -0,0 +1,155 @@
+//! the inlining of the small functions, the call is replaced by a copy of the
+//! blocks of the callee between the statements before and after the call
+//!
+//! the callees are copied as they are before any inlining, so the recursive
+//! functions are expanded at most once
+
+use crate::mir::{
+  BasicBlock, BlockId, Body, Local, Operand, Program, Rvalue, Stmt, StmtKind,
+  Terminator, TerminatorKind,
+};
+
+use fxhash::FxHashMap;
+
+/// the maximum size of an inlined body
+pub(crate) const INLINE_THRESHOLD: usize = 16;
+
+pub(crate) fn inline(program: &mut Program) {
+  let callees = program
+    .bodies
+    .iter()
+    .filter(|body| body.size() <= INLINE_THRESHOLD && !calls(body, &body.name))
+    .map(|body| (body.name.to_string(), body.to_owned()))
+    .collect::<FxHashMap<_, _>>();
+
+  for body in &mut program.bodies {
+    inline_body(body, &callees);
+  }
+}
+
+fn calls(body: &Body, name: &str) -> bool {
+  body
+    .blocks
+    .iter()
+    .flat_map(|block| &block.stmts)
+    .any(|stmt| {
+      matches!(
+        &stmt.kind,
+        StmtKind::Assign(_, Rvalue::Call(callee, _)) if callee == name
+      )
+    })
+}
+
+fn inline_body(body: &mut Body, callees: &FxHashMap<String, Body>) {
+  // only the blocks of the caller are visited, not the inlined blocks
+  let mut worklist = body.block_ids().collect::<Vec<_>>();
+
+  worklist.reverse();
+
+  while let Some(block) = worklist.pop() {
+    let call =
+      body
+        .block(block)
+        .stmts
+        .iter()
+        .position(|stmt| match &stmt.kind {
+          StmtKind::Assign(_, Rvalue::Call(callee, _)) => {
+            *callee != body.name && callees.contains_key(callee)
+          }
+          _ => false,
+        });
+
+    let Some(call) = call else {
+      continue;
+    };
+
+    let continuation = split_block(body, block, call);
+    let stmt = body.block_mut(block).stmts.pop().expect("a call");
+
+    let StmtKind::Assign(dest, Rvalue::Call(callee, inputs)) = stmt.kind else {
+      unreachable!()
+    };
+
+    let callee = &callees[&callee];
+    let locals = body.locals.len();
+    let blocks = body.blocks.len();
+
+    body.locals.extend(callee.locals.iter().cloned());
+
+    for (x, input) in inputs.into_iter().enumerate() {
+      body.block_mut(block).stmts.push(Stmt {
+        kind: StmtKind::Assign(Local(locals + x), Rvalue::Use(input)),
+        span: stmt.span,
+      });
+    }
+
+    body.block_mut(block).terminator = Terminator {
+      kind: TerminatorKind::Goto(BlockId(blocks)),
+      span: stmt.span,
+    };
+
+    for inlined in &callee.blocks {
+      let mut inlined = inlined.to_owned();
+
+      remap(&mut inlined, locals, blocks);
+
+      if let TerminatorKind::Return(value) = &inlined.terminator.kind {
+        inlined.stmts.push(Stmt {
+          kind: StmtKind::Assign(dest, Rvalue::Use(value.to_owned())),
+          span: inlined.terminator.span,
+        });
+
+        inlined.terminator.kind = TerminatorKind::Goto(continuation);
+      }
+
+      body.blocks.push(inlined);
+    }
+
+    worklist.push(continuation);
+  }
+}
+
+/// moves the statements after the call and the terminator into a new block,
+/// the block ends with the call
+fn split_block(body: &mut Body, block: BlockId, call: usize) -> BlockId {
+  let continuation = BlockId(body.blocks.len());
+  let split = body.block_mut(block);
+  let stmts = split.stmts.split_off(call + 1);
+  let span = split.terminator.span;
+
+  let terminator = std::mem::replace(
+    &mut split.terminator,
+    Terminator {
+      kind: TerminatorKind::Goto(continuation),
+      span,
+    },
+  );
+
+  body.blocks.push(BasicBlock { stmts, terminator });
+
+  continuation
+}
+
+/// shifts the locals and the blocks of an inlined block
+fn remap(block: &mut BasicBlock, locals: usize, blocks: usize) {
+  let shift = |operand: &mut Operand| {
+    if let Operand::Copy(local) = operand {
+      local.0 += locals;
+    }
+  };
+
+  for stmt in &mut block.stmts {
+    let StmtKind::Assign(local, rvalue) = &mut stmt.kind;
+
+    local.0 += locals;
+    rvalue.operands_mut().into_iter().for_each(shift);
+  }
+
+  if let Some(operand) = block.terminator.kind.operand_mut() {
+    shift(operand);
+  }
+
+  for target in block.terminator.kind.targets_mut() {
+    target.0 += blocks;
+  }
+}
@@ -0,0 +1,164 @@
+//! the simplification of the control flow graph: the branches on a constant
+//! become gotos, the empty blocks are skipped, a block is merged into its
+//! single predecessor and the unreachable blocks are removed
+
+use crate::mir::{
+  BasicBlock, BlockId, Body, Const, Operand, Terminator, TerminatorKind,
+};
+
+pub(crate) fn simplify(body: &mut Body) -> bool {
+  let mut changed = fold_branches(body);
+
+  changed |= skip_empty_blocks(body);
+  changed |= merge_blocks(body);
+  changed |= remove_unreachable_blocks(body);
+  changed
+}
+
+fn fold_branches(body: &mut Body) -> bool {
+  let mut changed = false;
+
+  for block in &mut body.blocks {
+    let target = match &block.terminator.kind {
+      TerminatorKind::Branch(
+        Operand::Const(Const::Bool(boolean)),
+        consequence,
+        alternative,
+      ) => match boolean {
+        true => *consequence,
+        false => *alternative,
+      },
+      TerminatorKind::Branch(_, consequence, alternative)
+        if consequence == alternative =>
+      {
+        *consequence
+      }
+      _ => continue,
+    };
+
+    block.terminator.kind = TerminatorKind::Goto(target);
+    changed = true;
+  }
+
+  changed
+}
+
+/// a block without statements which goes to another block is skipped by its
+/// predecessors
+fn skip_empty_blocks(body: &mut Body) -> bool {
+  let forward = |body: &Body, mut block: BlockId| {
+    // a loop of empty blocks has no end, the hops are bounded
+    for _ in 0..body.blocks.len() {
+      match &body.block(block).terminator.kind {
+        TerminatorKind::Goto(target)
+          if body.block(block).stmts.is_empty() && *target != block =>
+        {
+          block = *target
+        }
+        _ => break,
+      }
+    }
+
+    block
+  };
+
+  let mut changed = false;
+
+  for block in body.block_ids() {
+    let mut terminator = body.block(block).terminator.kind.to_owned();
+
+    for target in terminator.targets_mut() {
+      let forwarded = forward(body, *target);
+
+      if forwarded != *target {
+        *target = forwarded;
+        changed = true;
+      }
+    }
+
+    body.block_mut(block).terminator.kind = terminator;
+  }
+
+  changed
+}
+
+/// a block which is the single successor of its single predecessor is
+/// appended to it
+fn merge_blocks(body: &mut Body) -> bool {
+  let mut changed = false;
+
+  for block in body.block_ids() {
+    while let TerminatorKind::Goto(target) =
+      body.block(block).terminator.kind
+    {
+      let predecessors = body.predecessors();
+
+      if target == block
+        || target == BlockId::ENTRY
+        || predecessors[target.0].len() != 1
+      {
+        break;
+      }
+
+      // the merged block is left unreachable, then removed
+      let span = body.block(target).terminator.span;
+
+      let merged = std::mem::replace(
+        body.block_mut(target),
+        BasicBlock {
+          stmts: vec![],
+          terminator: Terminator {
+            kind: TerminatorKind::Unreachable,
+            span,
+          },
+        },
+      );
+
+      let block = body.block_mut(block);
+
+      block.stmts.extend(merged.stmts);
+      block.terminator = merged.terminator;
+      changed = true;
+    }
+  }
+
+  changed
+}
+
+/// removes the blocks which are not reached from the entry, the remaining
+/// blocks keep their order
+pub(crate) fn remove_unreachable_blocks(body: &mut Body) -> bool {
+  let mut reached = vec![false; body.blocks.len()];
+  let mut stack = vec![BlockId::ENTRY];
+
+  while let Some(block) = stack.pop() {
+    if !reached[block.0] {
+      reached[block.0] = true;
+      stack.extend(body.block(block).successors());
+    }
+  }
+
+  if reached.iter().all(|reached| *reached) {
+    return false;
+  }
+
+  let mut ids = vec![None; body.blocks.len()];
+  let mut blocks = vec![];
+
+  for (x, block) in std::mem::take(&mut body.blocks).into_iter().enumerate() {
+    if reached[x] {
+      ids[x] = Some(BlockId(blocks.len()));
+      blocks.push(block);
+    }
+  }
+
+  for block in &mut blocks {
+    for target in block.terminator.kind.targets_mut() {
+      *target = ids[target.0].expect("a reached block");
+    }
+  }
+
+  body.blocks = blocks;
+
+  true
+}
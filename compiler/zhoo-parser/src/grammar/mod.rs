@@ -8,4 +8,4 @@
 
 lalrpop_mod!(grammar, "/grammar/grammar.rs");
 
-pub use grammar::ProgramParser;
+pub use grammar::{ProgramParser, SegmentParser};
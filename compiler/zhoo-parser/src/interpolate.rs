@@ -0,0 +1,153 @@
+//! this module splits a str literal into its texts and its interpolations:
+//! `"x = {x}"`. a `{{` writes a `{` and a `}}` writes a `}`
+
+use crate::grammar::SegmentParser;
+use crate::parser::syntax_kind;
+use crate::unescape::unescape;
+
+use zhoo_ast::ast::{Expr, ExprKind, Lit, LitKind};
+use zhoo_ast::ptr::{fsp, Fsp};
+use zhoo_errors::SyntaxKind;
+use zhoo_span::span::Span;
+
+use lalrpop_util::ParseError;
+
+/// the expression of a str literal, `lo` and `hi` are the positions of its
+/// quotes in the source code. a literal without interpolation is a `str`
+pub(crate) fn interpolate(
+  literal: &str,
+  lo: usize,
+  hi: usize,
+) -> Result<Fsp<Expr>, SyntaxKind> {
+  let content = &literal[1..(literal.len() - 1)];
+  let offset = lo + 1;
+  let bytes = content.as_bytes();
+
+  let mut segments = Vec::new();
+  let mut text = String::new();
+  let mut text_lo = 0;
+  let mut chunk_lo = 0;
+  let mut x = 0;
+
+  while x < bytes.len() {
+    match bytes[x] {
+      // an escape is skipped, the braces of `\u{...}` are part of it
+      b'\\' => {
+        x += 2;
+
+        if bytes.get(x - 1) == Some(&b'u') && bytes.get(x) == Some(&b'{') {
+          while x < bytes.len() && bytes[x] != b'}' {
+            x += 1;
+          }
+
+          x += 1;
+        }
+      }
+      brace @ (b'{' | b'}') if bytes.get(x + 1) == Some(&brace) => {
+        text += &unescape(&content[chunk_lo..x], offset + chunk_lo)?;
+        text.push(brace as char);
+        x += 2;
+        chunk_lo = x;
+      }
+      b'}' => {
+        return Err(SyntaxKind::UnmatchedBrace(Span::new(
+          offset + x,
+          offset + x + 1,
+        )))
+      }
+      b'{' => {
+        text += &unescape(&content[chunk_lo..x], offset + chunk_lo)?;
+
+        if !text.is_empty() {
+          segments.push(text_segment(&text, offset + text_lo, offset + x));
+          text.clear();
+        }
+
+        let end = closing_brace(bytes, x).ok_or_else(|| {
+          SyntaxKind::UnclosedInterpolation(Span::new(
+            offset + x,
+            offset + x + 1,
+          ))
+        })?;
+
+        segments.push(parse_segment(content, offset, x + 1, end)?);
+        x = end + 1;
+        chunk_lo = x;
+        text_lo = x;
+      }
+      _ => x += 1,
+    }
+  }
+
+  text += &unescape(&content[chunk_lo..], offset + chunk_lo)?;
+
+  if segments.is_empty() {
+    return Ok(text_segment(&text, lo, hi));
+  }
+
+  if !text.is_empty() {
+    segments.push(text_segment(&text, offset + text_lo, hi - 1));
+  }
+
+  Ok(fsp(Expr::new(
+    ExprKind::Interpolation(segments),
+    Span::new(lo, hi),
+  )))
+}
+
+/// the `}` which closes the `{` at `open`, the braces of the expression are
+/// counted
+fn closing_brace(bytes: &[u8], open: usize) -> Option<usize> {
+  let mut depth = 0;
+
+  for (x, byte) in bytes.iter().enumerate().skip(open + 1) {
+    match byte {
+      b'{' => depth += 1,
+      b'}' if depth == 0 => return Some(x),
+      b'}' => depth -= 1,
+      _ => {}
+    }
+  }
+
+  None
+}
+
+fn text_segment(text: &str, lo: usize, hi: usize) -> Fsp<Expr> {
+  let span = Span::new(lo, hi);
+  let lit = fsp(Lit::new(LitKind::Str(text.to_string()), span));
+
+  fsp(Expr::new(ExprKind::Lit(lit), span))
+}
+
+/// parses the expression of an interpolation, it's parsed after blanks so its
+/// spans are the ones of the source code
+fn parse_segment(
+  content: &str,
+  offset: usize,
+  lo: usize,
+  hi: usize,
+) -> Result<Fsp<Expr>, SyntaxKind> {
+  let source = &content[lo..hi];
+
+  if source.trim().is_empty() {
+    return Err(SyntaxKind::EmptyInterpolation(Span::new(
+      offset + lo - 1,
+      offset + hi + 1,
+    )));
+  }
+
+  let source = format!("{}{source}", " ".repeat(offset + lo));
+
+  SegmentParser::new()
+    .parse(&source)
+    .map_err(|error| match error {
+      // the expression is cut by the closing brace
+      ParseError::UnrecognizedEOF { expected, .. } => {
+        SyntaxKind::UnrecognizedToken(
+          Span::new(offset + hi, offset + hi + 1),
+          expected.join(", "),
+        )
+      }
+      error => syntax_kind(error),
+    })
+}
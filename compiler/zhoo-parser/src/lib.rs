@@ -1,6 +1,12 @@
 #[macro_use]
 extern crate lalrpop_util;
 
+#[cfg(test)]
+mod tests;
+
 mod grammar;
+mod interpolate;
+mod number;
+mod unescape;
 
 pub mod parser;
@@ -0,0 +1,21 @@
+//! this module parses the integer literals
+
+use zhoo_errors::SyntaxKind;
+use zhoo_span::span::Span;
+
+/// the value of an integer literal without its suffix, its prefix gives its
+/// radix. it holds up to 64 bits, the analyzer checks that it fits in its type
+pub(crate) fn parse_int(lit: &str, span: Span) -> Result<i128, SyntaxKind> {
+  let digits = lit.replace('_', "");
+
+  let (digits, radix) = match digits.get(..2) {
+    Some("0b") => (&digits[2..], 2),
+    Some("0o") => (&digits[2..], 8),
+    Some("0x") => (&digits[2..], 16),
+    _ => (&digits[..], 10),
+  };
+
+  u64::from_str_radix(digits, radix)
+    .map(i128::from)
+    .map_err(|_| SyntaxKind::InvalidIntLit(span, lit.to_string()))
+}
@@ -1,18 +1,66 @@
 use crate::grammar::ProgramParser;
 
 use zhoo_ast::ast::Program;
-use zhoo_errors::Reporter;
+use zhoo_errors::{Report, Reporter, SyntaxKind};
+use zhoo_span::span::Span;
+
+use lalrpop_util::lexer::Token;
+use lalrpop_util::ParseError;
 
 use std::path::PathBuf;
 
 pub fn parse<P: Into<PathBuf>>(pathname: P) -> Program {
   let mut reporter = Reporter::default();
   let source_id = reporter.add_source(pathname.into()).unwrap();
+
+  parse_program(reporter, source_id)
+}
+
+/// parses a source code which is not read from a file, `name` names the source
+/// in the reports
+pub fn parse_source(name: &str, source_code: &str) -> Program {
+  let mut reporter = Reporter::default();
+  let source_id = reporter.add_source_code(name, source_code);
+
+  parse_program(reporter, source_id)
+}
+
+fn parse_program(reporter: Reporter, source_id: u32) -> Program {
   let source_code = reporter.code(source_id);
   let parser = ProgramParser::new();
 
   match parser.parse(source_code) {
     Ok(node) => Program::new(node.0, node.1, reporter),
-    Err(error) => panic!("{error}"),
+    Err(error) => {
+      let kind = syntax_kind(error);
+
+      reporter.raise(Report::Syntax(kind))
+    }
+  }
+}
+
+/// the syntax report of a parse error, the grammar actions already fail with
+/// one
+pub(crate) fn syntax_kind(
+  error: ParseError<usize, Token, SyntaxKind>,
+) -> SyntaxKind {
+  match error {
+    ParseError::InvalidToken { location } => {
+      SyntaxKind::InvalidToken(Span::new(location, location + 1))
+    }
+    ParseError::UnrecognizedEOF { location, expected } => {
+      SyntaxKind::UnrecognizedEOF(
+        Span::new(location, location),
+        expected.join(", "),
+      )
+    }
+    ParseError::UnrecognizedToken {
+      token: (lo, _, hi),
+      expected,
+    } => SyntaxKind::UnrecognizedToken(Span::new(lo, hi), expected.join(", ")),
+    ParseError::ExtraToken {
+      token: (lo, token, hi),
+    } => SyntaxKind::ExtraToken(Span::new(lo, hi), token.to_string()),
+    ParseError::User { error } => error,
   }
 }
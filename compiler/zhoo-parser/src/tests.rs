@@ -0,0 +1,136 @@
+use super::interpolate::interpolate;
+use super::number::parse_int;
+use super::parser::parse_source;
+use super::unescape::unescape;
+
+use zhoo_ast::ast::{Expr, ExprKind, LitKind, StmtKind};
+use zhoo_ast::ptr::Fsp;
+use zhoo_errors::SyntaxKind;
+use zhoo_span::span::Span;
+
+/// the value of the `val` of a source code
+fn val_value(name: &str, source: &str) -> Fsp<Expr> {
+  let program = parse_source(name, source);
+
+  match &program.stmts[0].kind {
+    StmtKind::Val(decl) => decl.value.to_owned(),
+    _ => panic!("the sample `{name}` should declare a `val`"),
+  }
+}
+
+fn str_lit(expr: &Expr) -> Option<&str> {
+  match &expr.kind {
+    ExprKind::Lit(lit) => match &lit.kind {
+      LitKind::Str(string) => Some(string),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+#[test]
+fn test_unescape() {
+  let decoded = unescape(r#"a\tb\n\\\"\x41\u{1F600}"#, 0).unwrap();
+
+  assert_eq!(decoded, "a\tb\n\\\"A😀");
+}
+
+#[test]
+fn test_unescape_invalid_escape() {
+  let error = unescape(r"ab\q", 10).unwrap_err();
+
+  assert!(matches!(
+    error,
+    SyntaxKind::InvalidEscape(span, escape) if span.lo == 12 && escape == r"\q"
+  ));
+}
+
+#[test]
+fn test_unescape_invalid_unicode_escape() {
+  assert!(matches!(
+    unescape(r"\u{110000}", 0),
+    Err(SyntaxKind::InvalidUnicodeEscape(..))
+  ));
+
+  assert!(matches!(
+    unescape(r"\u{}", 0),
+    Err(SyntaxKind::InvalidUnicodeEscape(..))
+  ));
+}
+
+#[test]
+fn test_parse_int() {
+  let span = Span::new(0, 0);
+
+  assert_eq!(parse_int("1_000", span).unwrap(), 1000);
+  assert_eq!(parse_int("0b1010", span).unwrap(), 10);
+  assert_eq!(parse_int("0o17", span).unwrap(), 15);
+  assert_eq!(parse_int("0xff", span).unwrap(), 255);
+
+  assert_eq!(
+    parse_int("18446744073709551615", span).unwrap(),
+    u64::MAX as i128
+  );
+}
+
+#[test]
+fn test_parse_int_invalid() {
+  let span = Span::new(4, 27);
+
+  assert!(matches!(
+    parse_int("0x1_0000_0000_0000_0000", span),
+    Err(SyntaxKind::InvalidIntLit(span, lit))
+      if span.lo == 4 && lit == "0x1_0000_0000_0000_0000"
+  ));
+
+  assert!(matches!(
+    parse_int("18446744073709551616", span),
+    Err(SyntaxKind::InvalidIntLit(..))
+  ));
+
+  assert!(matches!(
+    parse_int("0x_", span),
+    Err(SyntaxKind::InvalidIntLit(..))
+  ));
+}
+
+#[test]
+fn test_interpolate_braces() {
+  let value = val_value("braces", r#"val X: str = "{{x}} }} {{";"#);
+
+  assert_eq!(str_lit(&value), Some("{x} } {"));
+}
+
+#[test]
+fn test_interpolate_segments() {
+  let value = val_value("segments", r#"val X: str = "a {{{1}}}";"#);
+
+  let ExprKind::Interpolation(segments) = &value.kind else {
+    panic!("the str should be interpolated");
+  };
+
+  assert_eq!(segments.len(), 3);
+  assert_eq!(str_lit(&segments[0]), Some("a {"));
+  assert!(matches!(segments[1].kind, ExprKind::Lit(_)));
+  assert_eq!(str_lit(&segments[2]), Some("}"));
+}
+
+#[test]
+fn test_interpolate_unmatched_brace() {
+  assert!(matches!(
+    interpolate(r#""a }""#, 10, 15),
+    Err(SyntaxKind::UnmatchedBrace(span)) if span.lo == 13
+  ));
+
+  assert!(matches!(
+    interpolate(r#""{x""#, 10, 14),
+    Err(SyntaxKind::UnclosedInterpolation(span)) if span.lo == 11
+  ));
+}
+
+#[test]
+fn test_raw_str_braces() {
+  let value = val_value("raw", r#"val X: str = $"{x} } {"$;"#);
+
+  assert_eq!(str_lit(&value), Some("{x} } {"));
+}
@@ -0,0 +1,124 @@
+//! this module decodes the escapes of the str and char literals
+
+use zhoo_errors::SyntaxKind;
+use zhoo_span::span::Span;
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// decodes the escapes of the content of a literal, `offset` is the position
+/// of the content in the source code and gives the spans of the escapes
+pub(crate) fn unescape(
+  content: &str,
+  offset: usize,
+) -> Result<String, SyntaxKind> {
+  let mut decoded = String::with_capacity(content.len());
+  let mut chars = content.char_indices().peekable();
+
+  while let Some((lo, char)) = chars.next() {
+    if char != '\\' {
+      decoded.push(char);
+      continue;
+    }
+
+    let Some((_, escape)) = chars.next() else {
+      return Err(SyntaxKind::InvalidEscape(
+        Span::new(offset + lo, offset + lo + 1),
+        String::from("\\"),
+      ));
+    };
+
+    let decoded_char = match escape {
+      'n' => Some('\n'),
+      'r' => Some('\r'),
+      't' => Some('\t'),
+      '0' => Some('\0'),
+      '\\' => Some('\\'),
+      '\'' => Some('\''),
+      '"' => Some('"'),
+      'x' => {
+        let digits = take_while(&mut chars, 2, |char| char.is_ascii_hexdigit());
+
+        u8::from_str_radix(&digits, 16)
+          .ok()
+          .filter(|byte| digits.len() == 2 && byte.is_ascii())
+          .map(char::from)
+      }
+      'u' => {
+        let hi = escape_end(&mut chars, content);
+        let escape = &content[lo..hi];
+        let span = Span::new(offset + lo, offset + hi);
+
+        let digits = escape
+          .strip_prefix("\\u{")
+          .and_then(|escape| escape.strip_suffix('}'))
+          .filter(|digits| (1..=6).contains(&digits.len()))
+          .ok_or_else(|| {
+            SyntaxKind::InvalidUnicodeEscape(span, escape.to_string())
+          })?;
+
+        let scalar = u32::from_str_radix(digits, 16)
+          .ok()
+          .and_then(char::from_u32)
+          .ok_or_else(|| {
+            SyntaxKind::InvalidUnicodeEscape(span, escape.to_string())
+          })?;
+
+        decoded.push(scalar);
+        continue;
+      }
+      _ => None,
+    };
+
+    let hi = chars.peek().map(|(hi, _)| *hi).unwrap_or(content.len());
+
+    match decoded_char {
+      Some(decoded_char) => decoded.push(decoded_char),
+      None => {
+        return Err(SyntaxKind::InvalidEscape(
+          Span::new(offset + lo, offset + hi),
+          content[lo..hi].to_string(),
+        ))
+      }
+    }
+  }
+
+  Ok(decoded)
+}
+
+/// takes at most `max` chars which satisfy `predicate`
+fn take_while(
+  chars: &mut Peekable<CharIndices>,
+  max: usize,
+  predicate: impl Fn(char) -> bool,
+) -> String {
+  let mut taken = String::new();
+
+  while let Some((_, char)) = chars.next_if(|(_, char)| predicate(*char)) {
+    taken.push(char);
+
+    if taken.len() == max {
+      break;
+    }
+  }
+
+  taken
+}
+
+/// the end of a `\u{...}` escape, its closing brace or the first char which
+/// can not be part of it
+fn escape_end(chars: &mut Peekable<CharIndices>, content: &str) -> usize {
+  if chars.next_if(|(_, char)| *char == '{').is_none() {
+    return chars.peek().map(|(hi, _)| *hi).unwrap_or(content.len());
+  }
+
+  while let Some((hi, char)) =
+    chars.next_if(|(_, char)| char.is_ascii_alphanumeric() || *char == '}')
+  {
+    if char == '}' {
+      return hi + 1;
+    }
+  }
+
+  chars.peek().map(|(hi, _)| *hi).unwrap_or(content.len())
+}
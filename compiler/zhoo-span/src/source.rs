@@ -34,6 +34,16 @@ impl SourceMap {
     Ok(source_id)
   }
 
+  /// adds a source from its code, `path` only names it in the reports
+  pub fn add_code(&mut self, path: PathBuf, code: &str) -> u32 {
+    let source_id = self.sources.len() as u32;
+    let offset = self.code.len();
+
+    self.code.push_str(code);
+    self.sources.push(Box::new(Source::new(offset, path)));
+    source_id
+  }
+
   pub fn code(&self, source_id: u32) -> &str {
     let source_id = source_id as usize;
 
@@ -29,6 +29,19 @@ fn test_add_source_file() {
   assert_eq!(source_id, 0);
 }
 
+#[test]
+fn test_add_code() {
+  let mut source_map = SourceMap::default();
+
+  source_map.add_code(Path::new("a").to_path_buf(), "fun a() {}");
+
+  let source_id = source_map.add_code(Path::new("b").to_path_buf(), "fun b");
+
+  assert_eq!(source_id, 1);
+  assert_eq!(source_map.code(source_id), "fun b");
+  assert_eq!(source_map.path(Span::new(10, 13)), Path::new("b"));
+}
+
 #[test]
 fn test_get_source_code() {
   let mut source_map = SourceMap::default();
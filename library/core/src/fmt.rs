@@ -0,0 +1,35 @@
+//! the formatting of the values interpolated in a `str`: `"x = {x}"`, a
+//! value is written like `print` writes it
+
+use super::string::{Char, Str};
+use super::util::from_string;
+
+#[no_mangle]
+extern "C" fn fmt_int(int: isize) -> *const Str {
+  from_string(int.to_string())
+}
+
+#[no_mangle]
+extern "C" fn fmt_uint(int: u64) -> *const Str {
+  from_string(int.to_string())
+}
+
+#[no_mangle]
+extern "C" fn fmt_real(real: f64) -> *const Str {
+  from_string(real.to_string())
+}
+
+#[no_mangle]
+extern "C" fn fmt_f32(real: f32) -> *const Str {
+  from_string(real.to_string())
+}
+
+#[no_mangle]
+extern "C" fn fmt_bool(boolean: bool) -> *const Str {
+  from_string(boolean.to_string())
+}
+
+#[no_mangle]
+extern "C" fn fmt_char(scalar: Char) -> *const Str {
+  from_string(scalar.to_char().to_string())
+}
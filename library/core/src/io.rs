@@ -1,35 +1,56 @@
+use super::string::{Char, Str};
 use super::util;
 
 #[no_mangle]
-fn print(ptr: *const i8) {
-  let string = util::to_str(ptr);
+extern "C" fn print(string: *const Str) {
+  let string = util::to_str(string);
 
   print!("{string}");
 }
 
 #[no_mangle]
-fn println(ptr: *const i8) {
-  let string = util::to_str(ptr);
+extern "C" fn println(string: *const Str) {
+  let string = util::to_str(string);
 
   println!("{string}");
 }
 
 #[no_mangle]
-fn printi(num: isize) {
+extern "C" fn printi(num: isize) {
   print!("{num}");
 }
 
 #[no_mangle]
-fn printiln(num: isize) {
+extern "C" fn printiln(num: isize) {
   println!("{num}");
 }
 
 #[no_mangle]
-fn printr(num: f64) {
+extern "C" fn printr(num: f64) {
   print!("{num}");
 }
 
 #[no_mangle]
-fn printrln(num: f64) {
+extern "C" fn printrln(num: f64) {
   println!("{num}");
 }
+
+#[no_mangle]
+extern "C" fn printb(boolean: bool) {
+  print!("{boolean}");
+}
+
+#[no_mangle]
+extern "C" fn printbln(boolean: bool) {
+  println!("{boolean}");
+}
+
+#[no_mangle]
+extern "C" fn printc(scalar: Char) {
+  print!("{}", scalar.to_char());
+}
+
+#[no_mangle]
+extern "C" fn printcln(scalar: Char) {
+  println!("{}", scalar.to_char());
+}
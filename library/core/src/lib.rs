@@ -1,4 +1,6 @@
 mod util;
 
+pub mod fmt;
 pub mod io;
+pub mod string;
 pub mod sys;
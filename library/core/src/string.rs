@@ -0,0 +1,77 @@
+//! the strings of the compiled programs, a `str` is the address of a `Str`
+//! which holds the utf-8 bytes of the string and their length. the indices
+//! count the chars, not the bytes
+
+use super::sys::fail;
+use super::util::{from_string, to_str};
+
+/// the bytes of a string are not terminated by a nul, the strings created at
+/// run time are never freed
+#[repr(C)]
+pub struct Str {
+  pub ptr: *const u8,
+  pub len: usize,
+}
+
+/// a `char` is a unicode scalar value
+#[repr(transparent)]
+pub struct Char(u32);
+
+impl Char {
+  pub fn to_char(&self) -> char {
+    char::from_u32(self.0).unwrap_or(char::REPLACEMENT_CHARACTER)
+  }
+}
+
+#[no_mangle]
+extern "C" fn str_len(string: *const Str) -> isize {
+  to_str(string).chars().count() as isize
+}
+
+#[no_mangle]
+extern "C" fn str_concat(lhs: *const Str, rhs: *const Str) -> *const Str {
+  from_string(format!("{}{}", to_str(lhs), to_str(rhs)))
+}
+
+/// compares two strings by their bytes, it gives `-1`, `0` or `1`
+#[no_mangle]
+extern "C" fn str_cmp(lhs: *const Str, rhs: *const Str) -> isize {
+  to_str(lhs).cmp(to_str(rhs)) as isize
+}
+
+/// the char at an index, as a string of one char
+#[no_mangle]
+extern "C" fn str_at(string: *const Str, index: isize) -> *const Str {
+  let string = to_str(string);
+
+  match usize::try_from(index)
+    .ok()
+    .and_then(|index| string.chars().nth(index))
+  {
+    Some(char) => from_string(char.to_string()),
+    None => fail(&format!(
+      "the index `{index}` is out of bounds of a `str` of {} chars",
+      string.chars().count(),
+    )),
+  }
+}
+
+/// the chars from `start` to `end`, `end` is excluded
+#[no_mangle]
+extern "C" fn str_slice(
+  string: *const Str,
+  start: isize,
+  end: isize,
+) -> *const Str {
+  let string = to_str(string);
+  let len = string.chars().count();
+
+  match (usize::try_from(start), usize::try_from(end)) {
+    (Ok(start), Ok(end)) if start <= end && end <= len => {
+      from_string(string.chars().skip(start).take(end - start).collect())
+    }
+    _ => fail(&format!(
+      "the range `{start}..{end}` is out of bounds of a `str` of {len} chars"
+    )),
+  }
+}
@@ -1,14 +1,36 @@
-use super::util::to_str;
+use super::string::Str;
+use super::util::{from_string, to_str};
 
 use std::fs::File;
 
+extern "C" {
+  fn _exit(code: i32) -> !;
+}
+
+// this `exit` replaces the one of the libc, `std::process::exit` would call it
+// again, the output is flushed before leaving the process
 #[no_mangle]
-fn exit(code: isize) {
-  std::process::exit(code as i32)
+extern "C" fn exit(code: isize) {
+  use std::io::Write;
+
+  let _ = std::io::stdout().flush();
+
+  unsafe { _exit(code as i32) }
+}
+
+/// stops the program on an error of the core library
+pub(crate) fn fail(message: &str) -> ! {
+  use std::io::Write;
+
+  let _ = std::io::stdout().flush();
+
+  eprintln!("error: {message}");
+
+  unsafe { _exit(101) }
 }
 
 #[no_mangle]
-fn create(path: *const i8, source: *const i8) {
+extern "C" fn create(path: *const Str, source: *const Str) {
   use std::io::Write;
 
   let path = to_str(path);
@@ -26,7 +48,7 @@ fn create(path: *const i8, source: *const i8) {
 }
 
 #[no_mangle]
-fn open(path: *const i8) -> String {
+extern "C" fn open(path: *const Str) -> *const Str {
   use std::io::Read;
 
   let path = to_str(path);
@@ -39,7 +61,7 @@ fn open(path: *const i8) -> String {
   let mut contents = String::new();
 
   match file.read_to_string(&mut contents) {
-    Ok(_) => contents,
+    Ok(_) => from_string(contents),
     Err(error) => panic!("{error}"),
   }
 }
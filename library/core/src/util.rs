@@ -1,13 +1,25 @@
-use std::ffi;
+use super::string::Str;
 
 #[inline]
-pub fn to_str<'a>(string: *const i8) -> &'a str {
-  let cstr = unsafe { ffi::CStr::from_ptr(string) };
+pub fn to_str<'a>(string: *const Str) -> &'a str {
+  let bytes = unsafe {
+    let string = &*string;
 
-  // String::from_utf8_lossy(cstr.to_bytes_with_nul()).to_string()
+    std::slice::from_raw_parts(string.ptr, string.len)
+  };
 
-  match cstr.to_str() {
+  match std::str::from_utf8(bytes) {
     Ok(str_ref) => str_ref,
     Err(error) => panic!("{error}"),
   }
 }
+
+/// leaks a string created at run time, the program keeps its address
+pub fn from_string(string: String) -> *const Str {
+  let bytes = string.into_bytes().leak();
+
+  Box::into_raw(Box::new(Str {
+    ptr: bytes.as_ptr(),
+    len: bytes.len(),
+  }))
+}